@@ -0,0 +1,254 @@
+# Cymraeg (Welsh), translated from en.ftl
+# Messages are written as `id = text`, indented lines carry on the message before them on a new line and { $name } is replaced with an argument
+
+## Diagnostics from assembling and running programs
+
+token-number = rhif
+token-number-value = rhif `{ $number }`
+token-identifier = dynodydd
+token-identifier-value = dynodydd: `{ $identifier }`
+token-comment = sylw
+token-newline = llinell newydd
+list-or = { $list } neu { $last }
+
+lexer-invalid-character =
+    Gwall wrth ddadansoddi'r testun...
+    Nod annilys `{ $character }` yn ({ $line }:{ $column })
+
+parser-error =
+    Gwall wrth ddosrannu...
+    { $error }
+parser-expected-nothing = Ni ddisgwylid dim byd
+parser-expected = Disgwylid { $token }
+parser-expected-list = Disgwylid: { $tokens }
+parser-nothing = dim byd
+parser-received = { $expected }, cafwyd { $received }
+number-out-of-range = Mae'r rhif `{ $number }` y tu allan i'r amrediad, disgwylid rhif rhwng -999 a 999 yn gynhwysol
+address-out-of-range = Mae'r cyfeiriad `{ $address }` y tu allan i'r amrediad, disgwylid rhif rhwng 0 a 100 yn anghynhwysol
+
+generator-invalid-identifier =
+    Gwall wrth gynhyrchu cod...
+    Dynodydd annilys `{ $identifier }`
+generator-unsupported-instruction =
+    Gwall wrth gynhyrchu cod...
+    Nid yw `{ $instruction }` yn rhan o'r set gyfarwyddiadau { $instruction_set }, newidiwch i'r set gyfarwyddiadau { $supported } i'w ddefnyddio
+generator-program-too-large =
+    Gwall wrth gynhyrchu cod...
+    Mae'r rhaglen yn { $length } lleoliad o hyd ond dim ond 100 sy'n ffitio yn y cof
+generator-label-out-of-range =
+    Gwall wrth gynhyrchu cod...
+    Mae'r label `{ $identifier }` yn y cyfeiriad { $address }, y tu hwnt i ddiwedd y cof
+
+macro-error =
+    Gwall wrth ehangu macros...
+    { $error }
+macro-missing-name = Disgwylid enw ar ôl MACRO
+macro-invalid-parameter = Disgwylid dynodydd fel paramedr macro, cafwyd { $token }
+macro-unterminated = Nid yw'r macro `{ $name }` byth yn cael ei gau, a wnaethoch chi anghofio ENDM?
+macro-nested-definition = Mae'r macro `{ $name }` wedi'i ddiffinio y tu mewn i facro arall
+macro-unexpected-end = Cafwyd ENDM heb MACRO cyfatebol
+macro-argument-count = Mae'r macro `{ $name }` yn disgwyl { $expected } arg(iau), cafwyd { $received }
+macro-recursion-limit = Ehangwyd y macro `{ $name }` ormod o weithiau, ydy e'n ei ddefnyddio ei hun?
+
+warning-unreachable-code = Ni all y cod yn y cyfeiriad { $address } fyth gael ei redeg
+warning-uninitialised-data = Mae `{ $label }` yn cael ei ddarllen ond byth yn cael gwerth, a wnaethoch chi anghofio rhif ar ôl DAT neu STA?
+warning-branch-to-data = Yn neidio i `{ $label }`, sy'n ddata yn hytrach na chod
+warning-label-as-code-and-data = Defnyddir `{ $label }` fel label cod a label data
+warning-missing-halt = Mae'r cod yn rhedeg i mewn i ddata yn y cyfeiriad { $address }, a wnaethoch chi anghofio stopio?
+warning-program-too-large = Mae'r rhaglen yn { $length } lleoliad o hyd ond dim ond 100 sy'n ffitio yn y cof, ni ellir ei chydosod
+
+runtime-error =
+    Gwall wrth redeg...
+    { $error }
+runtime-expected-instruction = Rhedwyd i mewn i gof data wrth redeg cod, a wnaethoch chi anghofio stopio?
+runtime-expected-data =
+    Disgwylid operand yn pwyntio at leoliad data, cafwyd lleoliad cyfarwyddyd.
+    A wnaethoch chi ddefnyddio rhif yn lle dynodydd neu newid nifer y llinellau yn eich rhaglen?
+runtime-invalid-instruction = Nid yw'r cyfarwyddyd `{ $code }` yn rhan o'r set gyfarwyddiadau
+runtime-waiting-for-input = Yn aros am fewnbwn...
+runtime-export-failed = Methwyd allforio'r olrhain i { $path }: { $error }
+trace-missing-input = Gofynnodd y rhaglen am fwy o fewnbynnau nag a roddwyd
+trace-max-steps = Ni stopiodd y rhaglen o fewn { $max_steps } cam
+trace-unknown-format = Roedd disgwyl csv neu json, cafwyd { $format }
+
+stage-fetch-address = Nôl: PC → MAR
+stage-fetch-instruction = Nôl: RAM[MAR] → MDR → CIR, MAR
+stage-decode = Datgodio: CIR
+stage-execute = Gweithredu
+stage-increment = Cynyddu: PC + 1 → PC
+
+## The instruction set
+
+instruction-set-classic = Clasurol
+instruction-set-extended = Estynedig
+instruction-set-exam-board = Bwrdd Arholi
+instruction-set-unknown = Set gyfarwyddiadau anhysbys `{ $name }`, roedd disgwyl classic, extended, neu exam-board
+description-halt = Stopio rhedeg y rhaglen
+description-add = Adio'r gwerth mewn cyfeiriad at y cronadur
+description-sub = Tynnu'r gwerth mewn cyfeiriad o'r cronadur
+description-store = Storio'r cronadur mewn cyfeiriad
+description-load = Llwytho'r gwerth mewn cyfeiriad i'r cronadur
+description-load-indirect = Llwytho'r gwerth yn y cyfeiriad sydd wedi'i storio mewn cyfeiriad i'r cronadur
+description-branch = Neidio i gyfeiriad bob tro
+description-branch-zero = Neidio i gyfeiriad os yw'r cronadur yn sero
+description-branch-positive = Neidio i gyfeiriad os yw'r cronadur yn sero neu'n bositif
+description-input = Gofyn i'r defnyddiwr am rif a'i roi yn y cronadur
+description-output = Argraffu'r cronadur fel rhif
+description-output-character = Argraffu'r cronadur fel nod ASCII
+description-data = Nid cyfarwyddyd, mae'n rhoi rhif yn y cof pan fydd y rhaglen yn cael ei chydosod
+effect-halt = Does dim yn newid, mae'r rhaglen yn stopio
+effect-branch-zero = PC = X os ACC = 0
+effect-branch-positive = PC = X os ACC >= 0
+effect-input = ACC = mewnbwn
+effect-output = Allbynnu ACC
+effect-output-character = Allbynnu ACC fel nod
+effect-data = [X] = rhif (0 os nad oes un)
+
+## Files and the config
+
+file-read-failed = Methwyd darllen { $path }: { $error }
+file-write-failed = Methwyd ysgrifennu i { $path }: { $error }
+config-no-home-directory = Methwyd dod o hyd i'ch cyfeiriadur cartref i gadw'r ffurfweddiad ynddo, gosodwch { $variable } i ddewis ble caiff ei gadw
+config-io = Methwyd darllen neu ysgrifennu { $path }, { $error }
+config-parse = Mae camgymeriad yn y ffeil ffurfweddu, { $error }
+config-serialize = Nid oedd modd ysgrifennu'r ffurfweddiad fel TOML, { $error }
+config-unsupported-version = Fersiwn { $version } yw'r ffeil ffurfweddu (wedi'i chadw gan fersiwn mwy newydd o algor), dim ond hyd at fersiwn { $supported } mae'r fersiwn hon yn ei deall
+theme-invalid-colour = Disgwylid lliw fel "#1e1e2e", cafwyd "{ $colour }"
+theme-built-in-name = Mae "{ $name }" eisoes yn enw ar thema adeiledig
+theme-unsupported = Thema heb ei chefnogi
+config-system = Methwyd defnyddio ffurfweddiad y system yn { $path }, gofynnwch i bwy bynnag sy'n gofalu am y cyfrifiadur hwn i'w drwsio. { $error }
+
+## Used on more than one screen
+
+back = Yn ôl
+save = Cadw
+cancel = Canslo
+untitled = Dideitl
+warning = Rhybudd: { $warning }
+
+## Menu
+
+menu-config-error = Methwyd llwytho'ch ffurfweddiad felly mae'r gosodiadau diofyn yn cael eu defnyddio. { $error }
+menu-reset-config = Ailosod y Ffurfweddiad
+menu-dismiss = Diystyru
+menu-recovered = Cafwyd gwaith heb ei gadw o'r tro diwethaf: { $files }
+menu-restore = Adfer
+menu-discard = Taflu
+menu-lessons = Gwersi
+menu-sandbox = Blwch Tywod
+menu-recent-files = Ffeiliau Diweddar
+menu-reopen = Ailagor y Sesiwn Ddiwethaf
+
+## Lessons
+
+lesson-select-directory-error =
+    Gwall wrth agor y cyfeiriadur...
+    Methwyd darllen o'r cyfeiriadur gwersi, ydych chi'n siŵr bod y cyfeiriadur yn bodoli?
+lesson-select-untitled = Gwers Ddideitl
+lesson-select-start = Dechrau
+lesson-select-slides = { $count } sleid
+lesson-buffer = { $title } (gwers)
+lesson-answer = Ateb
+lesson-correct = Cywir!
+lesson-incorrect = Ddim yn hollol, rhowch gynnig arall arni
+lesson-trace-error =
+    Ni ellir rhedeg y rhaglen yn y tabl olrhain hwn:
+    { $error }
+lesson-trace-output = Allbwn
+lesson-trace-correct = Mae pob cell yn gywir!
+lesson-trace-wrong = Nid yw { $wrong } cell yn gywir, gwiriwch y celloedd sydd wedi'u hamlygu
+lesson-next = Nesaf
+lesson-completed = Gwers wedi'i Chwblhau!
+
+## Sandbox
+
+sandbox-save-changes = Cadw newidiadau i { $name }?
+sandbox-leave-unsaved = Mae gan rai rhaglenni newidiadau heb eu cadw, gadael beth bynnag?
+sandbox-dont-save = Peidio â Chadw
+sandbox-leave = Gadael
+
+## Panes
+
+pane-editor = Golygydd
+pane-state-viewer = Gwyliwr Cyflwr
+pane-terminal = Terfynell
+pane-reference = Cyfeirnod Cyfarwyddiadau
+pane-lesson = Gwers
+
+editor-pick-file = Dewis ffeil LMC...
+editor-save-file = Cadw ffeil LMC...
+editor-export-file = Allforio olrhain...
+editor-open = Agor
+editor-save-as = Cadw Fel
+editor-export-trace = Allforio Olrhain
+editor-format = Fformatio
+editor-assemble = Cydosod
+editor-run = Rhedeg
+editor-step = Cam
+editor-stop = Stopio
+editor-reset = Ailosod
+editor-input = Mewnbwn...
+
+reference-under-cursor = O dan y cyrchwr:
+reference-instruction-set = Set gyfarwyddiadau { $instruction_set }:
+
+memory-format-decimal = Degol
+memory-format-mnemonic = Cofair
+memory-format-signed = Ag Arwydd
+memory-format-labelled = Wedi'i Labelu
+state-was = Roedd yn { $value }
+state-last-stage = { $last }  (nesaf: { $next })
+state-next-stage = Nesaf: { $next }
+state-cpu = CPU:
+state-micro-steps = Dangos pob cam o'r cylchred nôl-datgodio-gweithredu
+state-ram = RAM:
+state-table = Tabl
+state-text-summary = Crynodeb Testun
+summary-program-counter = Rhifydd rhaglen { $value }, y cyfarwyddyd nesaf yw { $next }
+summary-accumulator = Cronadur { $value }
+summary-registers = Cofrestr cyfarwyddyd gyfredol { $cir }, cofrestr cyfeiriad cof { $mar }, cofrestr data cof { $mdr }
+summary-next-step = Cam nesaf: { $stage }
+summary-memory-empty = Mae pob lleoliad cof yn 0
+summary-memory-in-use = { $count } lleoliad cof yn cael eu defnyddio, mae'r gweddill yn 0:
+
+## Settings
+
+settings = Gosodiadau
+settings-pick-lessons-directory = Dewis cyfeiriadur gwersi...
+settings-appearance = Golwg
+settings-theme = Thema
+settings-language = Iaith
+settings-font-size = Maint y Ffont
+settings-preview = Rhagolwg
+settings-preview-success = Wedi'i gydosod yn llwyddiannus
+settings-preview-error = Gwall: label anhysbys
+settings-functionality = Swyddogaethau
+settings-lessons-directory = Cyfeiriadur Gwersi
+settings-browse = Pori
+settings-run-speed = Cyflymder Rhedeg
+run-speed-slow = Araf
+run-speed-medium = Canolig
+run-speed-fast = Cyflym
+run-speed-instant = Ar Unwaith
+settings-instruction-set = Set Gyfarwyddiadau'r Blwch Tywod
+settings-format-mnemonics = Fformatio Cofeiriau
+settings-keyboard-shortcuts = Llwybrau Byr y Bysellfwrdd
+settings-shortcuts = Llwybrau byr (e.e. Ctrl+Shift+F5)
+settings-reset-shortcuts = Ailosod Llwybrau Byr
+settings-locked = { $label }: (wedi'i gloi gan eich gweinyddwr)
+
+## Keyboard shortcuts
+
+command-next-slide = Sleid Nesaf
+command-previous-slide = Sleid Flaenorol
+command-next-pane = Cwarel Nesaf
+command-previous-pane = Cwarel Blaenorol
+command-toggle-summary = Toglo'r Crynodeb Testun
+keymap-empty = Teipiwch fysell, e.e. Ctrl+S
+keymap-missing-key = Does dim bysell ar ôl yr addaswyr
+keymap-unknown-key = Nid yw "{ $key }" yn fysell
+keymap-unknown-modifier = Nid yw "{ $modifier }" yn addasydd, defnyddiwch Ctrl, Alt, neu Shift
+keymap-conflict-command = Hefyd yn cael ei ddefnyddio ar gyfer { $command }
+keymap-conflict-reserved = Yn cael ei ddefnyddio ar gyfer { $used_for }
+keymap-reserved-typing = teipio a symud rhwng botymau, ychwanegwch Ctrl neu Alt
+keymap-reserved-clipboard = copïo a gludo yn y golygydd
@@ -0,0 +1,254 @@
+# English, the language every other catalogue is translated from
+# Messages are written as `id = text`, indented lines carry on the message before them on a new line and { $name } is replaced with an argument
+
+## Diagnostics from assembling and running programs
+
+token-number = number
+token-number-value = number `{ $number }`
+token-identifier = identifier
+token-identifier-value = identifier: `{ $identifier }`
+token-comment = comment
+token-newline = newline
+list-or = { $list } or { $last }
+
+lexer-invalid-character =
+    Encountered an error while lexing...
+    Invalid character `{ $character }` at ({ $line }:{ $column })
+
+parser-error =
+    Encountered an error while parsing...
+    { $error }
+parser-expected-nothing = Expected nothing
+parser-expected = Expected { $token }
+parser-expected-list = Expected: { $tokens }
+parser-nothing = nothing
+parser-received = { $expected }, received { $received }
+number-out-of-range = Number `{ $number }` out of range, expected a number between -999 and 999 inclusive
+address-out-of-range = Address `{ $address }` out of range, expected a number between 0 and 100 exclusive
+
+generator-invalid-identifier =
+    Encountered an error during code generation...
+    Invalid identifier `{ $identifier }`
+generator-unsupported-instruction =
+    Encountered an error during code generation...
+    `{ $instruction }` is not part of the { $instruction_set } instruction set, switch to the { $supported } instruction set to use it
+generator-program-too-large =
+    Encountered an error during code generation...
+    Program is { $length } locations long but only 100 fit in memory
+generator-label-out-of-range =
+    Encountered an error during code generation...
+    Label `{ $identifier }` is at address { $address }, past the end of memory
+
+macro-error =
+    Encountered an error while expanding macros...
+    { $error }
+macro-missing-name = Expected a name after MACRO
+macro-invalid-parameter = Expected an identifier as a macro parameter, received { $token }
+macro-unterminated = Macro `{ $name }` is never closed, did you forget ENDM?
+macro-nested-definition = Macro `{ $name }` is defined inside of another macro
+macro-unexpected-end = Found ENDM without a matching MACRO
+macro-argument-count = Macro `{ $name }` expects { $expected } argument(s), received { $received }
+macro-recursion-limit = Macro `{ $name }` expanded too many times, does it use itself?
+
+warning-unreachable-code = Code at address { $address } can never be run
+warning-uninitialised-data = `{ $label }` is read but never given a value, did you forget a number after DAT or an STA?
+warning-branch-to-data = Branching to `{ $label }`, which is data rather than code
+warning-label-as-code-and-data = `{ $label }` is used as both a code label and a data label
+warning-missing-halt = Code runs into data at address { $address }, did you forget to halt?
+warning-program-too-large = Program is { $length } locations long but only 100 fit in memory, it can't be assembled
+
+runtime-error =
+    Encountered an error at runtime...
+    { $error }
+runtime-expected-instruction = Ran into data memory whilst running code, did you forget to halt?
+runtime-expected-data =
+    Expected an operand pointing to a data location, got an instruction location.
+    Did you use a number instead of an identifier or change the line count of your program?
+runtime-invalid-instruction = Instruction `{ $code }` is not part of the instruction set
+runtime-waiting-for-input = Waiting for input...
+runtime-export-failed = Failed to export trace to { $path }: { $error }
+trace-missing-input = The program asked for more inputs than were given
+trace-max-steps = The program didn't halt within { $max_steps } steps
+trace-unknown-format = Expected csv or json, found { $format }
+
+stage-fetch-address = Fetch: PC → MAR
+stage-fetch-instruction = Fetch: RAM[MAR] → MDR → CIR, MAR
+stage-decode = Decode: CIR
+stage-execute = Execute
+stage-increment = Increment: PC + 1 → PC
+
+## The instruction set
+
+instruction-set-classic = Classic
+instruction-set-extended = Extended
+instruction-set-exam-board = Exam Board
+instruction-set-unknown = Unknown instruction set `{ $name }`, expected classic, extended, or exam-board
+description-halt = Stop running the program
+description-add = Add the value at an address to the accumulator
+description-sub = Subtract the value at an address from the accumulator
+description-store = Store the accumulator at an address
+description-load = Load the value at an address into the accumulator
+description-load-indirect = Load the value at the address stored at an address into the accumulator
+description-branch = Always jump to an address
+description-branch-zero = Jump to an address if the accumulator is zero
+description-branch-positive = Jump to an address if the accumulator is zero or positive
+description-input = Ask the user for a number and put it in the accumulator
+description-output = Print the accumulator as a number
+description-output-character = Print the accumulator as an ASCII character
+description-data = Not an instruction, puts a number in memory when the program is assembled
+effect-halt = Nothing changes, the program stops
+effect-branch-zero = PC = X if ACC = 0
+effect-branch-positive = PC = X if ACC >= 0
+effect-input = ACC = input
+effect-output = Output ACC
+effect-output-character = Output ACC as a character
+effect-data = [X] = number (0 if there is none)
+
+## Files and the config
+
+file-read-failed = Failed to read { $path }: { $error }
+file-write-failed = Failed to write to { $path }: { $error }
+config-no-home-directory = Couldn't find your home directory to keep the config in, set { $variable } to pick where it is kept
+config-io = Couldn't read or write { $path }, { $error }
+config-parse = The config file has a mistake in it, { $error }
+config-serialize = The config couldn't be written as TOML, { $error }
+config-unsupported-version = The config file is version { $version } (saved by a newer version of algor), this version only understands up to version { $supported }
+theme-invalid-colour = Expected a colour like "#1e1e2e", found "{ $colour }"
+theme-built-in-name = "{ $name }" is already the name of a built-in theme
+theme-unsupported = Unsupported theme
+config-system = The system config at { $path } couldn't be used, ask whoever looks after this computer to fix it. { $error }
+
+## Used on more than one screen
+
+back = Back
+save = Save
+cancel = Cancel
+untitled = Untitled
+warning = Warning: { $warning }
+
+## Menu
+
+menu-config-error = Your config couldn't be loaded so the default settings are being used. { $error }
+menu-reset-config = Reset Config
+menu-dismiss = Dismiss
+menu-recovered = Unsaved work from last time was found: { $files }
+menu-restore = Restore
+menu-discard = Discard
+menu-lessons = Lessons
+menu-sandbox = Sandbox
+menu-recent-files = Recent Files
+menu-reopen = Reopen Last Session
+
+## Lessons
+
+lesson-select-directory-error =
+    Encountered an error while opening directory...
+    Failed to read from lessons directory, are you sure the directory exists?
+lesson-select-untitled = Untitled Lesson
+lesson-select-start = Start
+lesson-select-slides = { $count } slide(s)
+lesson-buffer = { $title } (lesson)
+lesson-answer = Answer
+lesson-correct = Correct!
+lesson-incorrect = Not quite, try again
+lesson-trace-error =
+    The program in this trace table can't be run:
+    { $error }
+lesson-trace-output = Output
+lesson-trace-correct = Every cell is right!
+lesson-trace-wrong = { $wrong } cells aren't right, check the highlighted cells
+lesson-next = Next
+lesson-completed = Lesson Completed!
+
+## Sandbox
+
+sandbox-save-changes = Save changes to { $name }?
+sandbox-leave-unsaved = Some programs have unsaved changes, leave anyway?
+sandbox-dont-save = Don't Save
+sandbox-leave = Leave
+
+## Panes
+
+pane-editor = Editor
+pane-state-viewer = State Viewer
+pane-terminal = Terminal
+pane-reference = Instruction Reference
+pane-lesson = Lesson
+
+editor-pick-file = Pick LMC file...
+editor-save-file = Save LMC file...
+editor-export-file = Export trace...
+editor-open = Open
+editor-save-as = Save As
+editor-export-trace = Export Trace
+editor-format = Format
+editor-assemble = Assemble
+editor-run = Run
+editor-step = Step
+editor-stop = Stop
+editor-reset = Reset
+editor-input = Input...
+
+reference-under-cursor = Under the cursor:
+reference-instruction-set = { $instruction_set } instruction set:
+
+memory-format-decimal = Decimal
+memory-format-mnemonic = Mnemonic
+memory-format-signed = Signed
+memory-format-labelled = Labelled
+state-was = Was { $value }
+state-last-stage = { $last }  (next: { $next })
+state-next-stage = Next: { $next }
+state-cpu = CPU:
+state-micro-steps = Show each step of the fetch-decode-execute cycle
+state-ram = RAM:
+state-table = Table
+state-text-summary = Text Summary
+summary-program-counter = Program counter { $value }, next instruction at { $next }
+summary-accumulator = Accumulator { $value }
+summary-registers = Current instruction register { $cir }, memory address register { $mar }, memory data register { $mdr }
+summary-next-step = Next step: { $stage }
+summary-memory-empty = Every memory location is 0
+summary-memory-in-use = { $count } memory location(s) in use, the rest are 0:
+
+## Settings
+
+settings = Settings
+settings-pick-lessons-directory = Pick lessons directory...
+settings-appearance = Appearance
+settings-theme = Theme
+settings-language = Language
+settings-font-size = Font Size
+settings-preview = Preview
+settings-preview-success = Assembled successfully
+settings-preview-error = Error: unknown label
+settings-functionality = Functionality
+settings-lessons-directory = Lessons Directory
+settings-browse = Browse
+settings-run-speed = Run Speed
+run-speed-slow = Slow
+run-speed-medium = Medium
+run-speed-fast = Fast
+run-speed-instant = Instant
+settings-instruction-set = Sandbox Instruction Set
+settings-format-mnemonics = Format Mnemonics
+settings-keyboard-shortcuts = Keyboard Shortcuts
+settings-shortcuts = Shortcuts (e.g. Ctrl+Shift+F5)
+settings-reset-shortcuts = Reset Shortcuts
+settings-locked = { $label }: (locked by your administrator)
+
+## Keyboard shortcuts
+
+command-next-slide = Next Slide
+command-previous-slide = Previous Slide
+command-next-pane = Next Pane
+command-previous-pane = Previous Pane
+command-toggle-summary = Toggle Text Summary
+keymap-empty = Type a key, e.g. Ctrl+S
+keymap-missing-key = There is no key after the modifiers
+keymap-unknown-key = "{ $key }" isn't a key
+keymap-unknown-modifier = "{ $modifier }" isn't a modifier, use Ctrl, Alt, or Shift
+keymap-conflict-command = Also used for { $command }
+keymap-conflict-reserved = Used for { $used_for }
+keymap-reserved-typing = typing and moving between buttons, add Ctrl or Alt
+keymap-reserved-clipboard = copying and pasting in the editor
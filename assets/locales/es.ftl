@@ -0,0 +1,254 @@
+# Español (Spanish), translated from en.ftl
+# Messages are written as `id = text`, indented lines carry on the message before them on a new line and { $name } is replaced with an argument
+
+## Diagnostics from assembling and running programs
+
+token-number = número
+token-number-value = número `{ $number }`
+token-identifier = identificador
+token-identifier-value = identificador: `{ $identifier }`
+token-comment = comentario
+token-newline = salto de línea
+list-or = { $list } o { $last }
+
+lexer-invalid-character =
+    Se produjo un error durante el análisis léxico...
+    Carácter no válido `{ $character }` en ({ $line }:{ $column })
+
+parser-error =
+    Se produjo un error durante el análisis sintáctico...
+    { $error }
+parser-expected-nothing = No se esperaba nada
+parser-expected = Se esperaba { $token }
+parser-expected-list = Se esperaba: { $tokens }
+parser-nothing = nada
+parser-received = { $expected }, se recibió { $received }
+number-out-of-range = El número `{ $number }` está fuera de rango, se esperaba un número entre -999 y 999 inclusive
+address-out-of-range = La dirección `{ $address }` está fuera de rango, se esperaba un número entre 0 y 100 sin incluirlos
+
+generator-invalid-identifier =
+    Se produjo un error durante la generación de código...
+    Identificador no válido `{ $identifier }`
+generator-unsupported-instruction =
+    Se produjo un error durante la generación de código...
+    `{ $instruction }` no forma parte del conjunto de instrucciones { $instruction_set }, cambia al conjunto de instrucciones { $supported } para usarla
+generator-program-too-large =
+    Se produjo un error durante la generación de código...
+    El programa ocupa { $length } posiciones pero solo caben 100 en la memoria
+generator-label-out-of-range =
+    Se produjo un error durante la generación de código...
+    La etiqueta `{ $identifier }` está en la dirección { $address }, más allá del final de la memoria
+
+macro-error =
+    Se produjo un error al expandir las macros...
+    { $error }
+macro-missing-name = Se esperaba un nombre después de MACRO
+macro-invalid-parameter = Se esperaba un identificador como parámetro de la macro, se recibió { $token }
+macro-unterminated = La macro `{ $name }` nunca se cierra, ¿olvidaste ENDM?
+macro-nested-definition = La macro `{ $name }` está definida dentro de otra macro
+macro-unexpected-end = Se encontró ENDM sin un MACRO correspondiente
+macro-argument-count = La macro `{ $name }` espera { $expected } argumento(s), se recibieron { $received }
+macro-recursion-limit = La macro `{ $name }` se expandió demasiadas veces, ¿se usa a sí misma?
+
+warning-unreachable-code = El código en la dirección { $address } nunca se puede ejecutar
+warning-uninitialised-data = `{ $label }` se lee pero nunca recibe un valor, ¿olvidaste un número después de DAT o un STA?
+warning-branch-to-data = Saltando a `{ $label }`, que es un dato en lugar de código
+warning-label-as-code-and-data = `{ $label }` se usa como etiqueta de código y como etiqueta de datos
+warning-missing-halt = El código llega a los datos en la dirección { $address }, ¿olvidaste detener el programa?
+warning-program-too-large = El programa ocupa { $length } posiciones pero solo caben 100 en la memoria, no se puede ensamblar
+
+runtime-error =
+    Se produjo un error durante la ejecución...
+    { $error }
+runtime-expected-instruction = Se llegó a la memoria de datos mientras se ejecutaba el código, ¿olvidaste detener el programa?
+runtime-expected-data =
+    Se esperaba un operando que apuntara a una posición de datos, se obtuvo una posición de instrucción.
+    ¿Usaste un número en lugar de un identificador o cambiaste el número de líneas de tu programa?
+runtime-invalid-instruction = La instrucción `{ $code }` no forma parte del conjunto de instrucciones
+runtime-waiting-for-input = Esperando una entrada...
+runtime-export-failed = No se pudo exportar la traza a { $path }: { $error }
+trace-missing-input = El programa pidió más entradas de las que se dieron
+trace-max-steps = El programa no se detuvo en { $max_steps } pasos
+trace-unknown-format = Se esperaba csv o json, se encontró { $format }
+
+stage-fetch-address = Búsqueda: PC → MAR
+stage-fetch-instruction = Búsqueda: RAM[MAR] → MDR → CIR, MAR
+stage-decode = Decodificación: CIR
+stage-execute = Ejecución
+stage-increment = Incremento: PC + 1 → PC
+
+## The instruction set
+
+instruction-set-classic = Clásico
+instruction-set-extended = Ampliado
+instruction-set-exam-board = Tribunal de Examen
+instruction-set-unknown = Conjunto de instrucciones desconocido `{ $name }`, se esperaba classic, extended o exam-board
+description-halt = Detener la ejecución del programa
+description-add = Sumar al acumulador el valor de una dirección
+description-sub = Restar al acumulador el valor de una dirección
+description-store = Guardar el acumulador en una dirección
+description-load = Cargar en el acumulador el valor de una dirección
+description-load-indirect = Cargar en el acumulador el valor de la dirección guardada en una dirección
+description-branch = Saltar siempre a una dirección
+description-branch-zero = Saltar a una dirección si el acumulador es cero
+description-branch-positive = Saltar a una dirección si el acumulador es cero o positivo
+description-input = Pedir un número al usuario y ponerlo en el acumulador
+description-output = Mostrar el acumulador como número
+description-output-character = Mostrar el acumulador como carácter ASCII
+description-data = No es una instrucción, pone un número en la memoria al ensamblar el programa
+effect-halt = No cambia nada, el programa se detiene
+effect-branch-zero = PC = X si ACC = 0
+effect-branch-positive = PC = X si ACC >= 0
+effect-input = ACC = entrada
+effect-output = Mostrar ACC
+effect-output-character = Mostrar ACC como carácter
+effect-data = [X] = número (0 si no hay ninguno)
+
+## Files and the config
+
+file-read-failed = No se pudo leer { $path }: { $error }
+file-write-failed = No se pudo escribir en { $path }: { $error }
+config-no-home-directory = No se encontró tu directorio personal para guardar la configuración, define { $variable } para elegir dónde se guarda
+config-io = No se pudo leer o escribir { $path }, { $error }
+config-parse = El archivo de configuración tiene un error, { $error }
+config-serialize = No se pudo escribir la configuración como TOML, { $error }
+config-unsupported-version = El archivo de configuración es de la versión { $version } (guardado por una versión más reciente de algor), esta versión solo entiende hasta la versión { $supported }
+theme-invalid-colour = Se esperaba un color como "#1e1e2e", se encontró "{ $colour }"
+theme-built-in-name = "{ $name }" ya es el nombre de un tema integrado
+theme-unsupported = Tema no compatible
+config-system = No se pudo usar la configuración del sistema en { $path }, pide a quien administre este ordenador que la arregle. { $error }
+
+## Used on more than one screen
+
+back = Atrás
+save = Guardar
+cancel = Cancelar
+untitled = Sin título
+warning = Aviso: { $warning }
+
+## Menu
+
+menu-config-error = No se pudo cargar tu configuración, así que se está usando la configuración predeterminada. { $error }
+menu-reset-config = Restablecer Configuración
+menu-dismiss = Descartar Aviso
+menu-recovered = Se encontró trabajo sin guardar de la última vez: { $files }
+menu-restore = Recuperar
+menu-discard = Descartar
+menu-lessons = Lecciones
+menu-sandbox = Zona de Pruebas
+menu-recent-files = Archivos Recientes
+menu-reopen = Reabrir la Última Sesión
+
+## Lessons
+
+lesson-select-directory-error =
+    Se produjo un error al abrir el directorio...
+    No se pudo leer el directorio de lecciones, ¿seguro que el directorio existe?
+lesson-select-untitled = Lección Sin Título
+lesson-select-start = Empezar
+lesson-select-slides = { $count } diapositiva(s)
+lesson-buffer = { $title } (lección)
+lesson-answer = Respuesta
+lesson-correct = ¡Correcto!
+lesson-incorrect = No del todo, inténtalo de nuevo
+lesson-trace-error =
+    El programa de esta tabla de traza no se puede ejecutar:
+    { $error }
+lesson-trace-output = Salida
+lesson-trace-correct = ¡Todas las celdas son correctas!
+lesson-trace-wrong = { $wrong } celdas no son correctas, revisa las celdas resaltadas
+lesson-next = Siguiente
+lesson-completed = ¡Lección Completada!
+
+## Sandbox
+
+sandbox-save-changes = ¿Guardar los cambios en { $name }?
+sandbox-leave-unsaved = Algunos programas tienen cambios sin guardar, ¿salir de todos modos?
+sandbox-dont-save = No Guardar
+sandbox-leave = Salir
+
+## Panes
+
+pane-editor = Editor
+pane-state-viewer = Visor de Estado
+pane-terminal = Terminal
+pane-reference = Referencia de Instrucciones
+pane-lesson = Lección
+
+editor-pick-file = Elegir archivo LMC...
+editor-save-file = Guardar archivo LMC...
+editor-export-file = Exportar traza...
+editor-open = Abrir
+editor-save-as = Guardar Como
+editor-export-trace = Exportar Traza
+editor-format = Formatear
+editor-assemble = Ensamblar
+editor-run = Ejecutar
+editor-step = Paso
+editor-stop = Detener
+editor-reset = Reiniciar
+editor-input = Entrada...
+
+reference-under-cursor = Bajo el cursor:
+reference-instruction-set = Conjunto de instrucciones { $instruction_set }:
+
+memory-format-decimal = Decimal
+memory-format-mnemonic = Mnemónico
+memory-format-signed = Con Signo
+memory-format-labelled = Con Etiquetas
+state-was = Era { $value }
+state-last-stage = { $last }  (siguiente: { $next })
+state-next-stage = Siguiente: { $next }
+state-cpu = CPU:
+state-micro-steps = Mostrar cada paso del ciclo de búsqueda, decodificación y ejecución
+state-ram = RAM:
+state-table = Tabla
+state-text-summary = Resumen en Texto
+summary-program-counter = Contador de programa { $value }, la siguiente instrucción está en { $next }
+summary-accumulator = Acumulador { $value }
+summary-registers = Registro de instrucción actual { $cir }, registro de dirección de memoria { $mar }, registro de datos de memoria { $mdr }
+summary-next-step = Siguiente paso: { $stage }
+summary-memory-empty = Todas las posiciones de memoria son 0
+summary-memory-in-use = { $count } posición(es) de memoria en uso, el resto son 0:
+
+## Settings
+
+settings = Ajustes
+settings-pick-lessons-directory = Elegir el directorio de lecciones...
+settings-appearance = Apariencia
+settings-theme = Tema
+settings-language = Idioma
+settings-font-size = Tamaño de Letra
+settings-preview = Vista Previa
+settings-preview-success = Ensamblado correctamente
+settings-preview-error = Error: etiqueta desconocida
+settings-functionality = Funcionalidad
+settings-lessons-directory = Directorio de Lecciones
+settings-browse = Examinar
+settings-run-speed = Velocidad de Ejecución
+run-speed-slow = Lenta
+run-speed-medium = Media
+run-speed-fast = Rápida
+run-speed-instant = Instantánea
+settings-instruction-set = Conjunto de Instrucciones de la Zona de Pruebas
+settings-format-mnemonics = Formatear Mnemónicos
+settings-keyboard-shortcuts = Atajos de Teclado
+settings-shortcuts = Atajos (p. ej. Ctrl+Shift+F5)
+settings-reset-shortcuts = Restablecer Atajos
+settings-locked = { $label }: (bloqueado por tu administrador)
+
+## Keyboard shortcuts
+
+command-next-slide = Diapositiva Siguiente
+command-previous-slide = Diapositiva Anterior
+command-next-pane = Panel Siguiente
+command-previous-pane = Panel Anterior
+command-toggle-summary = Mostrar u Ocultar el Resumen en Texto
+keymap-empty = Escribe una tecla, p. ej. Ctrl+S
+keymap-missing-key = No hay ninguna tecla después de los modificadores
+keymap-unknown-key = "{ $key }" no es una tecla
+keymap-unknown-modifier = "{ $modifier }" no es un modificador, usa Ctrl, Alt o Shift
+keymap-conflict-command = También se usa para { $command }
+keymap-conflict-reserved = Se usa para { $used_for }
+keymap-reserved-typing = escribir y moverse entre botones, añade Ctrl o Alt
+keymap-reserved-clipboard = copiar y pegar en el editor
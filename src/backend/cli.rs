@@ -0,0 +1,330 @@
+use std::{
+    fs,
+    io::{self, Read},
+};
+
+use crate::backend::compiler::formatter::{self, AliasStyle};
+use crate::backend::paths::Overrides;
+use crate::shared::isa::InstructionSet;
+use crate::shared::trace::{self, TraceFormat};
+
+const FMT_USAGE: &str = "\
+Usage: algor fmt [--check] [--aliases standard|alternative] [--instruction-set classic|extended|exam-board] [FILES...]
+
+Formats LMC source files in place, or standard input to standard output if no files are given.
+
+Options:
+    --check            Don't write anything, list the files that aren't formatted and exit with 1 if there are any
+    --aliases          Use HLT and STA (standard) or COB and STO (alternative), defaults to standard
+    --instruction-set  The instruction set used to recognise mnemonics, defaults to classic";
+
+const TRACE_USAGE: &str = "\
+Usage: algor trace [--format csv|json] [--inputs 5,3] [--max-steps 1000] [--instruction-set classic|extended|exam-board] [--output FILE] FILE
+
+Runs an LMC program and writes a trace table of every instruction run (cycle, PC, instruction, ACC before and after, memory writes, input and output).
+
+Options:
+    --format           csv or json, defaults to csv
+    --inputs           Comma separated inputs given to INP in order
+    --max-steps        Stop with an error after this many instructions, defaults to 1000
+    --instruction-set  The instruction set the program is assembled with, defaults to classic
+    --output           Write the trace to a file instead of standard output";
+
+const APP_USAGE: &str = "\
+Usage: algor [--config FILE] [--data-dir DIR] [--lessons-dir DIR]
+       algor fmt [OPTIONS] [FILES...]
+       algor trace [OPTIONS] FILE
+
+Opens the application.
+
+Options:
+    --config       The config file to use, defaults to algor/config.toml in the platform's config directory ($XDG_CONFIG_HOME or ~/.config on Linux)
+    --data-dir     Where the last session and autosaved work are kept, defaults to algor in the platform's data directory ($XDG_DATA_HOME or ~/.local/share on Linux)
+    --lessons-dir  Open lessons from this directory instead of the one in the config
+
+Each option can also be set with an environment variable (ALGOR_CONFIG, ALGOR_DATA_DIR, and ALGOR_LESSONS_DIR), options take priority.";
+
+// Options for the fmt subcommand
+struct FmtOptions {
+    check: bool,
+    aliases: AliasStyle,
+    instruction_set: InstructionSet,
+    files: Vec<String>,
+}
+
+// Turn the arguments after "fmt" into options, returning an error message for anything that isn't recognised
+fn parse_fmt(args: Vec<String>) -> Result<FmtOptions, String> {
+    let mut options = FmtOptions {
+        check: false,
+        aliases: AliasStyle::default(),
+        instruction_set: InstructionSet::default(),
+        files: Vec::new(),
+    };
+
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--check" => options.check = true,
+            "--aliases" => {
+                options.aliases = match args.next().as_deref() {
+                    Some("standard") => AliasStyle::Standard,
+                    Some("alternative") => AliasStyle::Alternative,
+                    other => {
+                        return Err(format!(
+                            "Expected standard or alternative after --aliases, found {}",
+                            other.unwrap_or("nothing")
+                        ));
+                    }
+                }
+            }
+            "--instruction-set" => {
+                let Some(value) = args.next() else {
+                    return Err("Expected an instruction set after --instruction-set".into());
+                };
+                options.instruction_set = InstructionSet::try_from(value)?;
+            }
+            flag if flag.starts_with("--") => return Err(format!("Unknown option {flag}")),
+            _ => options.files.push(arg),
+        }
+    }
+
+    Ok(options)
+}
+
+// Run the fmt subcommand, returning the exit code
+fn fmt(args: Vec<String>) -> i32 {
+    if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        println!("{FMT_USAGE}");
+        return 0;
+    }
+
+    let options = match parse_fmt(args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{e}\n\n{FMT_USAGE}");
+            return 2;
+        }
+    };
+
+    let format = |source: &str| formatter::format(source, options.instruction_set, options.aliases);
+
+    // Without any files, act as a filter (e.g. for editors that format through a pipe)
+    if options.files.is_empty() {
+        let mut source = String::new();
+
+        if let Err(e) = io::stdin().read_to_string(&mut source) {
+            eprintln!("Failed to read from standard input: {e}");
+            return 1;
+        }
+
+        return match format(&source) {
+            Ok(formatted) if options.check => (formatted != source) as i32,
+            Ok(formatted) => {
+                print!("{formatted}");
+                0
+            }
+            Err(e) => {
+                eprintln!("<stdin>: {e}");
+                1
+            }
+        };
+    }
+
+    let mut code = 0;
+
+    for path in &options.files {
+        let source = match fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("{path}: {e}");
+                code = 1;
+                continue;
+            }
+        };
+
+        let formatted = match format(&source) {
+            Ok(formatted) => formatted,
+            Err(e) => {
+                eprintln!("{path}: {e}");
+                code = 1;
+                continue;
+            }
+        };
+
+        if formatted == source {
+            continue;
+        }
+
+        // Only list files in check mode so the command can be used to enforce a house style on submitted work
+        if options.check {
+            println!("{path}");
+            code = 1;
+        } else if let Err(e) = fs::write(path, formatted) {
+            eprintln!("{path}: {e}");
+            code = 1;
+        }
+    }
+
+    code
+}
+
+// Options for the trace subcommand
+struct TraceOptions {
+    format: TraceFormat,
+    inputs: Vec<i16>,
+    max_steps: usize,
+    instruction_set: InstructionSet,
+    output: Option<String>,
+    file: String,
+}
+
+// Ditto parse_fmt comment but for the arguments after "trace"
+fn parse_trace(args: Vec<String>) -> Result<TraceOptions, String> {
+    let mut format = TraceFormat::Csv;
+    let mut inputs = Vec::new();
+    let mut max_steps = 1000;
+    let mut instruction_set = InstructionSet::default();
+    let mut output = None;
+    let mut file = None;
+
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        // Every option is followed by a value
+        let mut value = || args.next().ok_or(format!("Expected a value after {arg}"));
+
+        match arg.as_str() {
+            "--format" => format = TraceFormat::try_from(value()?)?,
+            "--inputs" => {
+                inputs = value()?
+                    .split(',')
+                    .filter(|input| !input.trim().is_empty())
+                    .map(|input| {
+                        input
+                            .trim()
+                            .parse()
+                            .map_err(|_| format!("Expected a number as an input, found {input}"))
+                    })
+                    .collect::<Result<_, _>>()?
+            }
+            "--max-steps" => {
+                let value = value()?;
+                max_steps = value
+                    .parse()
+                    .map_err(|_| format!("Expected a number after --max-steps, found {value}"))?
+            }
+            "--instruction-set" => instruction_set = InstructionSet::try_from(value()?)?,
+            "--output" => output = Some(value()?),
+            flag if flag.starts_with("--") => return Err(format!("Unknown option {flag}")),
+            _ if file.is_some() => return Err(format!("Unexpected argument {arg}")),
+            _ => file = Some(arg),
+        }
+    }
+
+    Ok(TraceOptions {
+        format,
+        inputs,
+        max_steps,
+        instruction_set,
+        output,
+        file: file.ok_or("Expected a file to trace")?,
+    })
+}
+
+// Run the trace subcommand, returning the exit code
+fn trace(args: Vec<String>) -> i32 {
+    if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        println!("{TRACE_USAGE}");
+        return 0;
+    }
+
+    let options = match parse_trace(args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{e}\n\n{TRACE_USAGE}");
+            return 2;
+        }
+    };
+
+    let source = match fs::read_to_string(&options.file) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("{}: {e}", options.file);
+            return 1;
+        }
+    };
+
+    let trace = match trace::run(
+        &source,
+        options.instruction_set,
+        &options.inputs,
+        options.max_steps,
+    ) {
+        Ok(trace) => trace,
+        Err(e) => {
+            eprintln!("{}: {e}", options.file);
+            return 1;
+        }
+    };
+
+    let exported = trace.export(options.format);
+
+    match options.output {
+        Some(path) => {
+            if let Err(e) = fs::write(&path, exported) {
+                eprintln!("{path}: {e}");
+                return 1;
+            }
+        }
+        None => print!("{exported}"),
+    }
+
+    0
+}
+
+// Turn the arguments given when opening the application into paths that override the defaults
+fn parse_app(args: Vec<String>) -> Result<Overrides, String> {
+    let mut overrides = Overrides::default();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let path = match arg.as_str() {
+            "--config" => &mut overrides.config,
+            "--data-dir" => &mut overrides.data,
+            "--lessons-dir" => &mut overrides.lessons,
+            _ => return Err(format!("Unknown option {arg}")),
+        };
+
+        let Some(value) = args.next() else {
+            return Err(format!("Expected a path after {arg}"));
+        };
+        *path = Some(value.into());
+    }
+
+    Ok(overrides)
+}
+
+// The paths to open the application with, or the exit code if the arguments are wrong (or help was asked for)
+pub fn app(args: Vec<String>) -> Result<Overrides, i32> {
+    if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        println!("{APP_USAGE}");
+        return Err(0);
+    }
+
+    parse_app(args).map_err(|e| {
+        eprintln!("{e}\n\n{APP_USAGE}");
+        2
+    })
+}
+
+// Run a subcommand if one is given (e.g. algor fmt --check program.lmc), returning the exit code, otherwise None to open the application as normal
+pub fn run(args: Vec<String>) -> Option<i32> {
+    let mut args = args.into_iter();
+
+    match args.next().as_deref() {
+        Some("fmt") => Some(fmt(args.collect())),
+        Some("trace") => Some(trace(args.collect())),
+        _ => None,
+    }
+}
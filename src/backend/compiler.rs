@@ -1,18 +1,80 @@
+pub mod analysis;
+pub mod formatter;
 pub mod generator;
 pub mod lexer;
+pub mod macros;
 pub mod parser;
 
 #[cfg(test)]
 pub mod tests;
 
-use crate::backend::compiler::generator::Location;
-use crate::backend::compiler::lexer::Lexer;
-use crate::backend::compiler::parser::Parser;
+use crate::backend::compiler::analysis::Warning;
+use crate::backend::compiler::generator::{Location, UnsupportedInstruction};
+use crate::backend::compiler::lexer::{Lexer, Token};
+use crate::backend::compiler::macros::MacroExpander;
+use crate::backend::compiler::parser::{Parser, Program};
+use crate::shared::isa::{InstructionSet, Operation};
 use std;
 
-// Combines the lexer, parser, and code generator, returning machine code that can be placed into RAM, or a generic error if any method fails
+// Combines the lexer, macro expander, parser, and code generator, returning machine code that can be placed into RAM, or a generic error if any method fails
 pub fn compile(source: &str) -> Result<[Location; 100], Box<dyn std::error::Error>> {
-    Ok(<[Location; 100]>::try_from(
-        Parser::new(Lexer::new(source).lex()?).parse()?,
+    compile_with(source, InstructionSet::default())
+}
+
+// Ditto compile comment but for a specific instruction set
+pub fn compile_with(
+    source: &str,
+    instruction_set: InstructionSet,
+) -> Result<[Location; 100], Box<dyn std::error::Error>> {
+    Ok(generator::generate(
+        parse(source, instruction_set)?,
+        instruction_set,
     )?)
 }
+
+// Lex, expand, and parse the source code without generating any code, used for features that only need to know the structure of a program (e.g. warnings and the outline in the editor pane)
+pub fn parse(
+    source: &str,
+    instruction_set: InstructionSet,
+) -> Result<Program, Box<dyn std::error::Error>> {
+    let tokens = Lexer::with_instruction_set(source, instruction_set).lex()?;
+    let unsupported = unsupported_instruction(&tokens, instruction_set);
+
+    match Parser::with_instruction_set(MacroExpander::new(tokens).expand()?, instruction_set)
+        .parse()
+    {
+        Ok(program) => Ok(program),
+        // The parser only sees an identifier where the instruction should be, so say which instruction set it's from instead (e.g. OTC in the classic instruction set)
+        Err(error) => match unsupported {
+            Some(operation) => Err(UnsupportedInstruction {
+                operation,
+                instruction_set,
+            }
+            .into()),
+            None => Err(error.into()),
+        },
+    }
+}
+
+// Find the first identifier that's an instruction in another instruction set but not in this one, since the lexer can't tell it apart from a label
+fn unsupported_instruction(tokens: &[Token], instruction_set: InstructionSet) -> Option<Operation> {
+    tokens.iter().find_map(|token| {
+        let Token::Identifier(name) = token else {
+            return None;
+        };
+
+        InstructionSet::ALL
+            .iter()
+            .find_map(|other| other.mnemonic(name))
+            .map(|mnemonic| mnemonic.operation)
+            .filter(|operation| instruction_set.encoding(*operation).is_none())
+    })
+}
+
+// Check the program for likely mistakes (this still works if code generation would fail)
+pub fn warnings(
+    source: &str,
+    instruction_set: InstructionSet,
+) -> Result<Vec<Warning>, Box<dyn std::error::Error>> {
+    Ok(analysis::analyse(&parse(source, instruction_set)?))
+}
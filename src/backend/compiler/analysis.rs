@@ -0,0 +1,204 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display};
+use std::rc::Rc;
+
+use crate::backend::compiler::parser::{Instruction, Operand, Program};
+use crate::shared::locale::tr_with;
+
+// Mistakes that don't stop a program from being assembled but are most likely not what the user meant
+#[derive(PartialEq, Debug)]
+pub enum Warning {
+    // Code that can never be run (e.g. an instruction after HLT or BRA that nothing branches to)
+    UnreachableCode(u8),
+    // A data location without a value that is read but never stored to (e.g. X DAT, LDA X without any STA X)
+    UninitialisedData(Rc<str>),
+    // A branch to a data location (e.g. BRA X where X DAT)
+    BranchToData(Rc<str>),
+    // A label used for both code and data (e.g. loop LDA X and LDA loop)
+    LabelAsCodeAndData(Rc<str>),
+    // Code that can run straight into a data location as there is no HLT or BRA before it
+    MissingHalt(u8),
+    // More instructions than there are memory locations
+    ProgramTooLarge(usize),
+}
+
+// User-friendly warning messages
+impl Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            Warning::UnreachableCode(address) => tr_with(
+                "warning-unreachable-code",
+                &[("address", &format!("{address:02}"))],
+            ),
+            Warning::UninitialisedData(label) => {
+                tr_with("warning-uninitialised-data", &[("label", label)])
+            }
+            Warning::BranchToData(label) => tr_with("warning-branch-to-data", &[("label", label)]),
+            Warning::LabelAsCodeAndData(label) => {
+                tr_with("warning-label-as-code-and-data", &[("label", label)])
+            }
+            Warning::MissingHalt(address) => tr_with(
+                "warning-missing-halt",
+                &[("address", &format!("{address:02}"))],
+            ),
+            Warning::ProgramTooLarge(length) => {
+                tr_with("warning-program-too-large", &[("length", length)])
+            }
+        };
+
+        write!(f, "{text}")
+    }
+}
+
+// The operand of a branch instruction, None for every other instruction
+fn branch_target(instruction: &Instruction) -> Option<&Operand> {
+    match instruction {
+        Instruction::Branch(operand)
+        | Instruction::BranchZero(operand)
+        | Instruction::BranchPositive(operand) => Some(operand),
+        _ => None,
+    }
+}
+
+// The operand of an instruction that reads or writes data, None for every other instruction
+fn data_operand(instruction: &Instruction) -> Option<&Operand> {
+    match instruction {
+        Instruction::Add(operand)
+        | Instruction::Sub(operand)
+        | Instruction::Store(operand)
+        | Instruction::Load(operand)
+        | Instruction::LoadIndirect(operand) => Some(operand),
+        _ => None,
+    }
+}
+
+// Check a parsed program for likely mistakes, returning a list of warnings in the order they were found
+pub fn analyse(program: &Program) -> Vec<Warning> {
+    let instructions = &program.instructions;
+
+    // Addresses past the end of memory don't fit in a label, so the rest of the analysis would point at the wrong locations
+    if instructions.len() > 100 {
+        return vec![Warning::ProgramTooLarge(instructions.len())];
+    }
+
+    let mut warnings = Vec::new();
+
+    // Labels preceding data locations and their addresses (e.g. X DAT)
+    let data: HashMap<&Rc<str>, usize> = instructions
+        .iter()
+        .enumerate()
+        .filter_map(|(i, instruction)| match instruction {
+            Instruction::Data(label, _) => Some((label, i)),
+            _ => None,
+        })
+        .collect();
+
+    // Labels defined in both places, sorted so the warnings are always in the same order
+    let mut both: Vec<&Rc<str>> = program
+        .labels
+        .keys()
+        .filter(|label| data.contains_key(label))
+        .collect();
+
+    // Labels used in both places (i.e. a code label used as an operand for LDA, ADD, SUB, STA, or LDI)
+    for instruction in instructions {
+        if let Some(Operand::Identifier(label)) = data_operand(instruction)
+            && program.labels.contains_key(label)
+            && !both.contains(&label)
+        {
+            both.push(label);
+        }
+    }
+
+    both.sort();
+    warnings.extend(
+        both.into_iter()
+            .map(|label| Warning::LabelAsCodeAndData(Rc::clone(label))),
+    );
+
+    // Branches to data labels
+    let mut branched = HashSet::new();
+
+    for instruction in instructions {
+        if let Some(Operand::Identifier(label)) = branch_target(instruction)
+            && data.contains_key(label)
+            && branched.insert(label)
+        {
+            warnings.push(Warning::BranchToData(Rc::clone(label)));
+        }
+    }
+
+    // Data without a value that is read but never written to
+    for instruction in instructions {
+        let Instruction::Data(label, None) = instruction else {
+            continue;
+        };
+
+        let refers_to_label = |instruction: &&Instruction| match data_operand(instruction) {
+            Some(Operand::Identifier(operand)) => operand == label,
+            _ => false,
+        };
+
+        let (writes, reads): (Vec<&Instruction>, Vec<&Instruction>) = instructions
+            .iter()
+            .filter(refers_to_label)
+            .partition(|instruction| matches!(instruction, Instruction::Store(_)));
+
+        if !reads.is_empty() && writes.is_empty() {
+            warnings.push(Warning::UninitialisedData(Rc::clone(label)));
+        }
+    }
+
+    // Follow every path the program can take from the first address to find code that is never run and data that is run into
+    let mut reached = vec![false; instructions.len()];
+    let mut pending = vec![0usize];
+    let mut run_into_data = None;
+
+    while let Some(address) = pending.pop() {
+        let Some(instruction) = instructions.get(address) else {
+            continue;
+        };
+
+        if let Instruction::Data(_, _) = instruction {
+            run_into_data = Some(run_into_data.map_or(address, |first: usize| first.min(address)));
+            continue;
+        }
+
+        if reached[address] {
+            continue;
+        }
+        reached[address] = true;
+
+        // Where the branch goes to, if it goes to a data label it has already been warned about
+        let target = branch_target(instruction).and_then(|operand| match operand {
+            Operand::Number(number) => Some(*number as usize),
+            Operand::Identifier(label) => program.labels.get(label).map(|i| *i as usize),
+        });
+
+        match instruction {
+            Instruction::Halt => {}
+            Instruction::Branch(_) => pending.extend(target),
+            Instruction::BranchZero(_) | Instruction::BranchPositive(_) => {
+                pending.extend(target);
+                pending.push(address + 1);
+            }
+            _ => pending.push(address + 1),
+        }
+    }
+
+    if let Some(address) = run_into_data {
+        warnings.push(Warning::MissingHalt(address as u8));
+    }
+
+    // Only warn about the first address of each block of unreachable code
+    for (i, instruction) in instructions.iter().enumerate() {
+        if !reached[i]
+            && instruction.operation().is_some()
+            && (i == 0 || reached[i - 1] || instructions[i - 1].operation().is_none())
+        {
+            warnings.push(Warning::UnreachableCode(i as u8));
+        }
+    }
+
+    warnings
+}
@@ -0,0 +1,160 @@
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backend::compiler::lexer::{InvalidCharacter, Lexer, Token};
+use crate::shared::isa::InstructionSet;
+
+/* Which name the formatter uses for instructions that have an alias (e.g. HLT and COB)
+Implement Deserialize and Serialize for config files */
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub enum AliasStyle {
+    // HLT and STA
+    #[default]
+    Standard,
+    // COB and STO
+    Alternative,
+}
+
+impl AliasStyle {
+    // Represents a list of alias styles (used in drop-down menus)
+    pub const ALL: &'static [AliasStyle] = &[AliasStyle::Standard, AliasStyle::Alternative];
+}
+
+// Allow formatting and printing out of strings (for drop-down menus)
+impl Display for AliasStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AliasStyle::Standard => write!(f, "HLT, STA"),
+            AliasStyle::Alternative => write!(f, "COB, STO"),
+        }
+    }
+}
+
+// A single line of source code split into columns, empty strings for columns that aren't used
+struct Row {
+    label: String,
+    mnemonic: String,
+    operands: String,
+    comment: Option<Rc<str>>,
+}
+
+// Write a token the way the formatter wants it to look (uppercase mnemonics with aliases normalised, identifiers and numbers as they are)
+fn render(token: &Token, instruction_set: InstructionSet, aliases: AliasStyle) -> String {
+    if let Some(operation) = token.operation()
+        && let Some(mnemonic) = instruction_set.encoding(operation)
+    {
+        return match (aliases, mnemonic.aliases.first()) {
+            (AliasStyle::Alternative, Some(alias)) => alias.to_string(),
+            _ => mnemonic.name.to_string(),
+        };
+    }
+
+    match token {
+        Token::Number(number) => number.to_string(),
+        Token::Identifier(identifier) => identifier.to_string(),
+        // Everything left is a keyword, the Display implementation already uses uppercase for these
+        _ => token.to_string(),
+    }
+}
+
+// Reformat LMC source code so labels, mnemonics, operands, and comments line up in columns, failing if the source code can't be lexed
+pub fn format(
+    source: &str,
+    instruction_set: InstructionSet,
+    aliases: AliasStyle,
+) -> Result<String, InvalidCharacter> {
+    let tokens = Lexer::with_instruction_set(source, instruction_set).lex()?;
+    let lines: Vec<&[Token]> = tokens.split(|token| *token == Token::Newline).collect();
+
+    // Macro names are needed to tell a label in front of a macro (e.g. start double X) apart from a macro with arguments (e.g. double X Y)
+    let macros: HashSet<&Rc<str>> = lines
+        .iter()
+        .filter_map(|line| match line {
+            [Token::Macro, Token::Identifier(name), ..] => Some(name),
+            _ => None,
+        })
+        .collect();
+
+    let rows: Vec<Row> = lines
+        .into_iter()
+        .map(|line| {
+            // Comments are always the last token on a line as they go until the end of the line
+            let (line, comment) = match line {
+                [rest @ .., Token::Comment(comment)] => (rest, Some(Rc::clone(comment))),
+                _ => (line, None),
+            };
+
+            // An identifier is a label if it's followed by an instruction, DAT, or the name of a macro
+            let (label, line) = match line {
+                [Token::Identifier(label), next, ..]
+                    if match next {
+                        Token::Identifier(name) => macros.contains(name),
+                        Token::Number(_) => false,
+                        _ => true,
+                    } =>
+                {
+                    (label.to_string(), &line[1..])
+                }
+                _ => (String::new(), line),
+            };
+
+            let mut line = line
+                .iter()
+                .map(|token| render(token, instruction_set, aliases));
+
+            Row {
+                label,
+                mnemonic: line.next().unwrap_or_default(),
+                operands: line.collect::<Vec<_>>().join(" "),
+                comment,
+            }
+        })
+        .collect();
+
+    // The width of each column is the width of its longest entry
+    let label_width = rows.iter().map(|row| row.label.len()).max().unwrap_or(0);
+    let mnemonic_width = rows.iter().map(|row| row.mnemonic.len()).max().unwrap_or(0);
+    let operands_width = rows.iter().map(|row| row.operands.len()).max().unwrap_or(0);
+
+    let mut formatted = rows
+        .into_iter()
+        .map(|row| {
+            // Lines with only a comment aren't indented
+            if row.label.is_empty() && row.mnemonic.is_empty() {
+                return row
+                    .comment
+                    .map(|comment| format!("// {comment}").trim_end().to_string())
+                    .unwrap_or_default();
+            }
+
+            // Leave out the label column entirely if there are no labels in the program
+            let mut line = if label_width == 0 {
+                String::new()
+            } else {
+                format!("{:label_width$} ", row.label)
+            };
+
+            line.push_str(&format!(
+                "{:mnemonic_width$} {:operands_width$}",
+                row.mnemonic, row.operands
+            ));
+
+            if let Some(comment) = row.comment {
+                line.push_str(&format!(" // {comment}"));
+            }
+
+            // Remove the padding from the end of lines without operands or comments
+            line.trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    // Always end with exactly one newline
+    formatted.truncate(formatted.trim_end().len());
+    formatted.push('\n');
+
+    Ok(formatted)
+}
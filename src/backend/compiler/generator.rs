@@ -1,4 +1,7 @@
+use crate::backend::compiler::lexer::Token;
 use crate::backend::compiler::parser::{Instruction, Operand, Program};
+use crate::shared::isa::{InstructionSet, Operation};
+use crate::shared::locale::tr_with;
 use std::error::Error;
 use std::fmt::{self, Display};
 use std::rc::Rc;
@@ -41,7 +44,7 @@ impl Display for Location {
     }
 }
 
-// The only possible error while generating code, occurs when the user uses an identifier that isn't defined later in the code
+// Occurs when the user uses an identifier that isn't defined later in the code
 #[derive(Debug)]
 pub struct InvalidIdentifier {
     pub identifier: Rc<str>,
@@ -52,8 +55,11 @@ impl Display for InvalidIdentifier {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "Encountered an error during code generation...\nInvalid identifier `{}`",
-            self.identifier
+            "{}",
+            tr_with(
+                "generator-invalid-identifier",
+                &[("identifier", &self.identifier)]
+            )
         )
     }
 }
@@ -61,24 +67,165 @@ impl Display for InvalidIdentifier {
 // Ditto impl Error for InvalidCharacter {} comment
 impl Error for InvalidIdentifier {}
 
-fn get_operand(instruction: &Instruction, program: &Program) -> Result<u8, InvalidIdentifier> {
+// Occurs when an instruction isn't part of the instruction set the program is being assembled for (e.g. LDI in the classic instruction set)
+#[derive(Debug)]
+pub struct UnsupportedInstruction {
+    pub operation: Operation,
+    pub instruction_set: InstructionSet,
+}
+
+// User-friendly error message
+impl Display for UnsupportedInstruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            tr_with(
+                "generator-unsupported-instruction",
+                &[
+                    ("instruction", &Token::from(self.operation)),
+                    ("instruction_set", &self.instruction_set),
+                    ("supported", &self.supported()),
+                ],
+            )
+        )
+    }
+}
+
+// Ditto impl Error for InvalidCharacter {} comment
+impl Error for UnsupportedInstruction {}
+
+// Methods for the UnsupportedInstruction struct
+impl UnsupportedInstruction {
+    // The first instruction set that does have the instruction, so the error can say which one to switch to
+    pub fn supported(&self) -> InstructionSet {
+        InstructionSet::ALL
+            .iter()
+            .copied()
+            .find(|instruction_set| instruction_set.encoding(self.operation).is_some())
+            .unwrap_or(InstructionSet::Extended)
+    }
+}
+
+// Occurs when a program needs more locations than there are in memory
+#[derive(Debug)]
+pub struct ProgramTooLarge {
+    pub length: usize,
+}
+
+// User-friendly error message
+impl Display for ProgramTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            tr_with("generator-program-too-large", &[("length", &self.length)])
+        )
+    }
+}
+
+// Ditto impl Error for InvalidCharacter {} comment
+impl Error for ProgramTooLarge {}
+
+// Occurs when a label is defined after the last location in memory (e.g. on its own at the end of a 100 location program), so it can't be branched to
+#[derive(Debug)]
+pub struct LabelOutOfRange {
+    pub identifier: Rc<str>,
+    pub address: usize,
+}
+
+// User-friendly error message
+impl Display for LabelOutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            tr_with(
+                "generator-label-out-of-range",
+                &[("identifier", &self.identifier), ("address", &self.address)]
+            )
+        )
+    }
+}
+
+// Ditto impl Error for InvalidCharacter {} comment
+impl Error for LabelOutOfRange {}
+
+// The error type for the code generator, composing the errors above
+#[derive(Debug)]
+pub enum GeneratorError {
+    InvalidIdentifier(InvalidIdentifier),
+    UnsupportedInstruction(UnsupportedInstruction),
+    ProgramTooLarge(ProgramTooLarge),
+    LabelOutOfRange(LabelOutOfRange),
+}
+
+impl From<InvalidIdentifier> for GeneratorError {
+    fn from(error: InvalidIdentifier) -> Self {
+        GeneratorError::InvalidIdentifier(error)
+    }
+}
+
+impl From<UnsupportedInstruction> for GeneratorError {
+    fn from(error: UnsupportedInstruction) -> Self {
+        GeneratorError::UnsupportedInstruction(error)
+    }
+}
+
+impl From<ProgramTooLarge> for GeneratorError {
+    fn from(error: ProgramTooLarge) -> Self {
+        GeneratorError::ProgramTooLarge(error)
+    }
+}
+
+impl From<LabelOutOfRange> for GeneratorError {
+    fn from(error: LabelOutOfRange) -> Self {
+        GeneratorError::LabelOutOfRange(error)
+    }
+}
+
+// Show the error being composed
+impl Display for GeneratorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeneratorError::InvalidIdentifier(error) => write!(f, "{error}"),
+            GeneratorError::UnsupportedInstruction(error) => write!(f, "{error}"),
+            GeneratorError::ProgramTooLarge(error) => write!(f, "{error}"),
+            GeneratorError::LabelOutOfRange(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+// Ditto impl Error for InvalidCharacter {} comment
+impl Error for GeneratorError {}
+
+fn get_operand(instruction: &Instruction, program: &Program) -> Result<u8, GeneratorError> {
     match instruction {
         Instruction::Branch(operand)
         | Instruction::BranchZero(operand)
         | Instruction::BranchPositive(operand) => match operand {
             Operand::Number(number) => Ok(*number as u8),
             Operand::Identifier(identifier) => {
-                let label = program.labels.get(identifier).ok_or(InvalidIdentifier {
+                let label = *program.labels.get(identifier).ok_or(InvalidIdentifier {
                     identifier: identifier.to_owned(),
                 })?;
-                Ok(*label)
+
+                // The VM can only run code from the 100 locations in memory
+                if label >= 100 {
+                    Err(LabelOutOfRange {
+                        identifier: Rc::clone(identifier),
+                        address: label as usize,
+                    })?;
+                }
+                Ok(label)
             }
         },
 
         Instruction::Add(operand)
         | Instruction::Sub(operand)
         | Instruction::Store(operand)
-        | Instruction::Load(operand) => match operand {
+        | Instruction::Load(operand)
+        | Instruction::LoadIndirect(operand) => match operand {
             Operand::Number(number) => Ok(*number as u8),
             Operand::Identifier(identifier) => {
                 // Assume there is no identifier
@@ -98,9 +245,9 @@ fn get_operand(instruction: &Instruction, program: &Program) -> Result<u8, Inval
                     });
 
                 // Return the number or bubble up an error
-                number.ok_or(InvalidIdentifier {
+                Ok(number.ok_or(InvalidIdentifier {
                     identifier: Rc::clone(identifier),
-                })
+                })?)
             }
         },
 
@@ -108,38 +255,66 @@ fn get_operand(instruction: &Instruction, program: &Program) -> Result<u8, Inval
     }
 }
 
-// A macro that makes turning instructions into machine code easier, used over a function as macros are expaned at compile time, thus there is no stack overhead
-macro_rules! instruction_location {
-    ($a:expr,$b:expr,$c:expr) => {{ Location::Instruction(InstructionLocation::new($a, get_operand($b, $c)?)) }};
+// Turn an instruction into machine code using the encoding from the instruction set
+fn encode(
+    instruction: &Instruction,
+    program: &Program,
+    instruction_set: InstructionSet,
+) -> Result<Location, GeneratorError> {
+    // Data is never passed in, see the match statement below
+    let operation = instruction.operation().unwrap();
+
+    let mnemonic = instruction_set
+        .encoding(operation)
+        .ok_or(UnsupportedInstruction {
+            operation,
+            instruction_set,
+        })?;
+
+    // Instructions without an address have a fixed operand (e.g. 901 for INP) or an operand of 0 (e.g. 000 for HLT)
+    let operand = match mnemonic.operand {
+        Some(operand) => operand,
+        None if operation.has_operand() => get_operand(instruction, program)?,
+        None => 0,
+    };
+
+    Ok(Location::Instruction(InstructionLocation::new(
+        mnemonic.opcode,
+        operand,
+    )))
 }
 
-// Since code generation requires no attributes, there is no point using a struct and I can take advantage of the Rust standard library traits
+// Since code generation requires no attributes, there is no point using a struct, a plain function is enough
+pub fn generate(
+    program: Program,
+    instruction_set: InstructionSet,
+) -> Result<[Location; 100], GeneratorError> {
+    let mut code = [Location::Data(0); 100];
+
+    // Anything past the 100th location wouldn't be in memory, so labels pointing at it couldn't be used (the analyser also warns about this, so the editor can show where)
+    if program.instructions.len() > 100 {
+        Err(ProgramTooLarge {
+            length: program.instructions.len(),
+        })?;
+    }
+
+    // Use a for loop to avoid dynamic allocations
+    for (i, instruction) in program.instructions.iter().enumerate() {
+        code[i] = match instruction {
+            // Data without a value defaults to 0
+            Instruction::Data(_, number) => Location::Data(number.unwrap_or_default()),
+            _ => encode(instruction, &program, instruction_set)?,
+        };
+    }
+
+    Ok(code)
+}
+
+// Take advantage of the Rust standard library traits to generate code for the default (classic) instruction set
 impl TryFrom<Program> for [Location; 100] {
-    type Error = InvalidIdentifier;
+    type Error = GeneratorError;
 
     fn try_from(program: Program) -> Result<Self, Self::Error> {
-        let mut code = [Location::Data(0); 100];
-
-        // Use a for loop to avoid dynamic allocations
-        for (i, instruction) in program.instructions.iter().enumerate() {
-            let location = match instruction {
-                Instruction::Halt => Location::Instruction(InstructionLocation::new(0, 0)),
-                Instruction::Add(_) => instruction_location!(1, instruction, &program),
-                Instruction::Sub(_) => instruction_location!(2, instruction, &program),
-                Instruction::Store(_) => instruction_location!(3, instruction, &program),
-                // re: code 4, "This code is unused and gives an error."
-                Instruction::Load(_) => instruction_location!(5, instruction, &program),
-                Instruction::Branch(_) => instruction_location!(6, instruction, &program),
-                Instruction::BranchZero(_) => instruction_location!(7, instruction, &program),
-                Instruction::BranchPositive(_) => instruction_location!(8, instruction, &program),
-                Instruction::Input => Location::Instruction(InstructionLocation::new(9, 1)),
-                Instruction::Output => Location::Instruction(InstructionLocation::new(9, 2)),
-                Instruction::Data(_, number) => Location::Data(*number),
-            };
-
-            code[i] = location;
-        }
-
-        Ok(code)
+        generate(program, InstructionSet::default())
     }
 }
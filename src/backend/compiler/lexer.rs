@@ -1,25 +1,35 @@
 use std::error::Error;
 use std::fmt::{self, Display};
+use std::ops::Range;
 use std::rc::Rc;
 
+use crate::shared::isa::{InstructionSet, Operation};
+use crate::shared::locale::{tr, tr_with};
+
 // Define the tokens that the lexer will generate
 #[derive(PartialEq, Clone, Debug)] // Implement the ability to compare two tokens for testing
 pub enum Token {
-    Halt,           // HLT, COB
-    Add,            // ADD
-    Sub,            // SUB
-    Store,          // STA, STO
-    Load,           // LDA
-    Branch,         // BRA
-    BranchZero,     // BRZ
-    BranchPositive, // BRP
-    Input,          // INP
-    Output,         // OUT
-    Data,           // DAT
+    Halt,            // HLT, COB
+    Add,             // ADD
+    Sub,             // SUB
+    Store,           // STA, STO
+    Load,            // LDA
+    LoadIndirect,    // LDI
+    Branch,          // BRA
+    BranchZero,      // BRZ
+    BranchPositive,  // BRP
+    Input,           // INP
+    Output,          // OUT
+    OutputCharacter, // OTC
+    Data,            // DAT
+    Macro,           // MACRO
+    EndMacro,        // ENDM
 
     Number(i16), // A 64-bit or 32-bit unsigned integer (depends on operating system and/or processor architecture)
     Identifier(Rc<str>), // A reference-counted immutable string
 
+    Comment(Rc<str>), // Text after // until the end of the line, kept for the formatter but ignored when assembling
+
     Newline, // A newline (\n or potentially \r\n on windows)
 }
 
@@ -32,43 +42,96 @@ impl Display for Token {
             Token::Sub => "SUB",
             Token::Store => "STA (STO)",
             Token::Load => "LDA",
+            Token::LoadIndirect => "LDI",
             Token::Branch => "BRA",
             Token::BranchZero => "BRZ",
             Token::BranchPositive => "BRP",
             Token::Input => "INP",
             Token::Output => "OUT",
+            Token::OutputCharacter => "OTC",
             Token::Data => "DAT",
+            Token::Macro => "MACRO",
+            Token::EndMacro => "ENDM",
 
             // 0 is used as a placeholder for a number with no value, slightly hacky but users wouldn't use 0 as a number when using the DAT instruction as the default value is already 0
             Token::Number(number) => {
                 if *number == 0 {
-                    "number"
+                    &tr("token-number")
                 } else {
-                    &format!("number `{}`", number)
+                    &tr_with("token-number-value", &[("number", number)])
                 }
             }
 
             // An empty identifier is used as the identifier with no value, this completely works as it is impossible to lex an empty string anyway
             Token::Identifier(identifier) => {
                 if **identifier == *"" {
-                    "identifier"
+                    &tr("token-identifier")
                 } else {
-                    &format!("identifier: `{}`", identifier)
+                    &tr_with("token-identifier-value", &[("identifier", identifier)])
                 }
             }
 
-            Token::Newline => "newline",
+            Token::Comment(_) => &tr("token-comment"),
+            Token::Newline => &tr("token-newline"),
         };
 
         write!(f, "{token}")
     }
 }
 
+// Every operation has a matching token, the instruction set decides which mnemonics are turned into which operation
+impl From<Operation> for Token {
+    fn from(operation: Operation) -> Self {
+        match operation {
+            Operation::Halt => Token::Halt,
+            Operation::Add => Token::Add,
+            Operation::Sub => Token::Sub,
+            Operation::Store => Token::Store,
+            Operation::Load => Token::Load,
+            Operation::LoadIndirect => Token::LoadIndirect,
+            Operation::Branch => Token::Branch,
+            Operation::BranchZero => Token::BranchZero,
+            Operation::BranchPositive => Token::BranchPositive,
+            Operation::Input => Token::Input,
+            Operation::Output => Token::Output,
+            Operation::OutputCharacter => Token::OutputCharacter,
+        }
+    }
+}
+
+impl Token {
+    // The operation an instruction token performs, None for every other token
+    pub fn operation(&self) -> Option<Operation> {
+        Some(match self {
+            Token::Halt => Operation::Halt,
+            Token::Add => Operation::Add,
+            Token::Sub => Operation::Sub,
+            Token::Store => Operation::Store,
+            Token::Load => Operation::Load,
+            Token::LoadIndirect => Operation::LoadIndirect,
+            Token::Branch => Operation::Branch,
+            Token::BranchZero => Operation::BranchZero,
+            Token::BranchPositive => Operation::BranchPositive,
+            Token::Input => Operation::Input,
+            Token::Output => Operation::Output,
+            Token::OutputCharacter => Operation::OutputCharacter,
+            _ => return None,
+        })
+    }
+}
+
+// The byte offsets a token was lexed from (e.g. 4..7 for ADD in "LDA ADD"), used by the language server to point at parts of the source code
+pub type Span = Range<usize>;
+
 // The lexer struct and the attributes associated with it
 pub struct Lexer<'a> {
     source: &'a str,
     position: usize,
     tokens: Vec<Token>,
+    // Where each token in the list of tokens came from
+    spans: Vec<Span>,
+    // Decides which mnemonics are instructions (e.g. OTC is only an instruction in the extended instruction set)
+    instruction_set: InstructionSet,
 }
 
 // The error the lexer will throw
@@ -83,8 +146,15 @@ impl Display for InvalidCharacter {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "Encountered an error while lexing...\nInvalid character `{}` at ({}:{})",
-            self.character, self.line_column.0, self.line_column.1
+            "{}",
+            tr_with(
+                "lexer-invalid-character",
+                &[
+                    ("character", &self.character),
+                    ("line", &self.line_column.0),
+                    ("column", &self.line_column.1),
+                ],
+            )
         )
     }
 }
@@ -95,10 +165,17 @@ impl Error for InvalidCharacter {}
 // Implements the methods associated with the Lexer struct
 impl<'a> Lexer<'a> {
     pub fn new(source: &'a str) -> Self {
+        Self::with_instruction_set(source, InstructionSet::default())
+    }
+
+    // Create a lexer that recognises the mnemonics of a specific instruction set
+    pub fn with_instruction_set(source: &'a str, instruction_set: InstructionSet) -> Self {
         Self {
             source,
             position: 0,
             tokens: Vec::new(),
+            spans: Vec::new(),
+            instruction_set,
         }
     }
 
@@ -116,21 +193,16 @@ impl<'a> Lexer<'a> {
         // Take a string slice of the current position up until the end of the identifier and make it a String struct
         let string: Rc<str> = self.source[self.position..end + self.position].into();
 
-        // Match the input to the correct instruction token, otherwise, create an identifier
+        // Match the input to the correct instruction token using the instruction set, otherwise, create an identifier
         let token = match string.to_uppercase().as_str() {
-            "HLT" | "COB" => Token::Halt,
-            "ADD" => Token::Add,
-            "SUB" => Token::Sub,
-            "STA" | "STO" => Token::Store,
-            "LDA" => Token::Load,
-            "BRA" => Token::Branch,
-            "BRZ" => Token::BranchZero,
-            "BRP" => Token::BranchPositive,
-            "INP" => Token::Input,
-            "OUT" => Token::Output,
             "DAT" => Token::Data,
+            "MACRO" => Token::Macro,
+            "ENDM" => Token::EndMacro,
 
-            _ => Token::Identifier(string),
+            mnemonic => match self.instruction_set.mnemonic(mnemonic) {
+                Some(mnemonic) => mnemonic.operation.into(),
+                None => Token::Identifier(string),
+            },
         };
 
         // Push the token onto the list
@@ -158,13 +230,37 @@ impl<'a> Lexer<'a> {
         self.tokens.push(Token::Number(number.parse().unwrap_or(0)))
     }
 
+    // Add everything after // until the end of the line as a comment (Token::Comment(string)) to the list of tokens
+    fn lex_comment(&mut self) {
+        // Skip past the slashes, then find the end of the line (or the end of the input if there are no more lines)
+        let start = self.position + 2;
+        let end = self.source[start..]
+            .find('\n')
+            .map_or(self.source.len(), |end| start + end);
+
+        self.tokens
+            .push(Token::Comment(self.source[start..end].trim().into()));
+        self.position = end;
+    }
+
     // Take ownership of the struct and loop through the input and turn it into a list of tokens
-    pub fn lex(mut self) -> Result<Vec<Token>, InvalidCharacter> {
+    pub fn lex(self) -> Result<Vec<Token>, InvalidCharacter> {
+        Ok(self
+            .lex_spanned()?
+            .into_iter()
+            .map(|(token, _)| token)
+            .collect())
+    }
+
+    // Ditto lex comment but keeping where each token came from in the source code
+    pub fn lex_spanned(mut self) -> Result<Vec<(Token, Span)>, InvalidCharacter> {
         while self.position < self.source.len() {
             let character = self.source.as_bytes()[self.position] as char;
+            let start = self.position;
 
             /* If the character is the alphabet, call the lex_string method
             If it is a number, call the lex_number method
+            If it is two slashes (//), call the lex_comment method
             If it is a newline (\n), add a newline token and increment the position
             If it is whitespace, increase the position
             Otherwise, there must be an invalid character, bubble up the error */
@@ -172,13 +268,15 @@ impl<'a> Lexer<'a> {
             match character {
                 'A'..='Z' | 'a'..='z' => self.lex_string(),
                 '0'..='9' | '-' => self.lex_number(),
+                '/' if self.source[self.position..].starts_with("//") => self.lex_comment(),
 
                 '\n' => {
                     self.tokens.push(Token::Newline);
                     self.position += 1;
                 }
 
-                ' ' | '\t' => self.position += 1,
+                // Carriage returns are treated as whitespace so files with windows line endings (\r\n) can be lexed
+                ' ' | '\t' | '\r' => self.position += 1,
 
                 _ => {
                     // Calculate the line number by slicing the input from the beginning to the current position and counting the newlines
@@ -194,15 +292,21 @@ impl<'a> Lexer<'a> {
                         - self.source[0..self.position].rfind('\n').unwrap_or(0);
 
                     return Err(InvalidCharacter {
-                        character,
+                        // The whole character rather than its first byte, so characters outside of ASCII (e.g. €) are shown properly
+                        character: self.source[start..].chars().next().unwrap_or(character),
                         // Account for the default 0 value (see previous comment) by returning 1 (as in first character of the column)
                         line_column: (line, if column == 0 { 1 } else { column }),
                     });
                 }
             }
+
+            // Each pass of the loop adds at most one token, so if one was added it came from everything that was just skipped over
+            if self.spans.len() < self.tokens.len() {
+                self.spans.push(start..self.position);
+            }
         }
 
-        // Consume the struct and return the tokens
-        Ok(self.tokens)
+        // Consume the struct and return the tokens alongside their spans
+        Ok(self.tokens.into_iter().zip(self.spans).collect())
     }
 }
@@ -0,0 +1,289 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::rc::Rc;
+
+use crate::backend::compiler::lexer::Token;
+use crate::shared::locale::{tr, tr_with};
+
+// How many macros can be expanded inside of each other before giving up, this stops a macro that uses itself from looping forever (the language server uses the same limit when counting how many locations a macro takes up)
+pub const MAX_DEPTH: usize = 16;
+
+// A macro definition (e.g. MACRO double X ... ENDM), the body is stored as a list of lines without the newline tokens
+#[derive(Debug)]
+struct Macro {
+    parameters: Vec<Rc<str>>,
+    body: Vec<Vec<Token>>,
+}
+
+// The errors that can occur while expanding macros
+#[derive(PartialEq, Debug)]
+pub enum MacroError {
+    // MACRO was not followed by a name (e.g. MACRO 10)
+    MissingName,
+    // A parameter in the definition is not an identifier (e.g. MACRO double 10)
+    InvalidParameter(Rc<Token>),
+    // MACRO was never closed with ENDM
+    Unterminated(Rc<str>),
+    // MACRO inside of another MACRO
+    NestedDefinition(Rc<str>),
+    // ENDM without a matching MACRO
+    UnexpectedEnd,
+    // The macro was used with the wrong amount of arguments
+    ArgumentCount {
+        name: Rc<str>,
+        expected: usize,
+        received: usize,
+    },
+    // Macros expanded inside of each other too many times (most likely a macro using itself)
+    RecursionLimit(Rc<str>),
+}
+
+// User-friendly error messages
+impl Display for MacroError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            MacroError::MissingName => tr("macro-missing-name"),
+            MacroError::InvalidParameter(token) => {
+                tr_with("macro-invalid-parameter", &[("token", token)])
+            }
+            MacroError::Unterminated(name) => tr_with("macro-unterminated", &[("name", name)]),
+            MacroError::NestedDefinition(name) => {
+                tr_with("macro-nested-definition", &[("name", name)])
+            }
+            MacroError::UnexpectedEnd => tr("macro-unexpected-end"),
+            MacroError::ArgumentCount {
+                name,
+                expected,
+                received,
+            } => tr_with(
+                "macro-argument-count",
+                &[
+                    ("name", name),
+                    ("expected", expected),
+                    ("received", received),
+                ],
+            ),
+            MacroError::RecursionLimit(name) => tr_with("macro-recursion-limit", &[("name", name)]),
+        };
+
+        write!(f, "{}", tr_with("macro-error", &[("error", &text)]))
+    }
+}
+
+// Ditto impl Error for InvalidCharacter {} comment
+impl Error for MacroError {}
+
+// Takes the tokens from the lexer, removes macro definitions and replaces every use of a macro with its body
+pub struct MacroExpander {
+    tokens: Vec<Token>,
+    macros: HashMap<Rc<str>, Macro>,
+    // Counts every expansion so labels inside of macros can be given a unique name each time
+    expansions: usize,
+}
+
+// The name a label was written with, labels defined inside of a macro are given a unique name each time it's used (e.g. loop_double0) which isn't in the source code
+pub fn written_name(label: &str) -> &str {
+    // Identifiers are only letters, so an underscore can only come from a renamed label
+    label.split_once('_').map_or(label, |(local, _)| local)
+}
+
+// Checks if a line starts with a label (an identifier followed by an instruction, DAT, or the name of a macro)
+fn defines_label(line: &[Token], macros: &HashMap<Rc<str>, Macro>) -> Option<Rc<str>> {
+    let (Some(Token::Identifier(label)), Some(next)) = (line.first(), line.get(1)) else {
+        return None;
+    };
+
+    match next {
+        Token::Number(_) => None,
+        Token::Identifier(name) if !macros.contains_key(name) => None,
+        _ => Some(Rc::clone(label)),
+    }
+}
+
+impl MacroExpander {
+    pub fn new(mut tokens: Vec<Token>) -> Self {
+        // Remove comments so they aren't mistaken for arguments (e.g. double X // doubles X)
+        tokens.retain(|token| !matches!(token, Token::Comment(_)));
+
+        Self {
+            tokens,
+            macros: HashMap::new(),
+            expansions: 0,
+        }
+    }
+
+    // Store a definition starting at the MACRO line, consuming every line up to and including ENDM
+    fn define(
+        &mut self,
+        header: Vec<Token>,
+        lines: &mut impl Iterator<Item = Vec<Token>>,
+    ) -> Result<(), MacroError> {
+        let mut header = header.into_iter().skip(1);
+
+        let Some(Token::Identifier(name)) = header.next() else {
+            return Err(MacroError::MissingName);
+        };
+
+        // Every token after the name must be a parameter
+        let parameters = header
+            .map(|token| match token {
+                Token::Identifier(parameter) => Ok(parameter),
+                _ => Err(MacroError::InvalidParameter(Rc::new(token))),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut body = Vec::new();
+
+        loop {
+            let Some(line) = lines.next() else {
+                return Err(MacroError::Unterminated(name));
+            };
+
+            match line.first() {
+                Some(Token::EndMacro) => break,
+                Some(Token::Macro) => return Err(MacroError::NestedDefinition(name)),
+                _ => body.push(line),
+            }
+        }
+
+        self.macros.insert(name, Macro { parameters, body });
+        Ok(())
+    }
+
+    // Expand a single line into the output, expanding any macros used by the body of the macro too
+    fn expand_line(
+        &mut self,
+        line: Vec<Token>,
+        depth: usize,
+        output: &mut Vec<Vec<Token>>,
+    ) -> Result<(), MacroError> {
+        // A macro can be used by itself (e.g. double X) or after a label (e.g. start double X)
+        let (label, name, arguments) = match line.as_slice() {
+            [Token::Identifier(name), arguments @ ..] if self.macros.contains_key(name) => {
+                (None, Rc::clone(name), arguments)
+            }
+            [
+                Token::Identifier(label),
+                Token::Identifier(name),
+                arguments @ ..,
+            ] if self.macros.contains_key(name) => {
+                (Some(Rc::clone(label)), Rc::clone(name), arguments)
+            }
+            _ => {
+                output.push(line);
+                return Ok(());
+            }
+        };
+
+        if depth >= MAX_DEPTH {
+            return Err(MacroError::RecursionLimit(name));
+        }
+
+        let definition = &self.macros[&name];
+
+        if arguments.len() != definition.parameters.len() {
+            return Err(MacroError::ArgumentCount {
+                name,
+                expected: definition.parameters.len(),
+                received: arguments.len(),
+            });
+        }
+
+        let expansion = self.expansions;
+        self.expansions += 1;
+
+        // Map every parameter to the argument it was given
+        let mut substitutions: HashMap<Rc<str>, Token> = definition
+            .parameters
+            .iter()
+            .cloned()
+            .zip(arguments.iter().cloned())
+            .collect();
+
+        // Give every label defined in the body a unique name, if the macro was used after a label and the first line of the body has its own label, both names refer to the same location
+        let locals: HashSet<Rc<str>> = definition
+            .body
+            .iter()
+            .filter_map(|line| defines_label(line, &self.macros))
+            .collect();
+
+        let first_label = definition
+            .body
+            .first()
+            .and_then(|line| defines_label(line, &self.macros));
+
+        for local in locals {
+            let renamed = match (&label, &first_label) {
+                (Some(label), Some(first_label)) if *first_label == local => Rc::clone(label),
+                _ => format!("{local}_{name}{expansion}").into(),
+            };
+
+            substitutions.insert(local, Token::Identifier(renamed));
+        }
+
+        let mut body: Vec<Vec<Token>> = definition
+            .body
+            .iter()
+            .map(|line| {
+                line.iter()
+                    .map(|token| match token {
+                        Token::Identifier(identifier) => substitutions
+                            .get(identifier)
+                            .cloned()
+                            .unwrap_or_else(|| token.clone()),
+                        _ => token.clone(),
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // Put the outer label in front of the first line if it doesn't already have one
+        if let Some(label) = label
+            && first_label.is_none()
+        {
+            match body.first_mut() {
+                Some(line) => line.insert(0, Token::Identifier(label)),
+                None => body.push(vec![Token::Identifier(label)]),
+            }
+        }
+
+        for line in body {
+            self.expand_line(line, depth + 1, output)?;
+        }
+
+        Ok(())
+    }
+
+    // Take ownership of the struct and return the tokens with every macro expanded
+    pub fn expand(mut self) -> Result<Vec<Token>, MacroError> {
+        let tokens = std::mem::take(&mut self.tokens);
+
+        // Split the tokens into lines, removing the newline tokens (they are added back at the end)
+        let mut lines = tokens
+            .split(|token| *token == Token::Newline)
+            .map(|line| line.to_vec())
+            .collect::<Vec<_>>()
+            .into_iter();
+
+        // Remove the definitions first so macros can be used before they are defined
+        let mut program = Vec::new();
+
+        while let Some(line) = lines.next() {
+            match line.first() {
+                Some(Token::Macro) => self.define(line, &mut lines)?,
+                Some(Token::EndMacro) => return Err(MacroError::UnexpectedEnd),
+                _ => program.push(line),
+            }
+        }
+
+        let mut output = Vec::new();
+
+        for line in program {
+            self.expand_line(line, 0, &mut output)?;
+        }
+
+        // Join the lines back together with newline tokens
+        Ok(output.join(&Token::Newline))
+    }
+}
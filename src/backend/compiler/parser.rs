@@ -4,6 +4,9 @@ use std::fmt::{self, Display};
 use std::rc::Rc;
 
 use crate::backend::compiler::lexer::Token;
+use crate::backend::compiler::macros;
+use crate::shared::isa::{InstructionSet, Operation};
+use crate::shared::locale::{tr, tr_with};
 
 // Create the enum that defines if an instruction has a number or an identifier as the operand (e.g. LDA 10 would be a number while LDA ONE would be an identifier)
 #[derive(PartialEq, Debug)]
@@ -15,17 +18,40 @@ pub enum Operand {
 // Create the Instruction enum, similar to the Token enum, but bundling together the opcode and operand(s)
 #[derive(PartialEq, Debug)]
 pub enum Instruction {
-    Halt,                    // HLT
-    Add(Operand),            // ADD X
-    Sub(Operand),            // SUB X
-    Store(Operand),          // STA/STO X
-    Load(Operand),           // LDA X
-    Branch(Operand),         // BRA X
-    BranchZero(Operand),     // BRZ X
-    BranchPositive(Operand), // BRZ X
-    Input,                   // INP
-    Output,                  // OUT
-    Data(Rc<str>, i16),      // X DAT Y
+    Halt,                       // HLT
+    Add(Operand),               // ADD X
+    Sub(Operand),               // SUB X
+    Store(Operand),             // STA/STO X
+    Load(Operand),              // LDA X
+    LoadIndirect(Operand),      // LDI X
+    Branch(Operand),            // BRA X
+    BranchZero(Operand),        // BRZ X
+    BranchPositive(Operand),    // BRZ X
+    Input,                      // INP
+    Output,                     // OUT
+    OutputCharacter,            // OTC
+    Data(Rc<str>, Option<i16>), // X DAT Y, None if no value is given (i.e. X DAT)
+}
+
+impl Instruction {
+    // The operation the instruction performs when it is run, None for data as it is never run
+    pub fn operation(&self) -> Option<Operation> {
+        Some(match self {
+            Instruction::Halt => Operation::Halt,
+            Instruction::Add(_) => Operation::Add,
+            Instruction::Sub(_) => Operation::Sub,
+            Instruction::Store(_) => Operation::Store,
+            Instruction::Load(_) => Operation::Load,
+            Instruction::LoadIndirect(_) => Operation::LoadIndirect,
+            Instruction::Branch(_) => Operation::Branch,
+            Instruction::BranchZero(_) => Operation::BranchZero,
+            Instruction::BranchPositive(_) => Operation::BranchPositive,
+            Instruction::Input => Operation::Input,
+            Instruction::Output => Operation::Output,
+            Instruction::OutputCharacter => Operation::OutputCharacter,
+            Instruction::Data(_, _) => return None,
+        })
+    }
 }
 
 // This struct bundles together the instructions that will be returned from the parsing process and the labels that were defined in the program, useful when checking if an identifier exists later in the compilation process.
@@ -35,11 +61,60 @@ pub struct Program {
     pub instructions: Vec<Instruction>,
 }
 
+// What a label refers to, used for the outline of a program in the editor pane
+#[derive(PartialEq, Debug)]
+pub enum SymbolKind {
+    // A label before an instruction (e.g. loop in loop LDA X)
+    Code,
+    // A label before a data location (e.g. X in X DAT 5), None if it has no value
+    Data(Option<i16>),
+}
+
+// A label and the address it refers to
+#[derive(PartialEq, Debug)]
+pub struct Symbol {
+    pub name: Rc<str>,
+    pub address: u8,
+    pub kind: SymbolKind,
+}
+
+impl Program {
+    // Every label in the program sorted by address, code labels come from the list of labels and data labels come from DAT instructions (labels from inside of macros are given the name they were written with)
+    pub fn symbols(&self) -> Vec<Symbol> {
+        let mut symbols: Vec<Symbol> = self
+            .labels
+            .iter()
+            .map(|(name, address)| Symbol {
+                name: macros::written_name(name).into(),
+                address: *address,
+                kind: SymbolKind::Code,
+            })
+            .chain(
+                self.instructions.iter().enumerate().filter_map(
+                    |(i, instruction)| match instruction {
+                        Instruction::Data(name, value) => Some(Symbol {
+                            name: macros::written_name(name).into(),
+                            address: i as u8,
+                            kind: SymbolKind::Data(*value),
+                        }),
+                        _ => None,
+                    },
+                ),
+            )
+            .collect();
+
+        // Labels at the same address are sorted by name so the order doesn't change every time the program is parsed
+        symbols.sort_by(|a, b| a.address.cmp(&b.address).then(a.name.cmp(&b.name)));
+        symbols
+    }
+}
+
 // The struct with the attributes needed to turn tokens into instructions
 #[derive(Debug)]
 pub struct Parser {
     tokens: Vec<Rc<Token>>,
     position: usize,
+    instruction_set: InstructionSet,
     program: Program,
 }
 
@@ -55,32 +130,45 @@ pub struct InvalidToken {
 impl Display for InvalidToken {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let expected = match self.expected.len() {
-            0 => "Expected nothing".to_string(),
-            1 => format!("Expected {}", self.expected[0]),
-
-            _ => format!(
-                "Expected: {}",
-                self.expected
-                    .iter()
-                    .enumerate()
-                    // This is a fancy functional way of looping and combining a result from the previous iteration, easily turning a list of results into plain english
-                    .fold(String::new(), |acc, (i, token)| if i == 0 {
-                        token.to_string()
-                    } else if i == self.expected.len() - 1 {
-                        format!("{acc} or {token}")
-                    } else {
-                        format!("{acc}, {token}")
-                    })
+            0 => tr("parser-expected-nothing"),
+            1 => tr_with("parser-expected", &[("token", &self.expected[0])]),
+
+            _ => tr_with(
+                "parser-expected-list",
+                &[(
+                    "tokens",
+                    &self
+                        .expected
+                        .iter()
+                        .enumerate()
+                        // This is a fancy functional way of looping and combining a result from the previous iteration, easily turning a list of results into plain english (or whichever language is being shown)
+                        .fold(String::new(), |acc, (i, token)| {
+                            if i == 0 {
+                                token.to_string()
+                            } else if i == self.expected.len() - 1 {
+                                tr_with("list-or", &[("list", &acc), ("last", token)])
+                            } else {
+                                format!("{acc}, {token}")
+                            }
+                        }),
+                )],
             ),
         };
 
         let received = if let Some(token) = &self.received {
             token.to_string()
         } else {
-            "nothing".to_string()
+            tr("parser-nothing")
         };
 
-        write!(f, "{expected}, received {received}")
+        write!(
+            f,
+            "{}",
+            tr_with(
+                "parser-received",
+                &[("expected", &expected), ("received", &received)]
+            )
+        )
     }
 }
 
@@ -101,44 +189,37 @@ impl Display for ParserError {
         let text = match self {
             ParserError::InvalidToken(invalid_token) => invalid_token.to_string(),
             ParserError::NumberOutOfRange(number) => {
-                format!(
-                    "Number `{number}` out of range, expected a number between -999 and 999 inclusive"
-                )
+                tr_with("number-out-of-range", &[("number", number)])
             }
             ParserError::AddressOutOfRange(address) => {
-                format!(
-                    "Address `{address}` out of range, expected a number between 0 and 100 exclusive"
-                )
+                tr_with("address-out-of-range", &[("address", address)])
             }
         };
 
-        write!(f, "Encountered an error while parsing...\n{text}")
+        write!(f, "{}", tr_with("parser-error", &[("error", &text)]))
     }
 }
 
 // Ditto impl Error for InvalidCharacter {} comment
 impl Error for ParserError {}
 
-// A reusable list of all the instructions, useful for avoiding repetition when bubbling up errors
-const INSTRUCTIONS: [Token; 10] = [
-    Token::Halt,
-    Token::Add,
-    Token::Sub,
-    Token::Store,
-    Token::Load,
-    Token::Branch,
-    Token::BranchZero,
-    Token::BranchPositive,
-    Token::Input,
-    Token::Output,
-];
-
 // Methods for the Parser struct
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Self {
+        Self::with_instruction_set(tokens, InstructionSet::default())
+    }
+
+    // Create a parser that only lists the instructions of a specific instruction set in its errors
+    pub fn with_instruction_set(tokens: Vec<Token>, instruction_set: InstructionSet) -> Self {
         Self {
-            tokens: tokens.into_iter().map(Rc::new).collect(),
+            // Comments are only kept for the formatter, so they can be removed here
+            tokens: tokens
+                .into_iter()
+                .filter(|token| !matches!(token, Token::Comment(_)))
+                .map(Rc::new)
+                .collect(),
             position: 0,
+            instruction_set,
             program: Program {
                 labels: HashMap::new(),
                 instructions: Vec::new(),
@@ -146,6 +227,15 @@ impl Parser {
         }
     }
 
+    // A list of the instructions in the instruction set, useful for avoiding repetition when bubbling up errors
+    fn instructions(&self) -> Vec<Token> {
+        Operation::ALL
+            .iter()
+            .filter(|operation| self.instruction_set.encoding(**operation).is_some())
+            .map(|operation| Token::from(*operation))
+            .collect()
+    }
+
     // A helper function that will bubble up an error if the current token isn't a newline
     fn expect_newline(&mut self) -> Result<(), ParserError> {
         if let Some(token) = self.tokens.get(self.position) {
@@ -160,7 +250,7 @@ impl Parser {
         Ok(())
     }
 
-    // Parse an instruction where no operands are expected (i.e. HLT/COB, INP, COB)
+    // Parse an instruction where no operands are expected (i.e. HLT/COB, INP, OUT, OTC)
     fn parse_no_operand(&mut self) -> Result<(), ParserError> {
         let token = &self.tokens[self.position];
 
@@ -168,6 +258,7 @@ impl Parser {
             Token::Halt => Instruction::Halt,
             Token::Input => Instruction::Input,
             Token::Output => Instruction::Output,
+            Token::OutputCharacter => Instruction::OutputCharacter,
             _ => unreachable!(),
         };
 
@@ -179,7 +270,7 @@ impl Parser {
         Ok(())
     }
 
-    // Parse an instruction where one operand is expected (i.e. every instruction but HLT/COB, INP, COB, and DAT)
+    // Parse an instruction where one operand is expected (i.e. every instruction but HLT/COB, INP, OUT, OTC, and DAT)
     fn parse_single_operand(&mut self) -> Result<(), ParserError> {
         let token = &self.tokens[self.position];
 
@@ -212,6 +303,7 @@ impl Parser {
                     Token::Sub => Instruction::Sub(operand),
                     Token::Store => Instruction::Store(operand),
                     Token::Load => Instruction::Load(operand),
+                    Token::LoadIndirect => Instruction::LoadIndirect(operand),
                     Token::Branch => Instruction::Branch(operand),
                     Token::BranchZero => Instruction::BranchZero(operand),
                     Token::BranchPositive => Instruction::BranchPositive(operand),
@@ -242,7 +334,7 @@ impl Parser {
             .get(self.position + 1)
             .ok_or(ParserError::InvalidToken(InvalidToken {
                 expected: {
-                    let mut expected = INSTRUCTIONS.to_vec();
+                    let mut expected = self.instructions();
                     expected.push(Token::Data);
                     expected
                 },
@@ -263,7 +355,7 @@ impl Parser {
                             }
                             self.program
                                 .instructions
-                                .push(Instruction::Data(identifier, *number));
+                                .push(Instruction::Data(identifier, Some(*number)));
                             self.position += 3;
                         }
 
@@ -271,7 +363,7 @@ impl Parser {
                         Token::Newline => {
                             self.program
                                 .instructions
-                                .push(Instruction::Data(identifier, 0));
+                                .push(Instruction::Data(identifier, None));
                             self.position += 2;
                         }
 
@@ -284,7 +376,7 @@ impl Parser {
                 } else {
                     self.program
                         .instructions
-                        .push(Instruction::Data(identifier, 0));
+                        .push(Instruction::Data(identifier, None));
                     self.position += 2;
                 }
             }
@@ -292,7 +384,7 @@ impl Parser {
             // If the next token is an instruction operand (e.g. A B, A 10, etc.) that breaks the rules of the program so throw an error
             Token::Identifier(_) | Token::Number(_) => {
                 Err(ParserError::InvalidToken(InvalidToken {
-                    expected: INSTRUCTIONS.to_vec(),
+                    expected: self.instructions(),
                     received: Some(self.tokens.swap_remove(self.position)),
                 }))?
             }
@@ -300,7 +392,7 @@ impl Parser {
             // If the next token is a newline, the identifier is by itself (e.g. A) which makes no logical sense in an LMC program so throw an error
             Token::Newline => Err(ParserError::InvalidToken(InvalidToken {
                 expected: {
-                    let mut expected = INSTRUCTIONS.to_vec();
+                    let mut expected = self.instructions();
                     expected.push(Token::Data);
                     expected
                 },
@@ -330,17 +422,20 @@ impl Parser {
                 | Token::Sub
                 | Token::Store
                 | Token::Load
+                | Token::LoadIndirect
                 | Token::Branch
                 | Token::BranchZero
                 | Token::BranchPositive => self.parse_single_operand()?,
 
-                Token::Halt | Token::Input | Token::Output => self.parse_no_operand()?,
+                Token::Halt | Token::Input | Token::Output | Token::OutputCharacter => {
+                    self.parse_no_operand()?
+                }
                 Token::Identifier(identifier) => self.parse_identifier(Rc::clone(identifier))?,
 
                 Token::Newline => self.position += 1,
 
                 _ => Err(ParserError::InvalidToken(InvalidToken {
-                    expected: INSTRUCTIONS.to_vec(),
+                    expected: self.instructions(),
                     received: Some(self.tokens.swap_remove(self.position)),
                 }))?,
             }
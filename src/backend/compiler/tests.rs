@@ -2,10 +2,19 @@ use std::collections::HashMap;
 
 use crate::backend::compiler::{
     self,
-    generator::{InstructionLocation, Location},
+    analysis::Warning,
+    formatter::{self, AliasStyle},
+    generator::{
+        self, GeneratorError, InstructionLocation, LabelOutOfRange, Location, ProgramTooLarge,
+        UnsupportedInstruction,
+    },
     lexer::{InvalidCharacter, Lexer, Token},
-    parser::{Instruction, InvalidToken, Operand, Parser, ParserError, Program},
+    macros::{MacroError, MacroExpander},
+    parser::{
+        Instruction, InvalidToken, Operand, Parser, ParserError, Program, Symbol, SymbolKind,
+    },
 };
+use crate::shared::isa::{InstructionSet, Operation};
 
 #[test]
 // 1.1.1
@@ -60,6 +69,23 @@ fn lexer_invalid_chararcter() {
     );
 }
 
+#[test]
+// 1.1.3
+fn lexer_comments() {
+    let source = "// Output a number\nOUT // Print\n";
+
+    assert_eq!(
+        Lexer::new(source).lex(),
+        Ok(vec![
+            Token::Comment("Output a number".into()),
+            Token::Newline,
+            Token::Output,
+            Token::Comment("Print".into()),
+            Token::Newline
+        ])
+    );
+}
+
 #[test]
 //1.2.1
 fn parser_all_instructions() {
@@ -100,8 +126,8 @@ fn parser_all_instructions() {
                 Instruction::BranchPositive(Operand::Identifier("yzab".into())),
                 Instruction::Input,
                 Instruction::Output,
-                Instruction::Data("ABYZ".into(), 19),
-                Instruction::Data("YZAB".into(), 0),
+                Instruction::Data("ABYZ".into(), Some(19)),
+                Instruction::Data("YZAB".into(), None),
             ],
         })
     );
@@ -157,6 +183,60 @@ fn parser_number_out_of_range() {
     );
 }
 
+#[test]
+// 1.2.6
+fn parser_symbols() {
+    let source = r#"INP
+        loop SUB ONE
+        BRP loop
+        HLT
+        ONE DAT 1
+        RESULT DAT
+        "#;
+
+    assert_eq!(
+        Parser::new(Lexer::new(source).lex().unwrap())
+            .parse()
+            .unwrap()
+            .symbols(),
+        vec![
+            Symbol {
+                name: "loop".into(),
+                address: 1,
+                kind: SymbolKind::Code
+            },
+            Symbol {
+                name: "ONE".into(),
+                address: 4,
+                kind: SymbolKind::Data(Some(1))
+            },
+            Symbol {
+                name: "RESULT".into(),
+                address: 5,
+                kind: SymbolKind::Data(None)
+            },
+        ]
+    );
+
+    // Labels inside of a macro keep the name they were written with every time it's used
+    let source = r#"MACRO wait
+        loop BRA loop
+        ENDM
+        wait
+        wait
+        "#;
+
+    assert_eq!(
+        compiler::parse(source, InstructionSet::Classic)
+            .unwrap()
+            .symbols()
+            .into_iter()
+            .map(|symbol| (symbol.name.to_string(), symbol.address))
+            .collect::<Vec<_>>(),
+        vec![("loop".to_string(), 0), ("loop".to_string(), 1)]
+    );
+}
+
 #[test]
 //1.3.1
 fn generator_all_instructions() {
@@ -211,3 +291,299 @@ fn generator_all_instructions() {
         locations
     });
 }
+
+#[test]
+// 1.3.2
+fn generator_memory_limit() {
+    let halts = |count| "HLT\n".repeat(count);
+
+    // 101 locations, with the data the program reads in the last one
+    assert!(matches!(
+        generator::generate(
+            compiler::parse(
+                &format!("LDA X\nOUT\n{}X DAT 5", halts(98)),
+                InstructionSet::Classic
+            )
+            .unwrap(),
+            InstructionSet::Classic
+        ),
+        Err(GeneratorError::ProgramTooLarge(ProgramTooLarge {
+            length: 101
+        }))
+    ));
+    assert!(compiler::compile(&format!("BRA X\n{}X HLT", halts(99))).is_err());
+
+    // A label past the last location in memory
+    assert!(matches!(
+        generator::generate(
+            Program {
+                labels: HashMap::from([("X".into(), 100)]),
+                instructions: vec![Instruction::Branch(Operand::Identifier("X".into()))],
+            },
+            InstructionSet::Classic
+        ),
+        Err(GeneratorError::LabelOutOfRange(LabelOutOfRange {
+            address: 100,
+            ..
+        }))
+    ));
+
+    // Exactly 100 locations still fit
+    assert!(compiler::compile(&format!("BRA X\n{}X HLT", halts(98))).is_ok());
+}
+
+#[test]
+// 1.4.1
+fn macro_expansion() {
+    let source = r#"MACRO double X
+        loop LDA X
+        ADD X
+        BRA loop
+        ENDM
+        double A
+        start double B
+        HLT
+        "#;
+
+    let tokens = MacroExpander::new(Lexer::new(source).lex().unwrap())
+        .expand()
+        .unwrap();
+
+    assert_eq!(
+        Parser::new(tokens).parse().unwrap().instructions,
+        vec![
+            Instruction::Load(Operand::Identifier("A".into())),
+            Instruction::Add(Operand::Identifier("A".into())),
+            Instruction::Branch(Operand::Identifier("loop_double0".into())),
+            Instruction::Load(Operand::Identifier("B".into())),
+            Instruction::Add(Operand::Identifier("B".into())),
+            // The first label of the body takes the name of the label before the macro
+            Instruction::Branch(Operand::Identifier("start".into())),
+            Instruction::Halt,
+        ]
+    );
+}
+
+#[test]
+// 1.4.2
+fn macro_argument_count() {
+    let source = "MACRO double X\nADD X\nENDM\ndouble A B";
+
+    assert_eq!(
+        MacroExpander::new(Lexer::new(source).lex().unwrap()).expand(),
+        Err(MacroError::ArgumentCount {
+            name: "double".into(),
+            expected: 1,
+            received: 2
+        })
+    );
+}
+
+#[test]
+// 1.4.3
+fn macro_unterminated() {
+    let source = "MACRO double X\nADD X\n";
+
+    assert_eq!(
+        MacroExpander::new(Lexer::new(source).lex().unwrap()).expand(),
+        Err(MacroError::Unterminated("double".into()))
+    );
+}
+
+#[test]
+// 1.5.1
+fn instruction_set_mnemonics() {
+    let source = "LDI OTC COB";
+
+    // Extended mnemonics are only instructions in the extended instruction set
+    assert_eq!(
+        Lexer::new(source).lex(),
+        Ok(vec![
+            Token::Identifier("LDI".into()),
+            Token::Identifier("OTC".into()),
+            Token::Halt
+        ])
+    );
+
+    assert_eq!(
+        Lexer::with_instruction_set(source, InstructionSet::Extended).lex(),
+        Ok(vec![
+            Token::LoadIndirect,
+            Token::OutputCharacter,
+            Token::Halt
+        ])
+    );
+
+    // Aliases are not allowed in the exam board instruction set
+    assert_eq!(
+        Lexer::with_instruction_set(source, InstructionSet::ExamBoard).lex(),
+        Ok(vec![
+            Token::Identifier("LDI".into()),
+            Token::Identifier("OTC".into()),
+            Token::Identifier("COB".into())
+        ])
+    );
+}
+
+#[test]
+// 1.5.2
+fn instruction_set_encoding() {
+    let source = r#"LDI ABYZ
+        OTC
+        HLT
+        ABYZ DAT 3
+        "#;
+
+    assert_eq!(
+        compiler::compile_with(source, InstructionSet::Extended).unwrap()[0..4],
+        [
+            Location::Instruction(InstructionLocation::new(4, 3)),
+            Location::Instruction(InstructionLocation::new(9, 22)),
+            Location::Instruction(InstructionLocation::new(0, 0)),
+            Location::Data(3),
+        ]
+    );
+}
+
+#[test]
+// 1.5.3
+fn instruction_set_disassemble() {
+    let name = |instruction_set: InstructionSet, opcode, operand| {
+        instruction_set
+            .disassemble(opcode, operand)
+            .map(|mnemonic| mnemonic.name)
+    };
+
+    assert_eq!(name(InstructionSet::Classic, 5, 3), Some("LDA"));
+    assert_eq!(name(InstructionSet::Classic, 9, 2), Some("OUT"));
+    // Aliases are never used when disassembling
+    assert_eq!(name(InstructionSet::Classic, 0, 0), Some("HLT"));
+    assert_eq!(name(InstructionSet::Classic, 4, 3), None);
+    assert_eq!(name(InstructionSet::Extended, 4, 3), Some("LDI"));
+}
+
+#[test]
+// 1.5.4
+fn instruction_set_parser_errors() {
+    let source = "LOOP 5";
+    let expected = |instruction_set| {
+        let tokens = Lexer::with_instruction_set(source, instruction_set)
+            .lex()
+            .unwrap();
+
+        match Parser::with_instruction_set(tokens, instruction_set).parse() {
+            Err(ParserError::InvalidToken(InvalidToken { expected, .. })) => expected,
+            result => panic!("expected an invalid token, got {result:?}"),
+        }
+    };
+
+    // Errors only list the instructions that are part of the instruction set
+    assert!(!expected(InstructionSet::Classic).contains(&Token::OutputCharacter));
+    assert!(expected(InstructionSet::Extended).contains(&Token::LoadIndirect));
+    assert!(expected(InstructionSet::Extended).contains(&Token::OutputCharacter));
+}
+
+#[test]
+// 1.5.5
+fn instruction_set_unsupported_instruction() {
+    // An instruction from another instruction set is reported instead of the parser error it causes
+    let error = compiler::compile("INP\nOTC\nHLT").unwrap_err();
+    let error = error.downcast_ref::<UnsupportedInstruction>().unwrap();
+
+    assert_eq!(error.operation, Operation::OutputCharacter);
+    assert_eq!(error.instruction_set, InstructionSet::Classic);
+    assert_eq!(error.supported(), InstructionSet::Extended);
+
+    // Mnemonics from other instruction sets can still be used as labels
+    assert!(compiler::compile("LDA OTC\nHLT\nOTC DAT 5").is_ok());
+}
+
+#[test]
+// 1.6.1
+fn analysis_no_warnings() {
+    let source = r#"INP
+        loop SUB ONE
+        BRP loop
+        STA RESULT
+        OUT
+        HLT
+        ONE DAT 1
+        RESULT DAT
+        "#;
+
+    assert_eq!(
+        compiler::warnings(source, InstructionSet::Classic).unwrap(),
+        vec![]
+    );
+}
+
+#[test]
+// 1.6.2
+fn analysis_warnings() {
+    let source = r#"LDA COUNT
+        BRA COUNT
+        OUT
+        loop ADD loop
+        COUNT DAT
+        "#;
+
+    assert_eq!(
+        compiler::warnings(source, InstructionSet::Classic).unwrap(),
+        vec![
+            Warning::LabelAsCodeAndData("loop".into()),
+            Warning::BranchToData("COUNT".into()),
+            Warning::UninitialisedData("COUNT".into()),
+            Warning::UnreachableCode(2),
+        ]
+    );
+
+    // Falling through into data
+    assert_eq!(
+        compiler::warnings("INP\nOUT\nX DAT 5", InstructionSet::Classic).unwrap(),
+        vec![Warning::MissingHalt(2)]
+    );
+
+    // Nothing else is checked once the program is too large for memory
+    let source = format!("BRA END\n{}END OUT\nX DAT 5", "HLT\n".repeat(299));
+
+    assert_eq!(
+        compiler::warnings(&source, InstructionSet::Classic).unwrap(),
+        vec![Warning::ProgramTooLarge(302)]
+    );
+}
+
+#[test]
+// 1.7.1
+fn formatter_columns() {
+    let source = r#"// Count down from the input
+  inp
+loop    sub one // Take one away
+ brp   loop
+cob
+one dat   1
+
+
+
+"#;
+
+    assert_eq!(
+        formatter::format(source, InstructionSet::Classic, AliasStyle::Standard).unwrap(),
+        r#"// Count down from the input
+     INP
+loop SUB one  // Take one away
+     BRP loop
+     HLT
+one  DAT 1
+"#
+    );
+
+    assert_eq!(
+        formatter::format(
+            "HLT\nSTA X",
+            InstructionSet::Classic,
+            AliasStyle::Alternative
+        )
+        .unwrap(),
+        "COB\nSTO X\n"
+    );
+}
@@ -3,27 +3,69 @@ use std::time::Duration;
 use serde::{Deserialize, Serialize};
 use tokio::{fs::File, io::AsyncWriteExt};
 
+use crate::backend::compiler::formatter::AliasStyle;
+use crate::backend::{keymap::Keymap, paths};
 use crate::frontend::{screen::settings, util::theme::Theme};
+use crate::shared::isa::InstructionSet;
+use crate::shared::locale::{Language, tr_with};
 
 use std::{
-    env, fs,
-    io::{self, Write},
-    path::PathBuf,
-    str::FromStr,
+    error::Error,
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
 };
 
-// Config path for unix-like operating systems, appended to home directory
-#[cfg(any(
-    target_os = "linux",
-    target_os = "openbsd",
-    target_os = "freebsd",
-    target_os = "netbsd",
-    target_os = "dragonfly"
-))]
-pub const CONFIG_PATH: &str = ".config/algor/config.toml";
+// The version of the layout of config files, bumped whenever a key is renamed or its meaning changes (adding a key with a default doesn't need a new version)
+pub const CONFIG_VERSION: u32 = 1;
 
-#[cfg(target_os = "windows")]
-pub const CONFIG_PATH: &str = "AppData\\Roaming\\algor\\config.toml";
+/* Steps to bring a config file up to date, the step at index n turns a version n file into a version n + 1 file
+Version 0 files (from before the version key) only lack keys, which are filled in with defaults, so there is nothing to change */
+const MIGRATIONS: [fn(&mut toml::Table); CONFIG_VERSION as usize] = [|_| {}];
+
+// Possible errors while loading or saving the config
+#[derive(Debug)]
+pub enum ConfigError {
+    // There is no home directory to keep the config in
+    NoHomeDirectory,
+    // Reading or writing a file or directory failed
+    Io(PathBuf, io::Error),
+    // The config file isn't valid TOML or a key has a value of the wrong type
+    Parse(toml::de::Error),
+    // The config couldn't be turned into TOML to be written
+    Serialize(toml::ser::Error),
+    // The config file was saved by a newer version of algor
+    UnsupportedVersion(u32),
+    // Ditto for any of the above but with the system config, which the user can't fix themselves
+    System(PathBuf, Box<ConfigError>),
+}
+
+impl Error for ConfigError {}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            ConfigError::NoHomeDirectory => tr_with(
+                "config-no-home-directory",
+                &[("variable", &paths::CONFIG_VAR)],
+            ),
+            ConfigError::Io(path, e) => {
+                tr_with("config-io", &[("path", &path.display()), ("error", e)])
+            }
+            ConfigError::Parse(e) => tr_with("config-parse", &[("error", e)]),
+            ConfigError::Serialize(e) => tr_with("config-serialize", &[("error", e)]),
+            ConfigError::UnsupportedVersion(version) => tr_with(
+                "config-unsupported-version",
+                &[("version", version), ("supported", &CONFIG_VERSION)],
+            ),
+            ConfigError::System(path, e) => {
+                tr_with("config-system", &[("path", &path.display()), ("error", e)])
+            }
+        };
+
+        write!(f, "{text}")
+    }
+}
 
 /* Implement Deserialize and Serialize for saving and loading from files
 Implement Default for selecting the default run speed if no config file exists
@@ -48,22 +90,34 @@ impl From<RunSpeed> for Duration {
     }
 }
 
-// Facilitate deserialising (turning into struct from a file) and serialising (turning the struct into a file), this is required by the toml library
-#[derive(Clone, Debug, Deserialize, Serialize)]
+/* Facilitate deserialising (turning into struct from a file) and serialising (turning the struct into a file), this is required by the toml library
+Any key missing from the file takes its value from the default config, so config files saved before a setting existed still load */
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
 pub struct Config {
+    pub version: u32,
     pub theme: Theme,
     pub editor_font_size: u32,
     pub lessons_directory: String,
     pub run_speed: RunSpeed,
+    pub instruction_set: InstructionSet,
+    pub format_aliases: AliasStyle,
+    pub keymap: Keymap,
+    pub language: Language,
 }
 
 impl From<&mut settings::State> for Config {
     fn from(value: &mut settings::State) -> Self {
         Self {
+            version: CONFIG_VERSION,
             theme: value.theme.clone(),
             editor_font_size: value.editor_font_size,
             lessons_directory: value.lessons_directory.clone(),
             run_speed: value.run_speed.unwrap_or_default(),
+            instruction_set: value.instruction_set,
+            format_aliases: value.format_aliases,
+            keymap: value.keymap.clone(),
+            language: value.language,
         }
     }
 }
@@ -71,124 +125,299 @@ impl From<&mut settings::State> for Config {
 impl From<settings::State> for Config {
     fn from(value: settings::State) -> Self {
         Self {
+            version: CONFIG_VERSION,
             theme: value.theme,
             editor_font_size: value.editor_font_size,
             lessons_directory: value.lessons_directory,
             run_speed: value.run_speed.unwrap_or_default(),
+            instruction_set: value.instruction_set,
+            format_aliases: value.format_aliases,
+            keymap: value.keymap,
+            language: value.language,
         }
     }
 }
 
 impl Config {
     // Let the user save their config file to disk
-    pub async fn save(self, path: PathBuf) -> Result<(), io::Error> {
-        let Ok(mut file) = File::create(&path).await else {
-            panic!("Failed to create config file")
-        };
-
-        // Use the toml library to turn the config into a string
-        let config = match toml::to_string(&self) {
-            Ok(config) => config,
-            Err(_error) => match toml::to_string(&Config::default()) {
-                Ok(config) => config,
-                Err(_e) => unreachable!(),
-            },
-        };
+    pub async fn save(self, path: PathBuf) -> Result<(), ConfigError> {
+        // Use the toml library to turn the config into a string, return an error otherwise
+        let config = toml::to_string(&self).map_err(ConfigError::Serialize)?;
 
         // Write string to disk, return an error otherwise
-        file.write_all(config.as_bytes()).await?;
-        Ok(())
+        let mut file = File::create(&path)
+            .await
+            .map_err(|e| ConfigError::Io(path.clone(), e))?;
+        file.write_all(config.as_bytes())
+            .await
+            .map_err(|e| ConfigError::Io(path, e))
     }
-}
 
-// Implement the ability to create a default config (done in the frontend)
-impl Default for Config {
-    fn default() -> Self {
-        // If the user doesn't have a home directory (e.g. "~") on Linux, then there is a serious problem and the program cannot continue
-        let mut algor_dir = match env::home_dir().ok_or(std::io::Error::new(
-            io::ErrorKind::NotFound,
-            "No home directory",
-        )) {
-            Ok(algor_dir) => algor_dir,
-            Err(e) => panic!("{}", e),
-        };
+    // Turn the text of a config file into a config on top of the system config, migrating it first if it was saved by an older version (also returning whether it was migrated)
+    pub fn parse(file: &str, system: &SystemConfig) -> Result<(Self, bool), ConfigError> {
+        let mut table = toml::from_str::<toml::Table>(file).map_err(ConfigError::Parse)?;
 
-        // Add Documents/algor to the user's home directory (i.e. "~/Documents/algor")
-        algor_dir.push("Documents");
-        algor_dir.push("algor");
+        // Files from before the version key are version 0
+        let version = table
+            .get("version")
+            .and_then(toml::Value::as_integer)
+            .unwrap_or(0);
+        let version = u32::try_from(version).unwrap_or(0);
 
-        // Create the default lessons directory if it doesn't exist
-        if !algor_dir.exists()
-            && let Err(e) = fs::create_dir_all(&algor_dir)
-        {
-            // If the program lacks permissions or the computer lacks storage, etc. exit the program with error information
-            panic!("Failed to create default lessons directory, {e}");
+        if version > CONFIG_VERSION {
+            return Err(ConfigError::UnsupportedVersion(version));
         }
 
-        // Exit the program if the directory cannot be converted into a string
-        let Some(lessons_directory) = algor_dir.to_str() else {
-            panic!("Failed to convert path to string, path possibly contains invalid unicode")
-        };
+        for migration in &MIGRATIONS[version as usize..] {
+            migration(&mut table);
+        }
+        // Start from the system config, replacing anything the user has set unless it is locked
+        let mut merged = system.defaults.clone();
+        for (key, value) in table {
+            if !system.policy.is_locked(&key) {
+                merged.insert(key, value);
+            }
+        }
+        merged.insert("version".into(), i64::from(CONFIG_VERSION).into());
+
+        let config = toml::Value::Table(merged)
+            .try_into()
+            .map_err(ConfigError::Parse)?;
+        Ok((config, version < CONFIG_VERSION))
+    }
+}
 
+// Implement the ability to create a default config (done in the frontend), the lessons directory is only created when the config is loaded
+impl Default for Config {
+    fn default() -> Self {
         // Create and return a default config (light theme by default as this tends to be more inviting to new users)
         Self {
+            version: CONFIG_VERSION,
             theme: Theme::Light,
             editor_font_size: 16,
-            lessons_directory: lessons_directory.to_string(),
+            lessons_directory: paths::default_lessons_directory()
+                .to_string_lossy()
+                .into_owned(),
             run_speed: RunSpeed::Medium,
+            instruction_set: InstructionSet::Classic,
+            format_aliases: AliasStyle::Standard,
+            keymap: Keymap::default(),
+            language: Language::English,
+        }
+    }
+}
+
+// Restrictions the system config puts on the user (e.g. on a school's lab machines)
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Policy {
+    // Config keys that always take their value from the system config (e.g. lessons_directory), shown but not editable in the settings screen
+    pub locked: Vec<String>,
+    pub hide_settings: bool,
+    pub hide_sandbox: bool,
+}
+
+impl Policy {
+    pub fn is_locked(&self, key: &str) -> bool {
+        self.locked.iter().any(|locked| locked == key)
+    }
+
+    // Used when the system config exists but can't be read, every key is locked and everything it could hide is hidden so a broken file never lifts its restrictions
+    pub fn locked_down() -> Self {
+        Self {
+            locked: toml::Table::try_from(Config::default())
+                .map(|table| table.keys().cloned().collect())
+                .unwrap_or_default(),
+            hide_settings: true,
+            hide_sandbox: true,
         }
     }
 }
 
-// Allow turning a path into a config (deserialisation), not using TryFrom here as this operation must succeed or the program will not work
-impl TryFrom<PathBuf> for Config {
-    type Error = ();
+/* The config shared by every user of a computer, laid out like a user config with the policy keys alongside, e.g.
+lessons_directory = "/srv/algor/lessons"
+locked = ["lessons_directory"]
+hide_settings = false
+hide_sandbox = false */
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SystemConfig {
+    // Values used for keys the user's config doesn't set (or can't set if they are locked)
+    pub defaults: toml::Table,
+    pub policy: Policy,
+}
+
+impl SystemConfig {
+    // Keys that are part of the policy rather than the config
+    const POLICY_KEYS: [&str; 3] = ["locked", "hide_settings", "hide_sandbox"];
 
-    fn try_from(path: PathBuf) -> Result<Self, Self::Error> {
-        // Get config directory from path
-        let mut config_dir = path.clone();
-        config_dir.pop();
+    pub fn parse(file: &str) -> Result<Self, ConfigError> {
+        let mut defaults = toml::from_str::<toml::Table>(file).map_err(ConfigError::Parse)?;
 
-        // Create all directories leading up to config path, if the program can't exit early
-        if !config_dir.exists()
-            && let Err(e) = fs::create_dir_all(&config_dir)
-        {
-            panic!("Failed to create config directory, {e}");
+        let policy = toml::Value::Table(defaults.clone())
+            .try_into()
+            .map_err(ConfigError::Parse)?;
+        for key in Self::POLICY_KEYS {
+            defaults.remove(key);
         }
 
-        // Create the config file if it doesn't exist, if any errors occur while creating the file or the default config exit the program
-        if !path.exists() {
-            let Ok(mut file) = fs::File::create(&path) else {
-                panic!("Failed to create config file")
-            };
+        Ok(Self { defaults, policy })
+    }
 
-            if let Ok(config) = toml::to_string(&Config::default())
-                && let Err(e) = file.write_all(config.as_bytes())
-            {
-                panic!("{}", e);
+    // Read the system config, a computer without one has no defaults or policy
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        match fs::read_to_string(path) {
+            Ok(file) => {
+                Self::parse(&file).map_err(|e| ConfigError::System(path.to_path_buf(), Box::new(e)))
             }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(ConfigError::System(
+                path.to_path_buf(),
+                Box::new(ConfigError::Io(path.to_path_buf(), e)),
+            )),
+        }
+    }
+}
+
+impl Config {
+    // Read the user's config from a path, creating the config file with the default config if it doesn't exist
+    pub fn load(path: PathBuf, system: &SystemConfig) -> Result<Self, ConfigError> {
+        let io = |e| ConfigError::Io(path.clone(), e);
+
+        // Create all directories leading up to config path
+        if let Some(config_dir) = path.parent() {
+            fs::create_dir_all(config_dir).map_err(io)?;
         }
 
-        // Read the file from disk or exit
-        let Ok(file) = fs::read_to_string(&path) else {
-            panic!("Failed to read config to buffer")
-        };
+        let config = if path.exists() {
+            let (config, migrated) =
+                Config::parse(&fs::read_to_string(&path).map_err(io)?, system)?;
 
-        // Turn the config file into a struct, otherwise the user must've edited the file manually (i.e. knows what they are doing), tell them to check for syntax errors
-        let Ok(config) = toml::from_str::<Config>(file.as_str()) else {
-            panic!("Failed to read config, check for syntax errors")
+            // Write the migrated config back so the file is up to date, a config that can't be written to (e.g. one managed on a lab machine) still loads
+            if migrated && let Ok(file) = toml::to_string(&config) {
+                let _ = fs::write(&path, file);
+            }
+            config
+        } else {
+            let (config, _) = Config::parse("", system)?;
+            let file = toml::to_string(&config).map_err(ConfigError::Serialize)?;
+            fs::write(&path, file).map_err(io)?;
+            config
         };
 
-        // Get the lesson path from the config and make it a string, this error type is Infallible, i.e. it will never fail, so no need to match for errors
-        let Ok(lessons_path) = PathBuf::from_str(&config.lessons_directory);
-
-        // Make the lesson path if it doesn't exist, if the program can't, exit.
-        if !lessons_path.exists()
-            && let Err(e) = fs::create_dir_all(&config.lessons_directory)
-        {
-            panic!("Failed to create lessons directory, {e}");
-        }
+        // Make the lesson path if it doesn't exist
+        fs::create_dir_all(&config.lessons_directory)
+            .map_err(|e| ConfigError::Io(PathBuf::from(&config.lessons_directory), e))?;
 
         Ok(config)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::backend::config::{
+        CONFIG_VERSION, Config, ConfigError, Policy, RunSpeed, SystemConfig,
+    };
+    use crate::frontend::util::theme::Theme;
+    use crate::shared::{isa::InstructionSet, locale::Language};
+
+    // 8.1
+    #[test]
+    fn config_missing_keys() {
+        // A config saved before the version, instruction set, format aliases, and language keys existed
+        let (config, migrated) = Config::parse(
+            r#"theme = "Dark"
+            editor_font_size = 20
+            lessons_directory = "/srv/lessons"
+            run_speed = "Fast""#,
+            &SystemConfig::default(),
+        )
+        .unwrap();
+
+        assert!(migrated);
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.theme, Theme::Dark);
+        assert_eq!(config.editor_font_size, 20);
+        assert_eq!(config.lessons_directory, "/srv/lessons");
+        assert_eq!(config.run_speed, RunSpeed::Fast);
+        assert_eq!(config.instruction_set, InstructionSet::Classic);
+        assert_eq!(config.language, Language::English);
+
+        // An up to date config isn't migrated, and an empty one is the default config
+        let file = toml::to_string(&config).unwrap();
+        assert_eq!(
+            Config::parse(&file, &SystemConfig::default()).unwrap(),
+            (config, false)
+        );
+        assert_eq!(
+            Config::parse("", &SystemConfig::default()).unwrap().0,
+            Config::default()
+        );
+    }
+
+    // 8.2
+    #[test]
+    fn config_errors() {
+        assert!(matches!(
+            Config::parse("theme = ", &SystemConfig::default()),
+            Err(ConfigError::Parse(_))
+        ));
+        assert!(matches!(
+            Config::parse("editor_font_size = \"big\"", &SystemConfig::default()),
+            Err(ConfigError::Parse(_))
+        ));
+        assert!(matches!(
+            Config::parse(
+                &format!("version = {}", CONFIG_VERSION + 1),
+                &SystemConfig::default()
+            ),
+            Err(ConfigError::UnsupportedVersion(_))
+        ));
+    }
+
+    // 8.3
+    #[test]
+    fn config_system() {
+        let system = SystemConfig::parse(
+            r#"lessons_directory = "/srv/lessons"
+            theme = "Dark"
+            locked = ["lessons_directory"]
+            hide_sandbox = true"#,
+        )
+        .unwrap();
+
+        assert!(system.policy.is_locked("lessons_directory"));
+        assert!(system.policy.hide_sandbox);
+        assert!(!system.policy.hide_settings);
+        // Policy keys aren't config keys
+        assert!(!system.defaults.contains_key("locked"));
+
+        // The system config fills in keys the user hasn't set, and locked keys can't be changed
+        let (config, _) = Config::parse(
+            r#"lessons_directory = "/home/student/lessons"
+            editor_font_size = 20"#,
+            &system,
+        )
+        .unwrap();
+        assert_eq!(config.lessons_directory, "/srv/lessons");
+        assert_eq!(config.theme, Theme::Dark);
+        assert_eq!(config.editor_font_size, 20);
+
+        // Unlocked keys from the system config can be changed
+        let (config, _) = Config::parse(r#"theme = "Light""#, &system).unwrap();
+        assert_eq!(config.theme, Theme::Light);
+
+        assert!(SystemConfig::parse("locked = \"lessons_directory\"").is_err());
+    }
+    // 8.4
+    #[test]
+    fn config_locked_down() {
+        let policy = Policy::locked_down();
+
+        // Every key is locked, including ones the system config is most likely to lock
+        assert!(policy.is_locked("lessons_directory"));
+        assert!(policy.is_locked("theme"));
+        assert!(policy.is_locked("keymap"));
+        assert!(policy.hide_settings);
+        assert!(policy.hide_sandbox);
+    }
+}
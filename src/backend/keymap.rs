@@ -0,0 +1,433 @@
+use serde::{Deserialize, Serialize};
+
+use crate::shared::locale::{tr, tr_with};
+
+use std::{collections::BTreeMap, fmt::Display};
+
+// Something that can be given a key in the config, the keys for moving between and pressing buttons are fixed so the application can always be used from the keyboard (see src/frontend/util/shortcuts.rs)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Command {
+    Save,
+    Assemble,
+    Run,
+    Step,
+    Stop,
+    Reset,
+    NextSlide,
+    PreviousSlide,
+    NextPane,
+    PreviousPane,
+    ToggleSummary,
+}
+
+impl Command {
+    // Every command in the order they are listed in the settings screen
+    pub const ALL: [Command; 11] = [
+        Command::Save,
+        Command::Assemble,
+        Command::Run,
+        Command::Step,
+        Command::Stop,
+        Command::Reset,
+        Command::NextSlide,
+        Command::PreviousSlide,
+        Command::NextPane,
+        Command::PreviousPane,
+        Command::ToggleSummary,
+    ];
+}
+
+impl Display for Command {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            tr(match self {
+                Command::Save => "save",
+                Command::Assemble => "editor-assemble",
+                Command::Run => "editor-run",
+                Command::Step => "editor-step",
+                Command::Stop => "editor-stop",
+                Command::Reset => "editor-reset",
+                Command::NextSlide => "command-next-slide",
+                Command::PreviousSlide => "command-previous-slide",
+                Command::NextPane => "command-next-pane",
+                Command::PreviousPane => "command-previous-pane",
+                Command::ToggleSummary => "command-toggle-summary",
+            })
+        )
+    }
+}
+
+// Keys that aren't letters, digits, or symbols, by the name they are written with (other names that are accepted come after the name)
+const NAMED_KEYS: [(&str, &[&str]); 15] = [
+    ("Enter", &["Return"]),
+    ("Space", &[]),
+    ("Tab", &[]),
+    ("Escape", &["Esc"]),
+    ("Backspace", &[]),
+    ("Delete", &["Del"]),
+    ("Insert", &["Ins"]),
+    ("Home", &[]),
+    ("End", &[]),
+    ("PageUp", &[]),
+    ("PageDown", &[]),
+    ("Up", &["ArrowUp"]),
+    ("Down", &["ArrowDown"]),
+    ("Left", &["ArrowLeft"]),
+    ("Right", &["ArrowRight"]),
+];
+
+// Possible mistakes in a key binding
+#[derive(Debug, Clone, PartialEq)]
+pub enum BindingError {
+    Empty,
+    // The binding ends in a modifier or has no key after a plus
+    MissingKey,
+    UnknownKey(String),
+    UnknownModifier(String),
+}
+
+impl Display for BindingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            BindingError::Empty => tr("keymap-empty"),
+            BindingError::MissingKey => tr("keymap-missing-key"),
+            BindingError::UnknownKey(key) => tr_with("keymap-unknown-key", &[("key", key)]),
+            BindingError::UnknownModifier(modifier) => {
+                tr_with("keymap-unknown-modifier", &[("modifier", modifier)])
+            }
+        };
+
+        write!(f, "{text}")
+    }
+}
+
+/* A key and the modifiers held with it, written like "Ctrl+Shift+F5" in the config
+Ctrl is the command key on macOS, and letters are kept upper case so caps lock doesn't matter */
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Binding {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub key: String,
+}
+
+impl Binding {
+    // The name a key is written with, if it is one (e.g. "esc" is "Escape" and "s" is "S")
+    pub fn key_name(key: &str) -> Option<String> {
+        let mut chars = key.chars();
+
+        // Letters, digits, and symbols
+        if let (Some(c), None) = (chars.next(), chars.next())
+            && c.is_ascii_graphic()
+        {
+            return Some(c.to_ascii_uppercase().to_string());
+        }
+
+        // Function keys
+        if let Some(n) = key
+            .strip_prefix(['F', 'f'])
+            .and_then(|n| n.parse::<u8>().ok())
+            && (1..=12).contains(&n)
+        {
+            return Some(format!("F{n}"));
+        }
+
+        NAMED_KEYS
+            .iter()
+            .find(|(name, aliases)| {
+                name.eq_ignore_ascii_case(key)
+                    || aliases.iter().any(|alias| alias.eq_ignore_ascii_case(key))
+            })
+            .map(|(name, _)| name.to_string())
+    }
+
+    // Function keys don't type anything, so they can be used without ctrl or alt
+    fn is_function_key(&self) -> bool {
+        self.key.len() > 1 && self.key.starts_with('F') && self.key[1..].parse::<u8>().is_ok()
+    }
+
+    // The id of the message saying what the rest of the application already uses a binding for, if anything (see src/shared/locale.rs)
+    fn reserved(&self) -> Option<&'static str> {
+        if !self.ctrl && !self.alt && !self.is_function_key() {
+            Some("keymap-reserved-typing")
+        } else if self.ctrl && !self.alt && ["C", "X", "V", "A"].contains(&self.key.as_str()) {
+            Some("keymap-reserved-clipboard")
+        } else {
+            None
+        }
+    }
+}
+
+impl TryFrom<&str> for Binding {
+    type Error = BindingError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(BindingError::Empty);
+        }
+
+        // "+" on its own (or at the end, e.g. "Ctrl++") is the plus key
+        let (modifiers, key) = match value.strip_suffix("++") {
+            Some(modifiers) => (modifiers, "+"),
+            None if value == "+" => ("", "+"),
+            None => value.rsplit_once('+').unwrap_or(("", value)),
+        };
+
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(BindingError::MissingKey);
+        }
+
+        let mut binding = Self {
+            ctrl: false,
+            alt: false,
+            shift: false,
+            key: Self::key_name(key).ok_or_else(|| BindingError::UnknownKey(key.to_string()))?,
+        };
+
+        for modifier in modifiers.split('+').map(str::trim) {
+            match modifier.to_ascii_lowercase().as_str() {
+                "" if modifiers.is_empty() => {}
+                "ctrl" | "control" | "cmd" | "command" => binding.ctrl = true,
+                "alt" | "option" => binding.alt = true,
+                "shift" => binding.shift = true,
+                "" => return Err(BindingError::MissingKey),
+                _ => return Err(BindingError::UnknownModifier(modifier.to_string())),
+            }
+        }
+
+        Ok(binding)
+    }
+}
+
+// Ditto TryFrom<&str> comment, needed for deserialising
+impl TryFrom<String> for Binding {
+    type Error = BindingError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::try_from(value.as_str())
+    }
+}
+
+impl Display for Binding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (held, modifier) in [
+            (self.ctrl, "Ctrl"),
+            (self.alt, "Alt"),
+            (self.shift, "Shift"),
+        ] {
+            if held {
+                write!(f, "{modifier}+")?;
+            }
+        }
+
+        write!(f, "{}", self.key)
+    }
+}
+
+impl From<Binding> for String {
+    fn from(value: Binding) -> Self {
+        value.to_string()
+    }
+}
+
+// Why a binding can't be used for a command
+#[derive(Debug, Clone, PartialEq)]
+pub enum Conflict {
+    // Another command has the same binding
+    Command(Command),
+    // The binding is already used by the application (e.g. Ctrl+C copies in the editor), with the id of the message saying what for
+    Reserved(&'static str),
+}
+
+impl Display for Conflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Conflict::Command(command) => write!(
+                f,
+                "{}",
+                tr_with("keymap-conflict-command", &[("command", command)])
+            ),
+            Conflict::Reserved(used_for) => write!(
+                f,
+                "{}",
+                tr_with("keymap-conflict-reserved", &[("used_for", &tr(used_for))])
+            ),
+        }
+    }
+}
+
+/* The binding of every command, kept in the config as a table, e.g.
+[keymap]
+save = "Ctrl+S"
+assemble = "Ctrl+Enter"
+Commands missing from the table keep their default binding */
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(
+    from = "BTreeMap<Command, Binding>",
+    into = "BTreeMap<Command, Binding>"
+)]
+pub struct Keymap(BTreeMap<Command, Binding>);
+
+impl Keymap {
+    pub fn get(&self, command: Command) -> &Binding {
+        // Every command is given a binding when the keymap is made
+        &self.0[&command]
+    }
+
+    pub fn set(&mut self, command: Command, binding: Binding) {
+        self.0.insert(command, binding);
+    }
+
+    // The command a binding does, if any
+    pub fn command(&self, binding: &Binding) -> Option<Command> {
+        Command::ALL
+            .into_iter()
+            .find(|command| self.get(*command) == binding)
+    }
+
+    // Why a command's binding can't be used, if it can't (only commands listed before it count as conflicting, so each pair is only reported once)
+    pub fn conflict(&self, command: Command) -> Option<Conflict> {
+        let binding = self.get(command);
+
+        if let Some(used_for) = binding.reserved() {
+            return Some(Conflict::Reserved(used_for));
+        }
+
+        Command::ALL
+            .into_iter()
+            .take_while(|other| *other != command)
+            .find(|other| self.get(*other) == binding)
+            .map(Conflict::Command)
+    }
+
+    // Whether every command has a binding that can be used
+    pub fn is_valid(&self) -> bool {
+        Command::ALL
+            .into_iter()
+            .all(|command| self.conflict(command).is_none())
+    }
+}
+
+impl From<BTreeMap<Command, Binding>> for Keymap {
+    fn from(value: BTreeMap<Command, Binding>) -> Self {
+        let mut keymap = Self::default();
+        keymap.0.extend(value);
+        keymap
+    }
+}
+
+impl From<Keymap> for BTreeMap<Command, Binding> {
+    fn from(value: Keymap) -> Self {
+        value.0
+    }
+}
+
+// Ctrl+S saves, F5 runs, F10 steps, Shift+F5 stops, and Ctrl+Enter assembles like most editors and debuggers
+impl Default for Keymap {
+    fn default() -> Self {
+        Self(
+            [
+                (Command::Save, "Ctrl+S"),
+                (Command::Assemble, "Ctrl+Enter"),
+                (Command::Run, "F5"),
+                (Command::Step, "F10"),
+                (Command::Stop, "Shift+F5"),
+                (Command::Reset, "Ctrl+Shift+F5"),
+                (Command::NextSlide, "Alt+Right"),
+                (Command::PreviousSlide, "Alt+Left"),
+                (Command::NextPane, "F6"),
+                (Command::PreviousPane, "Shift+F6"),
+                (Command::ToggleSummary, "Ctrl+M"),
+            ]
+            .into_iter()
+            .map(|(command, binding)| (command, Binding::try_from(binding).unwrap()))
+            .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backend::keymap::{Binding, BindingError, Command, Conflict, Keymap};
+
+    // 11.1
+    #[test]
+    fn keymap_bindings() {
+        let binding = Binding::try_from(" shift + ctrl+f5").unwrap();
+        assert!(binding.ctrl && binding.shift && !binding.alt);
+        assert_eq!(binding.key, "F5");
+        // Modifiers are always written in the same order
+        assert_eq!(binding.to_string(), "Ctrl+Shift+F5");
+
+        assert_eq!(
+            Binding::try_from("cmd+esc").unwrap().to_string(),
+            "Ctrl+Escape"
+        );
+        assert_eq!(Binding::try_from("Alt+s").unwrap().to_string(), "Alt+S");
+        assert_eq!(Binding::try_from("Ctrl++").unwrap().to_string(), "Ctrl++");
+
+        assert_eq!(Binding::try_from(""), Err(BindingError::Empty));
+        assert_eq!(Binding::try_from("Ctrl+"), Err(BindingError::MissingKey));
+        assert_eq!(
+            Binding::try_from("Ctrl+F13"),
+            Err(BindingError::UnknownKey("F13".into()))
+        );
+        assert_eq!(
+            Binding::try_from("Meta+S"),
+            Err(BindingError::UnknownModifier("Meta".into()))
+        );
+    }
+
+    // 11.2
+    #[test]
+    fn keymap_conflicts() {
+        let mut keymap = Keymap::default();
+        assert!(keymap.is_valid());
+        assert_eq!(
+            keymap.command(&Binding::try_from("Ctrl+Enter").unwrap()),
+            Some(Command::Assemble)
+        );
+
+        // Only the later of two commands with the same binding is reported
+        keymap.set(Command::Step, Binding::try_from("F5").unwrap());
+        assert_eq!(keymap.conflict(Command::Run), None);
+        assert_eq!(
+            keymap.conflict(Command::Step),
+            Some(Conflict::Command(Command::Run))
+        );
+        assert!(!keymap.is_valid());
+
+        // Keys that type or copy and paste can't be used
+        keymap.set(Command::Step, Binding::try_from("Shift+S").unwrap());
+        assert!(matches!(
+            keymap.conflict(Command::Step),
+            Some(Conflict::Reserved(_))
+        ));
+        keymap.set(Command::Step, Binding::try_from("Ctrl+C").unwrap());
+        assert!(matches!(
+            keymap.conflict(Command::Step),
+            Some(Conflict::Reserved(_))
+        ));
+
+        // Commands missing from the config keep their default binding
+        let keymap: Keymap = toml::from_str::<toml::Table>(r#"run = "Ctrl+R""#)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(keymap.get(Command::Run).to_string(), "Ctrl+R");
+        assert_eq!(keymap.get(Command::Step).to_string(), "F10");
+
+        assert!(
+            toml::from_str::<toml::Table>(r#"run = "Ctrl+""#)
+                .unwrap()
+                .try_into::<Keymap>()
+                .is_err()
+        );
+    }
+}
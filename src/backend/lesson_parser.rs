@@ -2,18 +2,28 @@ use iced::{
     Element,
     widget::{column, text},
 };
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer, de};
 
 use crate::frontend::util::{font::Font, widgets::separator};
+use crate::shared::{
+    isa::InstructionSet,
+    locale::tr,
+    runtime::Output,
+    trace::{self, Row},
+};
 
 /* e.g.
     <head>title</head>
     <head></head>
+    <head><instruction-set>extended</instruction-set></head>
 */
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "kebab-case")]
 pub struct Head {
     pub title: Option<String>,
+    // The instruction set programs in this lesson are assembled with (classic, extended, or exam-board), classic if not specified
+    #[serde(default)]
+    pub instruction_set: InstructionSet,
 }
 
 /* e.g.
@@ -45,17 +55,222 @@ pub enum SlideMember {
     <li>2</li>
     <li>3</li>
 */
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Debug, Clone, Default)]
 pub struct Inputs {
-    #[serde(rename = "li")]
+    #[serde(rename = "li", default)]
     pub items: Vec<i16>,
 }
 
-// Ditto Inputs comment
-#[derive(Deserialize, Debug, Clone)]
-pub struct Outputs {
+/* e.g.
+    <li>1</li>
+    <text>Hello, world!</text>
+*/
+#[derive(Deserialize, PartialEq, Debug, Clone)]
+pub enum ExpectedOutput {
+    // A number printed by OUT
     #[serde(rename = "li")]
-    pub items: Vec<i16>,
+    Number(i16),
+    // Text printed by OTC, one character at a time
+    #[serde(rename = "text")]
+    Text(String),
+}
+
+// Ditto Inputs comment but with text as well as numbers
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct Outputs {
+    #[serde(rename = "#content", default)]
+    pub items: Vec<ExpectedOutput>,
+}
+
+impl Outputs {
+    // Check the output of a program against the expected outputs, characters next to each other are joined into text before comparing
+    pub fn matches(&self, output: &[Output]) -> bool {
+        let mut expected: Vec<ExpectedOutput> = Vec::new();
+
+        for item in &self.items {
+            match (item, expected.last_mut()) {
+                (ExpectedOutput::Text(text), Some(ExpectedOutput::Text(last))) => {
+                    last.push_str(text)
+                }
+                _ => expected.push(item.clone()),
+            }
+        }
+
+        let mut actual: Vec<ExpectedOutput> = Vec::new();
+
+        for item in output {
+            match (item, actual.last_mut()) {
+                (Output::Number(number), _) => actual.push(ExpectedOutput::Number(*number)),
+                (Output::Character(character), Some(ExpectedOutput::Text(last))) => {
+                    last.push(*character)
+                }
+                (Output::Character(character), _) => {
+                    actual.push(ExpectedOutput::Text(character.to_string()))
+                }
+                // Messages from algor (e.g. "Waiting for input...") aren't part of the program's output
+                (Output::Message(_), _) => {}
+            }
+        }
+
+        expected == actual
+    }
+}
+
+/* e.g.
+    <li>10</li>
+    <li>11</li>
+*/
+#[derive(Deserialize, Debug, Clone)]
+pub struct Addresses {
+    #[serde(rename = "li", default)]
+    pub items: Vec<u8>,
+}
+
+/* e.g.
+    <trace-table>
+        <program>
+        INP
+        STA total
+        ...
+        </program>
+        <memory>
+            <li>10</li>
+        </memory>
+    </trace-table>
+
+The program is run with the inputs of the slide, the student fills in PC, ACC, and the output after each instruction, as well as the values in the memory locations listed
+*/
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct TraceTable {
+    pub program: String,
+    pub memory: Option<Addresses>,
+}
+
+impl TraceTable {
+    // The column that can be left blank when nothing is printed, every other column can be left blank when the value hasn't changed
+    const OUTPUT_COLUMN: usize = 2;
+
+    // The memory locations that have a column in the table
+    pub fn addresses(&self) -> &[u8] {
+        self.memory
+            .as_ref()
+            .map_or(&[], |addresses| addresses.items.as_slice())
+    }
+
+    // The headers of each column
+    pub fn columns(&self) -> Vec<String> {
+        ["PC".into(), "ACC".into(), tr("lesson-trace-output")]
+            .into_iter()
+            .chain(
+                self.addresses()
+                    .iter()
+                    .map(|address| format!("[{address}]")),
+            )
+            .collect()
+    }
+
+    // Run the program to find the answers, the first row is the computer before the program starts
+    pub fn run(&self, instruction_set: InstructionSet, inputs: &[i16]) -> Result<Vec<Row>, String> {
+        trace::table(
+            &self.program,
+            instruction_set,
+            inputs,
+            self.addresses(),
+            1000,
+        )
+    }
+
+    // Check the table filled in by the student against the rows from running the program, giving whether each cell is right
+    pub fn check(expected: &[Row], answers: &[Vec<String>]) -> Vec<Vec<bool>> {
+        let cells = |row: &Row| {
+            [
+                Some(row.program_counter as i16),
+                Some(row.accumulator),
+                None,
+            ]
+            .into_iter()
+            .chain(row.memory.iter().map(|value| Some(*value)))
+            .collect::<Vec<_>>()
+        };
+
+        expected
+            .windows(2)
+            .zip(answers)
+            .map(|(rows, answers)| {
+                let (previous, row) = (cells(&rows[0]), cells(&rows[1]));
+
+                answers
+                    .iter()
+                    .enumerate()
+                    .map(|(column, answer)| {
+                        let answer = answer.trim();
+
+                        if column == Self::OUTPUT_COLUMN {
+                            return answer == rows[1].output.as_deref().unwrap_or_default();
+                        }
+
+                        if answer.is_empty() {
+                            row[column] == previous[column]
+                        } else {
+                            answer.parse().ok() == row[column]
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/* e.g.
+    <choice>OUT</choice>
+    <choice correct="true">STA</choice>
+*/
+#[derive(Deserialize, Debug, Clone)]
+pub struct Choice {
+    #[serde(rename = "@correct", default)]
+    pub correct: bool,
+    #[serde(rename = "#text")]
+    pub text: String,
+}
+
+/* e.g.
+    <quiz>
+        <question>Which instruction stores the accumulator in memory?</question>
+        <choice>OUT</choice>
+        <choice correct="true">STA</choice>
+        <feedback>STA stands for store accumulator</feedback>
+    </quiz>
+
+    <quiz>
+        <question>What is in the accumulator after line 4?</question>
+        <answer>12</answer>
+    </quiz>
+
+A quiz is either multiple choice (with choices) or has a numeric answer, the feedback is shown when the answer is wrong
+*/
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct Quiz {
+    pub question: String,
+    #[serde(rename = "choice", default)]
+    pub choices: Vec<Choice>,
+    pub answer: Option<i16>,
+    pub feedback: Option<String>,
+}
+
+impl Quiz {
+    // Check the choice picked for multiple choice questions, or the number typed in otherwise (a quiz with neither can't be answered, rather than anything being right)
+    pub fn check(&self, choice: Option<usize>, answer: &str) -> bool {
+        if self.choices.is_empty() {
+            self.answer
+                .is_some_and(|expected| answer.trim().parse() == Ok(expected))
+        } else {
+            choice
+                .and_then(|choice| self.choices.get(choice))
+                .is_some_and(|choice| choice.correct)
+        }
+    }
 }
 
 /* i.e.
@@ -71,9 +286,16 @@ pub struct Outputs {
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "kebab-case")]
 pub struct Slide {
+    // Inputs and outputs are only needed for programming exercises
+    #[serde(default)]
     pub inputs: Inputs,
+    #[serde(default)]
     pub outputs: Outputs,
-    #[serde(rename = "#content")]
+    // Turns the slide into a trace table exercise instead of a programming exercise
+    pub trace_table: Option<TraceTable>,
+    // Ditto for a quiz question
+    pub quiz: Option<Quiz>,
+    #[serde(rename = "#content", default)]
     pub members: Vec<SlideMember>,
 }
 
@@ -107,10 +329,21 @@ impl Slide {
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "kebab-case")]
 pub struct Body {
-    #[serde(rename = "slide")]
+    #[serde(rename = "slide", deserialize_with = "slides")]
     pub slides: Vec<Slide>,
 }
 
+// A lesson without any slides has nothing to show, so it's rejected like a lesson that isn't valid XML
+fn slides<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Slide>, D::Error> {
+    let slides = Vec::<Slide>::deserialize(deserializer)?;
+
+    if slides.is_empty() {
+        return Err(de::Error::custom("expected at least one slide"));
+    }
+
+    Ok(slides)
+}
+
 /* i.e.
     <head>
         ...
@@ -126,3 +359,195 @@ pub struct Lesson {
     pub head: Head,
     pub body: Body,
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::backend::lesson_parser::{ExpectedOutput, Lesson, Outputs, TraceTable};
+    use crate::shared::{isa::InstructionSet, runtime::Output};
+
+    // Parse a lesson from a string
+    fn lesson(source: &str) -> Lesson {
+        serde_xml_rs::from_str(source).unwrap()
+    }
+
+    // 5.1
+    #[test]
+    fn lesson_trace_table() {
+        let source = r#"<algor-lesson>
+            <head><title>Trace tables</title></head>
+            <body>
+                <slide>
+                    <inputs><li>6</li></inputs>
+                    <outputs />
+                    <trace-table>
+                        <program>INP
+                        STA total
+                        OUT
+                        HLT
+                        total DAT 1</program>
+                        <memory><li>4</li></memory>
+                    </trace-table>
+                    <p>Fill in the trace table</p>
+                </slide>
+            </body>
+        </algor-lesson>"#;
+
+        let lesson = lesson(source);
+        let slide = &lesson.body.slides[0];
+        let table = slide.trace_table.as_ref().unwrap();
+
+        assert_eq!(table.columns(), vec!["PC", "ACC", "Output", "[4]"]);
+
+        let expected = table
+            .run(InstructionSet::Classic, &slide.inputs.items)
+            .unwrap();
+
+        let answers: Vec<Vec<String>> = [
+            ["1", "6", "", ""],
+            // Blank cells are right when the value hasn't changed, a wrong ACC and a missing output
+            ["2", "7", "", "6"],
+            ["3", "", "", ""],
+            ["3", "", "", "6"],
+        ]
+        .iter()
+        .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+        .collect();
+
+        assert_eq!(
+            TraceTable::check(&expected, &answers),
+            vec![
+                vec![true, true, true, true],
+                vec![true, false, true, true],
+                vec![true, true, false, true],
+                vec![true, true, true, true],
+            ]
+        );
+    }
+
+    // 5.2
+    #[test]
+    fn lesson_quiz() {
+        let lesson = lesson(
+            r#"<algor-lesson>
+            <head></head>
+            <body>
+                <slide>
+                    <quiz>
+                        <question>Which instruction stores the accumulator in memory?</question>
+                        <choice>OUT</choice>
+                        <choice correct="true">STA</choice>
+                        <feedback>STA stands for store accumulator</feedback>
+                    </quiz>
+                </slide>
+                <slide>
+                    <quiz>
+                        <question>What is 5 + 7?</question>
+                        <answer>12</answer>
+                    </quiz>
+                </slide>
+                <slide>
+                    <quiz>
+                        <question>What is 5 + 7?</question>
+                    </quiz>
+                </slide>
+            </body>
+        </algor-lesson>"#,
+        );
+
+        let choice = lesson.body.slides[0].quiz.as_ref().unwrap();
+        assert_eq!(choice.choices.len(), 2);
+        assert!(!choice.check(Some(0), ""));
+        assert!(choice.check(Some(1), ""));
+        assert!(!choice.check(None, ""));
+        assert_eq!(
+            choice.feedback.as_deref(),
+            Some("STA stands for store accumulator")
+        );
+
+        let number = lesson.body.slides[1].quiz.as_ref().unwrap();
+        assert!(number.check(None, " 12 "));
+        assert!(!number.check(None, "13"));
+        assert!(lesson.body.slides[1].inputs.items.is_empty());
+
+        // Without choices or an answer nothing is right, including leaving it empty
+        let missing = lesson.body.slides[2].quiz.as_ref().unwrap();
+        assert!(!missing.check(None, ""));
+        assert!(!missing.check(None, "12"));
+    }
+
+    // 5.3
+    #[test]
+    fn lesson_outputs() {
+        let outputs = lesson(
+            r#"<algor-lesson>
+            <head></head>
+            <body>
+                <slide>
+                    <outputs>
+                        <li>1</li>
+                        <text>Hi</text>
+                        <text>!</text>
+                        <li>-2</li>
+                    </outputs>
+                </slide>
+            </body>
+        </algor-lesson>"#,
+        )
+        .body
+        .slides[0]
+            .outputs
+            .clone();
+
+        assert_eq!(
+            outputs.items,
+            vec![
+                ExpectedOutput::Number(1),
+                ExpectedOutput::Text("Hi".into()),
+                ExpectedOutput::Text("!".into()),
+                ExpectedOutput::Number(-2),
+            ]
+        );
+
+        // Characters printed one at a time match text split across elements, and messages from algor are ignored
+        assert!(outputs.matches(&[
+            Output::Number(1),
+            Output::Message("Waiting for input...".into()),
+            Output::Character('H'),
+            Output::Character('i'),
+            Output::Character('!'),
+            Output::Number(-2),
+        ]));
+        // A wrong character, a number printed as a character, and a missing output
+        assert!(!outputs.matches(&[
+            Output::Number(1),
+            Output::Character('H'),
+            Output::Character('o'),
+            Output::Character('!'),
+            Output::Number(-2),
+        ]));
+        assert!(!outputs.matches(&[
+            Output::Character('1'),
+            Output::Character('H'),
+            Output::Character('i'),
+            Output::Character('!'),
+            Output::Number(-2),
+        ]));
+        assert!(!outputs.matches(&[
+            Output::Number(1),
+            Output::Character('H'),
+            Output::Character('i'),
+            Output::Character('!'),
+        ]));
+        assert!(Outputs::default().matches(&[]));
+    }
+    // 5.4
+    #[test]
+    fn lesson_without_slides() {
+        for source in [
+            "<algor-lesson><head><title>Empty</title></head><body></body></algor-lesson>",
+            "<algor-lesson><head><title>Empty</title></head><body /></algor-lesson>",
+        ] {
+            assert!(serde_xml_rs::from_str::<Lesson>(source).is_err());
+        }
+    }
+}
@@ -0,0 +1,414 @@
+pub mod document;
+pub mod rpc;
+
+#[cfg(test)]
+pub mod tests;
+
+use std::collections::HashMap;
+use std::io;
+
+use serde_json::{Value, json};
+
+use crate::backend::compiler::lexer::{Span, Token};
+use crate::backend::lsp::document::Document;
+use crate::shared::isa::InstructionSet;
+
+// JSON-RPC error codes (see the language server protocol specification)
+const INVALID_PARAMS: i64 = -32602;
+const METHOD_NOT_FOUND: i64 = -32601;
+const REQUEST_FAILED: i64 = -32803;
+
+// Completion item kinds (see the language server protocol specification)
+const COMPLETION_FUNCTION: u8 = 3;
+const COMPLETION_VARIABLE: u8 = 6;
+const COMPLETION_KEYWORD: u8 = 14;
+
+// Turn a byte offset into a line and character, characters are counted in UTF-16 code units as that's what editors expect by default
+pub fn position(source: &str, offset: usize) -> Value {
+    // Offsets past the end or in the middle of a character are moved back to the start of it, so a bad span can't crash the server
+    let mut offset = offset.min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+
+    let before = &source[..offset];
+    let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+
+    json!({
+        "line": before.matches('\n').count(),
+        "character": before[line_start..].encode_utf16().count(),
+    })
+}
+
+// Ditto position comment but the other way around, positions past the end of a line or file are moved back to the end
+pub fn offset(source: &str, position: &Value) -> Option<usize> {
+    let line = position["line"].as_u64()? as usize;
+    let character = position["character"].as_u64()? as usize;
+
+    let line_start: usize = source.split_inclusive('\n').take(line).map(str::len).sum();
+    let mut units = 0;
+
+    for (i, c) in source[line_start..].char_indices() {
+        if units >= character || c == '\n' {
+            return Some(line_start + i);
+        }
+        units += c.len_utf16();
+    }
+
+    Some(source.len())
+}
+
+// A span as an LSP range
+pub fn range(source: &str, span: &Span) -> Value {
+    json!({
+        "start": position(source, span.start),
+        "end": position(source, span.end),
+    })
+}
+
+// The state of the language server, a list of open files and the instruction set they are checked with
+#[derive(Default)]
+pub struct Server {
+    documents: HashMap<String, Document>,
+    instruction_set: InstructionSet,
+    // Set after the editor sends shutdown, the exit code depends on it
+    shutdown: bool,
+    // Set after the editor sends exit
+    pub exited: bool,
+}
+
+// Build a response to a request
+fn response(id: &Value, result: Result<Value, (i64, String)>) -> Value {
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": code, "message": message },
+        }),
+    }
+}
+
+// Build a notification, a message that doesn't need a response
+fn notification(method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "method": method, "params": params })
+}
+
+impl Server {
+    pub fn new(instruction_set: InstructionSet) -> Self {
+        Self {
+            instruction_set,
+            ..Default::default()
+        }
+    }
+
+    // The exit code of the server, 0 if the editor asked it to shut down first
+    pub fn exit_code(&self) -> i32 {
+        !self.shutdown as i32
+    }
+
+    // Send the errors and warnings in a file to the editor
+    fn publish_diagnostics(&self, uri: &str) -> Value {
+        let diagnostics: Vec<Value> = self
+            .documents
+            .get(uri)
+            .map(|document| {
+                document
+                    .diagnostics()
+                    .into_iter()
+                    .map(|diagnostic| {
+                        json!({
+                            "range": range(document.source(), &diagnostic.span),
+                            // 1 is an error, 2 is a warning
+                            "severity": if diagnostic.warning { 2 } else { 1 },
+                            "source": "algor",
+                            "message": diagnostic.message,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        notification(
+            "textDocument/publishDiagnostics",
+            json!({ "uri": uri, "diagnostics": diagnostics }),
+        )
+    }
+
+    // The document and byte offset a request is about (e.g. where the cursor is when the user hovers)
+    fn document_position<'a>(
+        &'a self,
+        params: &'a Value,
+    ) -> Result<(&'a str, &'a Document, usize), (i64, String)> {
+        let uri = params["textDocument"]["uri"]
+            .as_str()
+            .ok_or((INVALID_PARAMS, "Missing text document".to_string()))?;
+
+        let document = self
+            .documents
+            .get(uri)
+            .ok_or((REQUEST_FAILED, format!("`{uri}` is not open")))?;
+
+        let offset = offset(document.source(), &params["position"])
+            .ok_or((INVALID_PARAMS, "Missing position".to_string()))?;
+
+        Ok((uri, document, offset))
+    }
+
+    // The identifier under the cursor, requests like rename only make sense on identifiers
+    fn identifier_at(document: &Document, offset: usize) -> Option<&str> {
+        match document.token_at(offset) {
+            Some((Token::Identifier(identifier), _)) => Some(identifier),
+            _ => None,
+        }
+    }
+
+    fn initialize(&mut self, params: &Value) -> Result<Value, (i64, String)> {
+        // Editors can pick the instruction set with {"instructionSet": "extended"}
+        if let Some(instruction_set) = params["initializationOptions"]["instructionSet"].as_str() {
+            self.instruction_set = InstructionSet::try_from(instruction_set.to_string())
+                .map_err(|e| (INVALID_PARAMS, e))?;
+        }
+
+        Ok(json!({
+            "capabilities": {
+                // 1 means the editor sends the whole file every time it changes
+                "textDocumentSync": 1,
+                "hoverProvider": true,
+                "definitionProvider": true,
+                "referencesProvider": true,
+                "renameProvider": true,
+                "completionProvider": {},
+            },
+            "serverInfo": { "name": "algor-lsp", "version": env!("CARGO_PKG_VERSION") },
+        }))
+    }
+
+    fn hover(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let (_, document, offset) = self.document_position(params)?;
+
+        Ok(match document.hover(offset) {
+            Some(hover) => json!({ "contents": { "kind": "markdown", "value": hover } }),
+            None => Value::Null,
+        })
+    }
+
+    fn definition(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let (uri, document, offset) = self.document_position(params)?;
+
+        Ok(Self::identifier_at(document, offset)
+            .and_then(|identifier| document.definition(identifier))
+            .map_or(
+                Value::Null,
+                |span| json!({ "uri": uri, "range": range(document.source(), &span) }),
+            ))
+    }
+
+    fn references(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let (uri, document, offset) = self.document_position(params)?;
+
+        let Some(identifier) = Self::identifier_at(document, offset) else {
+            return Ok(Value::Null);
+        };
+
+        let definition = document.definition(identifier);
+        let include_declaration = params["context"]["includeDeclaration"]
+            .as_bool()
+            .unwrap_or(true);
+
+        Ok(document
+            .references(identifier)
+            .into_iter()
+            .filter(|span| include_declaration || Some(span) != definition.as_ref())
+            .map(|span| json!({ "uri": uri, "range": range(document.source(), &span) }))
+            .collect())
+    }
+
+    fn completion(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let (_, document, _) = self.document_position(params)?;
+        let instruction_set = document.instruction_set();
+
+        // Every mnemonic and alias, followed by the keywords that aren't instructions
+        let mut items: Vec<Value> = instruction_set
+            .mnemonics()
+            .iter()
+            .flat_map(|mnemonic| {
+                std::iter::once(mnemonic.name)
+                    .chain(mnemonic.aliases.iter().copied())
+                    .map(move |name| (name, Token::from(mnemonic.operation).to_string()))
+            })
+            .chain(
+                [("DAT", "Data"), ("MACRO", "Macro"), ("ENDM", "End of macro")]
+                    .map(|(name, detail)| (name, detail.to_string())),
+            )
+            .map(|(name, detail)| {
+                json!({ "label": name, "kind": COMPLETION_KEYWORD, "detail": detail })
+            })
+            .collect();
+
+        // Labels defined in the file with their addresses
+        items.extend(document.labels().into_iter().map(|(label, _)| {
+            let detail = document
+                .address(label)
+                .map(|address| format!("Address {address:02}"));
+
+            json!({ "label": **label, "kind": COMPLETION_VARIABLE, "detail": detail })
+        }));
+
+        // Ditto for macros
+        items.extend(document.macros().into_keys().map(
+            |name| json!({ "label": **name, "kind": COMPLETION_FUNCTION, "detail": "Macro" }),
+        ));
+
+        Ok(Value::Array(items))
+    }
+
+    fn rename(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let (uri, document, offset) = self.document_position(params)?;
+
+        let identifier = Self::identifier_at(document, offset)
+            .ok_or((REQUEST_FAILED, "Only labels can be renamed".to_string()))?;
+
+        let new_name = params["newName"]
+            .as_str()
+            .ok_or((INVALID_PARAMS, "Missing new name".to_string()))?;
+
+        // The new name has to be lexed as an identifier, so it can only have letters and can't be a keyword
+        let valid = !new_name.is_empty()
+            && new_name.chars().all(|c| c.is_ascii_alphabetic())
+            && document.instruction_set().mnemonic(new_name).is_none()
+            && !["DAT", "MACRO", "ENDM"]
+                .iter()
+                .any(|keyword| keyword.eq_ignore_ascii_case(new_name));
+
+        if !valid {
+            return Err((
+                REQUEST_FAILED,
+                format!(
+                    "`{new_name}` can't be used as a label, labels can only contain letters and can't be an instruction"
+                ),
+            ));
+        }
+
+        let edits: Vec<Value> = document
+            .references(identifier)
+            .into_iter()
+            .map(|span| json!({ "range": range(document.source(), &span), "newText": new_name }))
+            .collect();
+
+        Ok(json!({ "changes": { uri: edits } }))
+    }
+
+    // Handle a single message from the editor, returning the messages to send back
+    pub fn handle(&mut self, message: Value) -> Vec<Value> {
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+
+        // Requests have an id that the response must use, notifications don't need a response
+        if let Some(id) = message.get("id") {
+            let result = match method {
+                "initialize" => self.initialize(params),
+                "shutdown" => {
+                    self.shutdown = true;
+                    Ok(Value::Null)
+                }
+                "textDocument/hover" => self.hover(params),
+                "textDocument/definition" => self.definition(params),
+                "textDocument/references" => self.references(params),
+                "textDocument/completion" => self.completion(params),
+                "textDocument/rename" => self.rename(params),
+                _ => Err((METHOD_NOT_FOUND, format!("Unsupported method `{method}`"))),
+            };
+
+            return vec![response(id, result)];
+        }
+
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+
+        match method {
+            "exit" => self.exited = true,
+
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                self.documents.insert(
+                    uri.to_string(),
+                    Document::new(text.to_string(), self.instruction_set),
+                );
+
+                return vec![self.publish_diagnostics(uri)];
+            }
+
+            // Only whole files are sent, so the last change has the latest text
+            "textDocument/didChange" => {
+                if let Some(text) = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str())
+                {
+                    self.documents.insert(
+                        uri.to_string(),
+                        Document::new(text.to_string(), self.instruction_set),
+                    );
+                }
+
+                return vec![self.publish_diagnostics(uri)];
+            }
+
+            // Clear the diagnostics of a file once it's closed
+            "textDocument/didClose" => {
+                self.documents.remove(uri);
+                return vec![self.publish_diagnostics(uri)];
+            }
+
+            _ => {}
+        }
+
+        Vec::new()
+    }
+}
+
+// Run the language server over standard input and output until the editor tells it to exit, returning the exit code
+pub fn run(args: Vec<String>) -> i32 {
+    // The instruction set can also be picked on the command line for editors that can't send initialization options
+    let instruction_set = match args.as_slice() {
+        [] => InstructionSet::default(),
+        [flag, name] if flag == "--instruction-set" => {
+            match InstructionSet::try_from(name.clone()) {
+                Ok(instruction_set) => instruction_set,
+                Err(e) => {
+                    eprintln!("{e}");
+                    return 2;
+                }
+            }
+        }
+        _ => {
+            eprintln!("Usage: algor-lsp [--instruction-set classic|extended|exam-board]");
+            return 2;
+        }
+    };
+
+    let mut server = Server::new(instruction_set);
+    let mut stdin = io::stdin().lock();
+    let mut stdout = io::stdout().lock();
+
+    while !server.exited {
+        let message = match rpc::read(&mut stdin) {
+            Ok(Some(message)) => message,
+            // The editor closed the connection without saying exit
+            Ok(None) => return 1,
+            Err(e) => {
+                eprintln!("Failed to read message: {e}");
+                return 1;
+            }
+        };
+
+        for message in server.handle(message) {
+            if let Err(e) = rpc::write(&mut stdout, &message) {
+                eprintln!("Failed to write message: {e}");
+                return 1;
+            }
+        }
+    }
+
+    server.exit_code()
+}
@@ -0,0 +1,522 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::backend::compiler::{
+    self, analysis,
+    analysis::Warning,
+    generator::{self, GeneratorError},
+    lexer::{InvalidCharacter, Lexer, Span, Token},
+    macros::{MAX_DEPTH, MacroError, MacroExpander},
+    parser::Parser,
+};
+use crate::shared::isa::InstructionSet;
+
+// An error or warning attached to part of the source code
+#[derive(PartialEq, Debug)]
+pub struct Diagnostic {
+    pub span: Span,
+    pub message: String,
+    // Warnings don't stop the program from being assembled, errors do
+    pub warning: bool,
+}
+
+impl Diagnostic {
+    // The compiler's errors start with a line saying which stage failed (e.g. "Encountered an error while parsing..."), only the last line is useful next to the code
+    fn error(span: Span, error: &dyn std::fmt::Display) -> Self {
+        let message = error.to_string();
+
+        Self {
+            span,
+            message: message.lines().last().unwrap_or_default().to_string(),
+            warning: false,
+        }
+    }
+}
+
+// The tokens of a single line without the newline
+type Line<'a> = &'a [(Token, Span)];
+
+// A single line of tokens split into the label it defines (if any) and the rest of the line
+struct Statement<'a> {
+    label: Option<&'a (Token, Span)>,
+    rest: Line<'a>,
+}
+
+// An open LMC source file, lexed once every time it changes so requests from the editor can be answered quickly
+pub struct Document {
+    source: String,
+    instruction_set: InstructionSet,
+    // The tokens in the file without comments, or the reason the file couldn't be lexed
+    tokens: Result<Vec<(Token, Span)>, InvalidCharacter>,
+}
+
+impl Document {
+    pub fn new(source: String, instruction_set: InstructionSet) -> Self {
+        let tokens = Lexer::with_instruction_set(&source, instruction_set)
+            .lex_spanned()
+            .map(|mut tokens| {
+                tokens.retain(|(token, _)| !matches!(token, Token::Comment(_)));
+                tokens
+            });
+
+        Self {
+            source,
+            instruction_set,
+            tokens,
+        }
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn instruction_set(&self) -> InstructionSet {
+        self.instruction_set
+    }
+
+    // Every token in the file, empty if the file couldn't be lexed
+    fn tokens(&self) -> &[(Token, Span)] {
+        self.tokens.as_deref().unwrap_or_default()
+    }
+
+    // The tokens of each line without the newlines
+    fn lines(&self) -> Vec<Line<'_>> {
+        self.tokens()
+            .split(|(token, _)| *token == Token::Newline)
+            .collect()
+    }
+
+    // The names of every macro defined in the file (e.g. double in MACRO double X) and where the name is
+    pub fn macros(&self) -> HashMap<&Rc<str>, &Span> {
+        self.lines()
+            .into_iter()
+            .filter_map(|line| match line {
+                [(Token::Macro, _), (Token::Identifier(name), span), ..] => Some((name, span)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // Split off the label at the start of a line the same way the macro expander does (see defines_label in src/backend/compiler/macros.rs)
+    fn statement<'a>(line: Line<'a>, macros: &HashMap<&Rc<str>, &Span>) -> Statement<'a> {
+        let label = match line {
+            // A macro used by itself is never a label
+            [(Token::Identifier(name), _), ..] if macros.contains_key(name) => None,
+            [first @ (Token::Identifier(_), _), (next, _), ..] => match next {
+                Token::Number(_) | Token::Newline => None,
+                Token::Identifier(name) if !macros.contains_key(name) => None,
+                _ => Some(first),
+            },
+            _ => None,
+        };
+
+        Statement {
+            label,
+            rest: &line[label.is_some() as usize..],
+        }
+    }
+
+    // The name of the macro a line uses (e.g. double in start double X), None if it doesn't use one
+    fn invocation<'a>(
+        statement: &Statement<'a>,
+        macros: &HashMap<&Rc<str>, &Span>,
+    ) -> Option<&'a Rc<str>> {
+        match statement.rest.first() {
+            Some((Token::Identifier(name), _)) if macros.contains_key(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    // Labels defined in the file and the span of their definition, in the order they are defined
+    pub fn labels(&self) -> Vec<(&Rc<str>, &Span)> {
+        let macros = self.macros();
+
+        self.lines()
+            .into_iter()
+            .filter_map(|line| match Self::statement(line, &macros).label {
+                Some((Token::Identifier(label), span)) => Some((label, span)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // Where a label or macro is defined
+    pub fn definition(&self, name: &str) -> Option<Span> {
+        self.labels()
+            .into_iter()
+            .find(|(label, _)| ***label == *name)
+            .map(|(_, span)| span.clone())
+            .or_else(|| {
+                self.macros()
+                    .into_iter()
+                    .find(|(macro_name, _)| ***macro_name == *name)
+                    .map(|(_, span)| span.clone())
+            })
+    }
+
+    // Every use of an identifier, including where it is defined
+    pub fn references(&self, name: &str) -> Vec<Span> {
+        self.tokens()
+            .iter()
+            .filter_map(|(token, span)| match token {
+                Token::Identifier(identifier) if **identifier == *name => Some(span.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // The token under the cursor, a cursor just after the end of a token still counts as being on it
+    pub fn token_at(&self, offset: usize) -> Option<&(Token, Span)> {
+        self.tokens()
+            .iter()
+            .filter(|(token, _)| *token != Token::Newline)
+            .find(|(_, span)| span.start <= offset && offset <= span.end)
+    }
+
+    // How many memory locations each macro takes up once it's expanded
+    fn macro_sizes(&self) -> HashMap<Rc<str>, usize> {
+        let macros = self.macros();
+        let mut bodies: HashMap<Rc<str>, Vec<Line>> = HashMap::new();
+        let mut current = None;
+
+        for line in self.lines() {
+            match (line.first(), &current) {
+                (Some((Token::Macro, _)), _) => {
+                    current = match line.get(1) {
+                        Some((Token::Identifier(name), _)) => Some(Rc::clone(name)),
+                        _ => None,
+                    };
+                    bodies.extend(current.clone().map(|name| (name, Vec::new())));
+                }
+                (Some((Token::EndMacro, _)), _) => current = None,
+                (_, Some(name)) => bodies.get_mut(name).unwrap().push(line),
+                _ => {}
+            }
+        }
+
+        // Count a body, expanding the macros it uses, giving up on macros that use themselves
+        fn size(
+            name: &Rc<str>,
+            bodies: &HashMap<Rc<str>, Vec<Line>>,
+            macros: &HashMap<&Rc<str>, &Span>,
+            depth: usize,
+        ) -> usize {
+            if depth >= MAX_DEPTH {
+                return 0;
+            }
+
+            bodies[name]
+                .iter()
+                .map(|line| {
+                    let statement = Document::statement(line, macros);
+
+                    match Document::invocation(&statement, macros) {
+                        Some(name) if bodies.contains_key(name) => {
+                            size(name, bodies, macros, depth + 1)
+                        }
+                        _ => !statement.rest.is_empty() as usize,
+                    }
+                })
+                .sum()
+        }
+
+        bodies
+            .keys()
+            .map(|name| (Rc::clone(name), size(name, &bodies, &macros, 0)))
+            .collect()
+    }
+
+    // The first address and the number of memory locations each line assembles to, lines inside of macro definitions assemble to nothing
+    fn addresses(&self) -> Vec<(usize, usize)> {
+        let macros = self.macros();
+        let sizes = self.macro_sizes();
+        let mut address = 0;
+        let mut in_macro = false;
+
+        self.lines()
+            .into_iter()
+            .map(|line| {
+                let statement = Self::statement(line, &macros);
+
+                let size = match statement.rest.first() {
+                    Some((Token::Macro, _)) => {
+                        in_macro = true;
+                        0
+                    }
+                    Some((Token::EndMacro, _)) => {
+                        in_macro = false;
+                        0
+                    }
+                    _ if in_macro => 0,
+                    _ => match Self::invocation(&statement, &macros) {
+                        Some(name) => sizes.get(name).copied().unwrap_or(0),
+                        None => !statement.rest.is_empty() as usize,
+                    },
+                };
+
+                address += size;
+                (address - size, size)
+            })
+            .collect()
+    }
+
+    // The span of the line a memory location is assembled from
+    fn line_of_address(&self, address: usize) -> Option<Span> {
+        self.lines()
+            .into_iter()
+            .zip(self.addresses())
+            .find(|(_, (start, size))| (*start..start + size).contains(&address))
+            .and_then(|(line, _)| Some(line.first()?.1.start..line.last()?.1.end))
+    }
+
+    // The address of a label
+    pub fn address(&self, label: &str) -> Option<usize> {
+        let macros = self.macros();
+
+        self.lines()
+            .into_iter()
+            .zip(self.addresses())
+            .find(|(line, _)| {
+                matches!(
+                    Self::statement(line, &macros).label,
+                    Some((Token::Identifier(name), _)) if **name == *label
+                )
+            })
+            .map(|(_, (address, _))| address)
+    }
+
+    // A short description of the token under the cursor (e.g. the address and machine code of a label)
+    pub fn hover(&self, offset: usize) -> Option<String> {
+        let (token, _) = self.token_at(offset)?;
+
+        if let Some(operation) = token.operation() {
+            let mnemonic = self.instruction_set.encoding(operation)?;
+
+            // The same help as the instruction reference pane in the algor IDE
+            return Some(format!(
+                "**{token}** `{}`\n\n{}\n\n`{}`",
+                mnemonic.machine_code(),
+                operation.description(),
+                operation.effect()
+            ));
+        }
+
+        let Token::Identifier(label) = token else {
+            return None;
+        };
+
+        if self.macros().contains_key(label) {
+            return Some(format!("Macro `{label}`"));
+        }
+
+        let address = self.address(label)?;
+
+        // Only show what's stored at the address if the program can be assembled
+        match compiler::compile_with(&self.source, self.instruction_set) {
+            Ok(memory) if address < memory.len() => Some(format!(
+                "`{label}`: address {address:02}, contains {}",
+                memory[address]
+            )),
+            _ => Some(format!("`{label}`: address {address:02}")),
+        }
+    }
+
+    // Errors in the structure of macro definitions and uses, checked line by line so every mistake can be shown at once
+    fn macro_diagnostics(&self) -> Vec<Diagnostic> {
+        let macros = self.macros();
+        let mut parameters: HashMap<&Rc<str>, usize> = HashMap::new();
+        let mut diagnostics = Vec::new();
+        let mut open: Option<(&Rc<str>, Span)> = None;
+
+        for line in self.lines() {
+            let Some(first) = line.first() else {
+                continue;
+            };
+            let span = first.1.start..line.last().unwrap().1.end;
+            let statement = Self::statement(line, &macros);
+
+            let error = match (&first.0, &open) {
+                (Token::Macro, Some((name, _))) => {
+                    Some(MacroError::NestedDefinition(Rc::clone(name)))
+                }
+                (Token::Macro, None) => match line.get(1) {
+                    Some((Token::Identifier(name), _)) => {
+                        open = Some((name, span.clone()));
+                        parameters.insert(name, line.len() - 2);
+
+                        line[2..]
+                            .iter()
+                            .find(|(token, _)| !matches!(token, Token::Identifier(_)))
+                            .map(|(token, _)| MacroError::InvalidParameter(Rc::new(token.clone())))
+                    }
+                    _ => Some(MacroError::MissingName),
+                },
+                (Token::EndMacro, Some(_)) => {
+                    open = None;
+                    None
+                }
+                (Token::EndMacro, None) => Some(MacroError::UnexpectedEnd),
+                _ => Self::invocation(&statement, &macros).and_then(|name| {
+                    let expected = *parameters.get(name)?;
+                    let received = statement.rest.len() - 1;
+
+                    (expected != received).then(|| MacroError::ArgumentCount {
+                        name: Rc::clone(name),
+                        expected,
+                        received,
+                    })
+                }),
+            };
+
+            diagnostics.extend(error.map(|error| Diagnostic::error(span, &error)));
+        }
+
+        if let Some((name, span)) = open {
+            diagnostics.push(Diagnostic::error(
+                span,
+                &MacroError::Unterminated(Rc::clone(name)),
+            ));
+        }
+
+        diagnostics
+    }
+
+    // Syntax errors, each line is parsed by itself so every mistake can be shown at once
+    fn parser_diagnostics(&self) -> Vec<Diagnostic> {
+        let macros = self.macros();
+
+        self.lines()
+            .into_iter()
+            .filter(|line| {
+                let statement = Self::statement(line, &macros);
+
+                // Macro definitions and uses are checked separately
+                !line.is_empty()
+                    && !matches!(
+                        statement.rest.first(),
+                        Some((Token::Macro | Token::EndMacro, _))
+                    )
+                    && Self::invocation(&statement, &macros).is_none()
+            })
+            .filter_map(|line| {
+                let tokens = line.iter().map(|(token, _)| token.clone()).collect();
+                let error = Parser::with_instruction_set(tokens, self.instruction_set)
+                    .parse()
+                    .err()?;
+
+                Some(Diagnostic::error(
+                    line.first()?.1.start..line.last()?.1.end,
+                    &error,
+                ))
+            })
+            .collect()
+    }
+
+    // Where a warning should be shown, labels are shown where they are defined and addresses on the line they are assembled from
+    fn warning_span(&self, warning: &Warning) -> Option<Span> {
+        match warning {
+            Warning::UnreachableCode(address) | Warning::MissingHalt(address) => {
+                self.line_of_address(*address as usize)
+            }
+            Warning::ProgramTooLarge(_) => self.line_of_address(100),
+            Warning::UninitialisedData(label)
+            | Warning::BranchToData(label)
+            | Warning::LabelAsCodeAndData(label) => self
+                .definition(label)
+                .or_else(|| self.references(label).into_iter().next()),
+        }
+    }
+
+    // Every error and warning in the file, the same checks the algor IDE does when assembling
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        let tokens = match &self.tokens {
+            Ok(tokens) => tokens,
+            Err(error) => {
+                // The lexer stops at the first character it doesn't recognise, so its first appearance on the line must be the one
+                let line_start = self
+                    .source
+                    .split_inclusive('\n')
+                    .take(error.line_column.0 - 1)
+                    .map(str::len)
+                    .sum::<usize>();
+                let start = line_start
+                    + self.source[line_start..]
+                        .find(error.character)
+                        .unwrap_or_default();
+                let length = self.source[start..]
+                    .chars()
+                    .next()
+                    .map_or(0, char::len_utf8);
+
+                return vec![Diagnostic::error(start..start + length, error)];
+            }
+        };
+
+        let mut diagnostics = self.macro_diagnostics();
+        diagnostics.extend(self.parser_diagnostics());
+
+        if !diagnostics.is_empty() {
+            return diagnostics;
+        }
+
+        // Errors that can only be found by looking at the whole program are shown on the first line if they can't be traced back to the source code
+        let first_line = self
+            .lines()
+            .into_iter()
+            .find(|line| !line.is_empty())
+            .and_then(|line| Some(line.first()?.1.start..line.last()?.1.end))
+            .unwrap_or(0..0);
+
+        let tokens = tokens.iter().map(|(token, _)| token.clone()).collect();
+
+        let program = match MacroExpander::new(tokens)
+            .expand()
+            .map_err(|error| {
+                let span = match &error {
+                    MacroError::RecursionLimit(name) => self.references(name).into_iter().nth(1),
+                    _ => None,
+                };
+                Diagnostic::error(span.unwrap_or(first_line.clone()), &error)
+            })
+            .and_then(|tokens| {
+                Parser::with_instruction_set(tokens, self.instruction_set)
+                    .parse()
+                    .map_err(|error| Diagnostic::error(first_line.clone(), &error))
+            }) {
+            Ok(program) => program,
+            Err(diagnostic) => return vec![diagnostic],
+        };
+
+        for warning in analysis::analyse(&program) {
+            diagnostics.push(Diagnostic {
+                span: self.warning_span(&warning).unwrap_or(first_line.clone()),
+                message: warning.to_string(),
+                warning: true,
+            });
+        }
+
+        match generator::generate(program, self.instruction_set) {
+            // An undefined label is shown everywhere it's used
+            Err(GeneratorError::InvalidIdentifier(error)) => {
+                let references = self.references(&error.identifier);
+
+                if references.is_empty() {
+                    diagnostics.push(Diagnostic::error(first_line, &error));
+                }
+
+                for span in references {
+                    diagnostics.push(Diagnostic::error(span, &error));
+                }
+            }
+            // Ditto warning_span comment, the first line that doesn't fit in memory
+            Err(GeneratorError::ProgramTooLarge(error)) => diagnostics.push(Diagnostic::error(
+                self.line_of_address(100).unwrap_or(first_line),
+                &error,
+            )),
+            Err(error) => diagnostics.push(Diagnostic::error(first_line, &error)),
+            Ok(_) => {}
+        }
+
+        diagnostics
+    }
+}
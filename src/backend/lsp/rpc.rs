@@ -0,0 +1,52 @@
+use std::io::{self, BufRead, Write};
+
+use serde_json::Value;
+
+// Read a single JSON-RPC message, each message is a set of headers followed by a blank line and then the JSON itself (e.g. "Content-Length: 2\r\n\r\n{}"), None when the editor closes the connection
+pub fn read(reader: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut length = None;
+
+    loop {
+        let mut header = String::new();
+
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+
+        let header = header.trim_end();
+
+        // A blank line ends the headers
+        if header.is_empty() {
+            break;
+        }
+
+        // Content-Type is the only other header and there is only one type, so it can be ignored
+        if let Some((name, value)) = header.split_once(':')
+            && name.eq_ignore_ascii_case("Content-Length")
+        {
+            length = value.trim().parse().ok();
+        }
+    }
+
+    let Some(length) = length else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Message without a Content-Length header",
+        ));
+    };
+
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+// Write a single JSON-RPC message with the headers the editor expects
+pub fn write(writer: &mut impl Write, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+
+    write!(writer, "Content-Length: {}\r\n\r\n{body}", body.len())?;
+    writer.flush()
+}
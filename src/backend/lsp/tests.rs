@@ -0,0 +1,146 @@
+use serde_json::json;
+
+use crate::backend::lsp::{Server, document::Document, offset, position};
+use crate::shared::isa::InstructionSet;
+
+const SOURCE: &str = r#"MACRO double X
+LDA X
+ADD X
+ENDM
+start INP
+STA num
+double num
+BRZ end
+OUT
+end HLT
+num DAT
+"#;
+
+#[test]
+// 3.1.1
+fn document_labels() {
+    let document = Document::new(SOURCE.to_string(), InstructionSet::Classic);
+
+    // The macro is two instructions long, so everything after it is two addresses further on
+    assert_eq!(document.address("start"), Some(0));
+    assert_eq!(document.address("end"), Some(6));
+    assert_eq!(document.address("num"), Some(7));
+
+    assert_eq!(document.definition("num"), Some(81..84));
+    assert_eq!(document.references("num").len(), 3);
+    assert_eq!(document.diagnostics(), vec![]);
+}
+
+#[test]
+// 3.1.2
+fn document_diagnostics() {
+    let document = Document::new("LDA\nOUT 5\nADD X\n".to_string(), InstructionSet::Classic);
+    let diagnostics = document.diagnostics();
+
+    // Every line with a syntax error is reported, not just the first
+    assert_eq!(
+        diagnostics
+            .iter()
+            .map(|d| d.span.clone())
+            .collect::<Vec<_>>(),
+        vec![0..3, 4..9]
+    );
+
+    let document = Document::new("ADD X\nHLT\n".to_string(), InstructionSet::Classic);
+
+    assert_eq!(document.diagnostics()[0].span, 4..5);
+    assert_eq!(
+        document.diagnostics()[0].message,
+        "Invalid identifier `X`".to_string()
+    );
+}
+
+#[test]
+// 3.1.3
+fn server_rename() {
+    let mut server = Server::default();
+    let uri = "file:///count.lmc";
+
+    server.handle(json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": { "textDocument": { "uri": uri, "text": SOURCE } },
+    }));
+
+    let response = server.handle(json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "textDocument/rename",
+        "params": {
+            "textDocument": { "uri": uri },
+            "position": { "line": 10, "character": 1 },
+            "newName": "total",
+        },
+    }));
+
+    assert_eq!(
+        response[0]["result"]["changes"][uri]
+            .as_array()
+            .unwrap()
+            .len(),
+        3
+    );
+
+    // Numbers can't be in labels
+    let response = server.handle(json!({
+        "jsonrpc": "2.0",
+        "id": 2,
+        "method": "textDocument/rename",
+        "params": {
+            "textDocument": { "uri": uri },
+            "position": { "line": 10, "character": 1 },
+            "newName": "total2",
+        },
+    }));
+
+    assert!(response[0].get("error").is_some());
+}
+
+#[test]
+// 3.1.4
+fn positions() {
+    let source = "// é\nLDA X";
+
+    assert_eq!(position(source, 9), json!({ "line": 1, "character": 3 }));
+    assert_eq!(
+        offset(source, &json!({ "line": 0, "character": 4 })),
+        Some(5)
+    );
+    assert_eq!(
+        offset(source, &json!({ "line": 1, "character": 40 })),
+        Some(11)
+    );
+}
+
+#[test]
+// 3.1.5
+fn non_ascii() {
+    let mut server = Server::default();
+    let uri = "file:///euro.lmc";
+
+    // The diagnostic covers the whole character rather than ending part way through it
+    let response = server.handle(json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": { "textDocument": { "uri": uri, "text": "LDA X // ok\n€\n" } },
+    }));
+
+    let diagnostic = &response[0]["params"]["diagnostics"][0];
+    assert_eq!(
+        diagnostic["range"],
+        json!({
+            "start": { "line": 1, "character": 0 },
+            "end": { "line": 1, "character": 1 },
+        })
+    );
+    assert!(diagnostic["message"].as_str().unwrap().contains('€'));
+
+    // Offsets in the middle of a character or past the end are moved back
+    assert_eq!(position("€", 1), json!({ "line": 0, "character": 0 }));
+    assert_eq!(position("€", 10), json!({ "line": 0, "character": 1 }));
+}
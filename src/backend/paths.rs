@@ -0,0 +1,260 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+use crate::backend::config::ConfigError;
+
+// Environment variables that move where algor keeps its files (e.g. off a network share on a lab machine), command line flags take priority over these
+pub const CONFIG_VAR: &str = "ALGOR_CONFIG";
+pub const DATA_VAR: &str = "ALGOR_DATA_DIR";
+pub const LESSONS_VAR: &str = "ALGOR_LESSONS_DIR";
+
+// Paths given on the command line (see src/backend/cli.rs)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Overrides {
+    pub config: Option<PathBuf>,
+    pub data: Option<PathBuf>,
+    pub lessons: Option<PathBuf>,
+}
+
+// Where algor keeps its files
+#[derive(Clone, Debug, PartialEq)]
+pub struct Paths {
+    // The config file
+    pub config: PathBuf,
+    // The config shared by every user of the computer, which can't be moved by the user so its policy can't be dodged
+    pub system: PathBuf,
+    // The directory the session and autosaved buffers are kept in
+    pub data: PathBuf,
+    // Used instead of the lessons directory in the config for this run, if given
+    pub lessons: Option<PathBuf>,
+}
+
+impl Paths {
+    // Work out where files are kept from the command line, environment variables, and the platform's conventions (in that order)
+    pub fn resolve(overrides: Overrides) -> Result<Self, ConfigError> {
+        Self::resolve_with(overrides, env::home_dir(), |name| {
+            env::var_os(name).map(PathBuf::from)
+        })
+    }
+
+    // Ditto resolve comment, looking up the home directory and environment variables with the values given (so it can be tested)
+    fn resolve_with(
+        overrides: Overrides,
+        home: Option<PathBuf>,
+        var: impl Fn(&str) -> Option<PathBuf>,
+    ) -> Result<Self, ConfigError> {
+        // Empty variables count as unset
+        let var = |name: &str| var(name).filter(|value| !value.as_os_str().is_empty());
+        let home = home.as_deref();
+
+        let config = match overrides.config.or_else(|| var(CONFIG_VAR)) {
+            Some(config) => config,
+            None => config_home(home, &var)
+                .ok_or(ConfigError::NoHomeDirectory)?
+                .join("algor")
+                .join("config.toml"),
+        };
+
+        let data = match overrides.data.or_else(|| var(DATA_VAR)) {
+            Some(data) => data,
+            None => data_home(home, &var)
+                .ok_or(ConfigError::NoHomeDirectory)?
+                .join("algor"),
+        };
+
+        Ok(Self {
+            config,
+            system: system_config(),
+            data,
+            lessons: overrides.lessons.or_else(|| var(LESSONS_VAR)),
+        })
+    }
+
+    // Used when there is nowhere else to keep files (i.e. there is no home directory), so the application can still open
+    pub fn fallback() -> Self {
+        let algor_dir = env::temp_dir().join("algor");
+
+        Self {
+            config: algor_dir.join("config.toml"),
+            system: system_config(),
+            data: algor_dir,
+            lessons: None,
+        }
+    }
+
+    // The recent files and what the user was last doing (see src/backend/session.rs)
+    pub fn session(&self) -> PathBuf {
+        self.data.join("session.toml")
+    }
+
+    // The unsaved editor buffers (see src/backend/recovery.rs)
+    pub fn recovery(&self) -> PathBuf {
+        self.data.join("recovery")
+    }
+
+    // Custom themes are kept next to the config, so they move with it (see src/frontend/util/theme.rs)
+    pub fn themes(&self) -> PathBuf {
+        self.config.with_file_name("themes")
+    }
+}
+
+// XDG variables must be absolute paths, relative ones are ignored
+fn xdg(var: &impl Fn(&str) -> Option<PathBuf>, name: &str) -> Option<PathBuf> {
+    var(name).filter(|path| path.is_absolute())
+}
+
+/* The directory configs are kept in:
+- Windows: %APPDATA% (AppData\Roaming)
+- Linux, BSDs, and other unix-like operating systems: $XDG_CONFIG_HOME or ~/.config
+- macOS: $XDG_CONFIG_HOME if it is set, otherwise ~/Library/Application Support */
+fn config_home(home: Option<&Path>, var: &impl Fn(&str) -> Option<PathBuf>) -> Option<PathBuf> {
+    if cfg!(target_os = "windows") {
+        var("APPDATA").or_else(|| Some(home?.join("AppData").join("Roaming")))
+    } else if let Some(config_home) = xdg(var, "XDG_CONFIG_HOME") {
+        Some(config_home)
+    } else if cfg!(target_os = "macos") {
+        Some(home?.join("Library").join("Application Support"))
+    } else {
+        Some(home?.join(".config"))
+    }
+}
+
+// Ditto config_home comment but for data, using %LOCALAPPDATA% (AppData\Local) on Windows and $XDG_DATA_HOME or ~/.local/share on unix-like operating systems
+fn data_home(home: Option<&Path>, var: &impl Fn(&str) -> Option<PathBuf>) -> Option<PathBuf> {
+    if cfg!(target_os = "windows") {
+        var("LOCALAPPDATA").or_else(|| Some(home?.join("AppData").join("Local")))
+    } else if let Some(data_home) = xdg(var, "XDG_DATA_HOME") {
+        Some(data_home)
+    } else if cfg!(target_os = "macos") {
+        Some(home?.join("Library").join("Application Support"))
+    } else {
+        Some(home?.join(".local").join("share"))
+    }
+}
+
+/* The system config file:
+- Windows: C:\ProgramData\algor\config.toml
+- macOS: /Library/Application Support/algor/config.toml
+- Linux, BSDs, and other unix-like operating systems: /etc/algor/config.toml
+These are never looked up from environment variables (e.g. %PROGRAMDATA%), as anyone can set those for their own programs to get around the policy */
+fn system_config() -> PathBuf {
+    let directory = if cfg!(target_os = "windows") {
+        PathBuf::from("C:\\ProgramData")
+    } else if cfg!(target_os = "macos") {
+        PathBuf::from("/Library/Application Support")
+    } else {
+        PathBuf::from("/etc")
+    };
+
+    directory.join("algor").join("config.toml")
+}
+
+// Where lessons are kept unless the user picks somewhere else, an algor folder in $XDG_DOCUMENTS_DIR or ~/Documents (or in the current directory if there is no home directory)
+pub fn default_lessons_directory() -> PathBuf {
+    let var = |name: &str| env::var_os(name).map(PathBuf::from);
+
+    xdg(&var, "XDG_DOCUMENTS_DIR")
+        .or_else(|| Some(env::home_dir()?.join("Documents")))
+        .unwrap_or_else(|| PathBuf::from("Documents"))
+        .join("algor")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::backend::paths::{Overrides, Paths};
+
+    // Look up variables from a list instead of the environment
+    fn vars(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<PathBuf> {
+        let vars = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), PathBuf::from(value)))
+            .collect::<Vec<_>>();
+
+        move |name| {
+            vars.iter()
+                .find(|(var, _)| var == name)
+                .map(|(_, value)| value.clone())
+        }
+    }
+
+    // 9.1
+    #[test]
+    #[cfg(all(unix, not(target_os = "macos")))]
+    fn paths_xdg() {
+        let home = Some(PathBuf::from("/home/student"));
+
+        let paths = Paths::resolve_with(Overrides::default(), home.clone(), vars(&[])).unwrap();
+        assert_eq!(
+            paths.config,
+            PathBuf::from("/home/student/.config/algor/config.toml")
+        );
+        assert_eq!(
+            paths.data,
+            PathBuf::from("/home/student/.local/share/algor")
+        );
+        assert_eq!(paths.lessons, None);
+        assert_eq!(paths.system, PathBuf::from("/etc/algor/config.toml"));
+
+        // Relative XDG directories are ignored
+        let paths = Paths::resolve_with(
+            Overrides::default(),
+            home.clone(),
+            vars(&[
+                ("XDG_CONFIG_HOME", "/local/config"),
+                ("XDG_DATA_HOME", "relative/data"),
+            ]),
+        )
+        .unwrap();
+        assert_eq!(
+            paths.config,
+            PathBuf::from("/local/config/algor/config.toml")
+        );
+        assert_eq!(
+            paths.data,
+            PathBuf::from("/home/student/.local/share/algor")
+        );
+
+        // Without a home directory there is nowhere to put the config
+        assert!(Paths::resolve_with(Overrides::default(), None, vars(&[])).is_err());
+    }
+
+    // 9.2
+    #[test]
+    fn paths_overrides() {
+        let variables = vars(&[
+            ("ALGOR_CONFIG", "/srv/algor/config.toml"),
+            ("ALGOR_DATA_DIR", "/tmp/algor"),
+            ("ALGOR_LESSONS_DIR", ""),
+            ("PROGRAMDATA", "/home/student"),
+        ]);
+
+        // Variables work without a home directory, and empty ones are ignored
+        let paths = Paths::resolve_with(Overrides::default(), None, &variables).unwrap();
+        assert_eq!(paths.config, PathBuf::from("/srv/algor/config.toml"));
+        assert_eq!(paths.data, PathBuf::from("/tmp/algor"));
+        assert_eq!(paths.lessons, None);
+        assert_eq!(paths.session(), PathBuf::from("/tmp/algor/session.toml"));
+        assert_eq!(paths.themes(), PathBuf::from("/srv/algor/themes"));
+        // The system config can't be moved by the user
+        assert!(!paths.system.starts_with("/home/student"));
+
+        // Flags take priority over variables
+        let paths = Paths::resolve_with(
+            Overrides {
+                config: Some("/media/usb/config.toml".into()),
+                data: None,
+                lessons: Some("/srv/lessons".into()),
+            },
+            None,
+            &variables,
+        )
+        .unwrap();
+        assert_eq!(paths.config, PathBuf::from("/media/usb/config.toml"));
+        assert_eq!(paths.data, PathBuf::from("/tmp/algor"));
+        assert_eq!(paths.lessons, Some(PathBuf::from("/srv/lessons")));
+    }
+}
@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::Path,
+    time::Duration,
+};
+
+// How often unsaved editor buffers are written to the recovery directory
+pub const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+// The text of an editor that hasn't been saved, kept so it can be restored if the application crashes
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Buffer {
+    // Shown in the recovery prompt (e.g. the file name of a sandbox tab or the title of a lesson)
+    pub name: String,
+    // The file the buffer was opened from or last saved to, if any
+    #[serde(default)]
+    pub path: Option<String>,
+    pub text: String,
+}
+
+// Replace the buffers in the recovery directory, one file each so a file being cut off only loses that buffer
+pub fn save(directory: &Path, buffers: &[Buffer]) -> io::Result<()> {
+    fs::create_dir_all(directory)?;
+
+    // Write to a temporary file first and rename it over the old one, so crashing part way through never leaves a half-written buffer
+    for (i, buffer) in buffers.iter().enumerate() {
+        let buffer = toml::to_string(buffer).map_err(io::Error::other)?;
+        let temporary = directory.join(format!("{i}.toml.tmp"));
+
+        fs::File::create(&temporary)?.write_all(buffer.as_bytes())?;
+        fs::rename(&temporary, directory.join(format!("{i}.toml")))?;
+    }
+
+    // Remove buffers left over from the last save
+    for entry in fs::read_dir(directory)?.filter_map(|entry| entry.ok()) {
+        let stale = entry
+            .path()
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.parse::<usize>().ok())
+            .is_none_or(|index| index >= buffers.len());
+
+        if stale {
+            fs::remove_file(entry.path())?;
+        }
+    }
+
+    Ok(())
+}
+
+// Read every buffer in the recovery directory in the order they were saved, skipping any that can't be read
+pub fn load(directory: &Path) -> Vec<Buffer> {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return Vec::new();
+    };
+
+    let mut buffers = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let index = entry.path().file_stem()?.to_str()?.parse::<usize>().ok()?;
+            let buffer = toml::from_str(&fs::read_to_string(entry.path()).ok()?).ok()?;
+            Some((index, buffer))
+        })
+        .collect::<Vec<(usize, Buffer)>>();
+
+    buffers.sort_by_key(|(index, _)| *index);
+    buffers.into_iter().map(|(_, buffer)| buffer).collect()
+}
+
+// Remove every buffer (i.e. once they have been restored or thrown away), a missing directory is already clear
+pub fn clear(directory: &Path) -> io::Result<()> {
+    match fs::remove_dir_all(directory) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use crate::backend::recovery::{self, Buffer};
+
+    // 7.1
+    #[test]
+    fn recovery_round_trip() {
+        let directory = env::temp_dir().join(format!("algor-recovery-{}", std::process::id()));
+
+        let buffers = vec![
+            Buffer {
+                name: "add.lmc".into(),
+                path: Some("/home/student/add.lmc".into()),
+                text: "INP\nOUT\nHLT".into(),
+            },
+            Buffer {
+                name: "Untitled".into(),
+                path: None,
+                text: "HLT".into(),
+            },
+        ];
+
+        recovery::save(&directory, &buffers).unwrap();
+        assert_eq!(recovery::load(&directory), buffers);
+
+        // Saving fewer buffers doesn't leave the old ones behind
+        recovery::save(&directory, &buffers[1..]).unwrap();
+        assert_eq!(recovery::load(&directory), buffers[1..]);
+
+        recovery::clear(&directory).unwrap();
+        assert_eq!(recovery::load(&directory), vec![]);
+        assert!(recovery::clear(&directory).is_ok());
+    }
+}
@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+
+use crate::frontend::screen::sandbox::Pane;
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::Path,
+};
+
+// How many files are kept in the recent files list
+pub const RECENT_FILES: usize = 8;
+
+// The screen the user was last working in
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub enum LastScreen {
+    #[default]
+    Menu,
+    Sandbox,
+    LessonSelect,
+    // Lessons don't have an id, so the lesson is found again by its title
+    LessonView {
+        title: Option<String>,
+        slide: usize,
+    },
+}
+
+// A file open in a sandbox tab and where the cursor was in it
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct OpenFile {
+    pub path: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+// How the panes of the sandbox screen were arranged, pane_grid's own layout can't be saved so it is copied into this
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum Layout {
+    Split {
+        // Vertical splits put a on the left and b on the right, horizontal splits put a on top
+        vertical: bool,
+        ratio: f32,
+        a: Box<Layout>,
+        b: Box<Layout>,
+    },
+    Pane(Pane),
+}
+
+// The sandbox screen as it was left
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct SandboxSession {
+    // Only tabs that were opened from or saved to a file, new programs are left to autosave
+    pub files: Vec<OpenFile>,
+    // Position of the tab that was shown in files
+    pub active: usize,
+    pub layout: Layout,
+}
+
+// Everything remembered between launches that isn't a setting, all fields default so an old or missing file is never a problem
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct Session {
+    // Most recently opened or saved first
+    #[serde(default)]
+    pub recent_files: Vec<String>,
+    #[serde(default)]
+    pub screen: LastScreen,
+    #[serde(default)]
+    pub sandbox: Option<SandboxSession>,
+}
+
+impl Session {
+    // Read the session from disk, starting afresh if there isn't one or it can't be read
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|file| toml::from_str(&file).ok())
+            .unwrap_or_default()
+    }
+
+    // Write the session to disk, this is small enough to do without a task (i.e. while the window is closing)
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+
+        let session = toml::to_string(self).map_err(io::Error::other)?;
+        fs::File::create(path)?.write_all(session.as_bytes())
+    }
+
+    // Move a file to the top of the recent files list, dropping the oldest if the list is full
+    pub fn add_recent(&mut self, path: &str) {
+        self.recent_files.retain(|recent| recent != path);
+        self.recent_files.insert(0, path.to_string());
+        self.recent_files.truncate(RECENT_FILES);
+    }
+
+    // Whether there is anything for "Reopen Last Session" to reopen
+    pub fn can_reopen(&self) -> bool {
+        self.screen != LastScreen::Menu || self.sandbox.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backend::session::{
+        LastScreen, Layout, OpenFile, RECENT_FILES, SandboxSession, Session,
+    };
+    use crate::frontend::screen::sandbox::Pane;
+
+    // 6.1
+    #[test]
+    fn session_round_trip() {
+        let session = Session {
+            recent_files: vec!["/home/student/add.lmc".into()],
+            screen: LastScreen::LessonView {
+                title: Some("Loops".into()),
+                slide: 2,
+            },
+            sandbox: Some(SandboxSession {
+                files: vec![OpenFile {
+                    path: "/home/student/add.lmc".into(),
+                    line: 3,
+                    column: 4,
+                }],
+                active: 0,
+                layout: Layout::Split {
+                    vertical: true,
+                    ratio: 0.5,
+                    a: Box::new(Layout::Pane(Pane::Editor)),
+                    b: Box::new(Layout::Pane(Pane::StateViewer)),
+                },
+            }),
+        };
+
+        let file = toml::to_string(&session).unwrap();
+        assert_eq!(toml::from_str::<Session>(&file).unwrap(), session);
+
+        // Sessions saved by older versions (or empty files) still load
+        assert_eq!(toml::from_str::<Session>("").unwrap(), Session::default());
+    }
+
+    // 6.2
+    #[test]
+    fn session_recent_files() {
+        let mut session = Session::default();
+
+        for i in 0..RECENT_FILES + 2 {
+            session.add_recent(&format!("{i}.lmc"));
+        }
+        session.add_recent("5.lmc");
+
+        assert_eq!(session.recent_files.len(), RECENT_FILES);
+        assert_eq!(session.recent_files[0], "5.lmc");
+        assert_eq!(session.recent_files[1], format!("{}.lmc", RECENT_FILES + 1));
+        assert_eq!(
+            session
+                .recent_files
+                .iter()
+                .filter(|file| *file == "5.lmc")
+                .count(),
+            1
+        );
+    }
+}
@@ -0,0 +1,6 @@
+use std::env;
+
+// A language server for LMC source files so students can get the same checks as the algor IDE in their own editors (see src/backend/lsp.rs)
+fn main() {
+    std::process::exit(algor::backend::lsp::run(env::args().skip(1).collect()));
+}
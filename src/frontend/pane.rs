@@ -1,11 +1,46 @@
 pub mod editor;
+pub mod reference;
 pub mod state_viewer;
 pub mod terminal;
 
+use iced::widget::pane_grid;
+
+// The pane after (or before) the focused one in the order they are laid out (left to right and top to bottom), used to move focus between panes with the keyboard
+pub fn cycle<T>(
+    panes: &pane_grid::State<T>,
+    focused: Option<pane_grid::Pane>,
+    forward: bool,
+) -> Option<pane_grid::Pane> {
+    fn order(node: &pane_grid::Node, panes: &mut Vec<pane_grid::Pane>) {
+        match node {
+            pane_grid::Node::Split { a, b, .. } => {
+                order(a, panes);
+                order(b, panes);
+            }
+            pane_grid::Node::Pane(pane) => panes.push(*pane),
+        }
+    }
+
+    let mut order_of_panes = Vec::new();
+    order(panes.layout(), &mut order_of_panes);
+    let count = order_of_panes.len();
+
+    // Start from the first (or last) pane if none has focus yet
+    let next =
+        match focused.and_then(|focused| order_of_panes.iter().position(|pane| *pane == focused)) {
+            Some(i) if forward => (i + 1) % count,
+            Some(i) => (i + count - 1) % count,
+            None if forward => 0,
+            None => count.checked_sub(1)?,
+        };
+
+    order_of_panes.get(next).copied()
+}
+
 pub mod style {
     use iced::{
         Background, Border, Color, Theme, border::Radius, widget::container, widget::scrollable,
-        widget::text,
+        widget::text, widget::text_input,
     };
 
     // Default container with a background and slight rounding on the top two corners
@@ -15,7 +50,8 @@ pub mod style {
                 theme.extended_palette().primary.strong.color,
             )),
 
-            text_color: Some(Color::from_rgb(1f32, 1f32, 1f32)),
+            // Whichever of black or white stands out on the primary colour (custom themes may have light primary colours)
+            text_color: Some(theme.extended_palette().primary.strong.text),
             border: Border {
                 radius: Radius::new(2).bottom_left(0).bottom_right(0),
                 ..Default::default()
@@ -30,7 +66,7 @@ pub mod style {
             background: Some(Background::Color(
                 theme.extended_palette().secondary.base.color,
             )),
-            text_color: if theme == &Theme::Light {
+            text_color: if !theme.extended_palette().is_dark {
                 Some(Color::from_rgb(0f32, 0f32, 0f32))
             } else {
                 Some(Color::from_rgb(1f32, 1f32, 1f32))
@@ -69,6 +105,63 @@ pub mod style {
         }
     }
 
+    // Register or memory location in the state viewer, with a faint background in the primary colour if it was used by the last part of the fetch-decode-execute cycle and a border in the warning colour if it was changed recently, fading out as the change gets older
+    pub fn state_cell(
+        theme: &Theme,
+        highlighted: bool,
+        change_age: Option<u8>,
+    ) -> container::Style {
+        let palette = theme.extended_palette();
+
+        container::Style {
+            background: highlighted.then_some(Background::Color(palette.primary.weak.color)),
+            text_color: highlighted.then_some(palette.primary.weak.text),
+            border: Border {
+                color: change_age.map_or(Color::TRANSPARENT, |age| {
+                    theme
+                        .palette()
+                        .warning
+                        .scale_alpha(1f32 - age as f32 / crate::shared::vm::CHANGE_STEPS as f32)
+                }),
+                width: 2f32,
+                radius: Radius::new(2),
+            },
+            ..Default::default()
+        }
+    }
+
+    // Default text input with a border in the success colour if the value is right and the danger colour if it's wrong (None if it hasn't been checked)
+    pub fn marked_input(
+        theme: &Theme,
+        status: text_input::Status,
+        mark: Option<bool>,
+    ) -> text_input::Style {
+        let mut style = text_input::default(theme, status);
+
+        match mark {
+            Some(true) => style.border.color = theme.palette().success,
+            Some(false) => style.border.color = theme.palette().danger,
+            None => {}
+        }
+
+        style
+    }
+
+    // Box behind tooltips
+    pub fn tooltip(theme: &Theme) -> container::Style {
+        container::Style {
+            background: Some(Background::Color(
+                theme.extended_palette().background.weak.color,
+            )),
+            text_color: Some(theme.extended_palette().background.weak.text),
+            border: Border {
+                radius: Radius::new(2),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
     // Force iced to render a solid background as to prevent opaque background when dragging window
     pub fn solid_background(theme: &Theme) -> container::Style {
         container::Style {
@@ -185,4 +278,18 @@ pub mod style {
             color: Some(Color::from_rgb(1f32, 0f32, 0f32)),
         }
     }
+
+    // Yellow text
+    pub fn terminal_warn(_theme: &Theme) -> text::Style {
+        text::Style {
+            color: Some(Color::from_rgb(1f32, 1f32, 0f32)),
+        }
+    }
+
+    // Text in the warning colour of the theme (used outside of the terminal where the background isn't always black)
+    pub fn warning(theme: &Theme) -> text::Style {
+        text::Style {
+            color: Some(theme.palette().warning),
+        }
+    }
 }
@@ -1,17 +1,241 @@
+use std::sync::Arc;
+
 use rfd::AsyncFileDialog;
 
 use iced::{
     Element, Length, Padding, alignment,
-    widget::{button, column, container, row, space, text_editor, text_input},
+    keyboard::{self, key},
+    widget::{
+        button, column, container, row, scrollable, space, text, text_editor,
+        text_editor::{Action, Binding, Cursor, Edit, Position},
+        text_input,
+    },
 };
 
+use crate::backend::compiler::{self, parser::SymbolKind};
+use crate::backend::keymap::Keymap;
+use crate::backend::lsp::document::Document;
 use crate::frontend::pane::style;
+use crate::frontend::util::{
+    highlighter::{self, Highlighter},
+    shortcuts,
+};
+use crate::shared::isa::InstructionSet;
+use crate::shared::locale::{tr, tr_with};
+
+// How many completions are suggested at once
+const MAX_SUGGESTIONS: usize = 6;
+
+// Lets the text editor be given keyboard focus when the editor pane is focused with the keyboard
+pub const ID: &str = "editor";
+
+// A label in the outline list next to the text editor
+#[derive(Debug, Clone)]
+pub struct OutlineItem {
+    pub name: String,
+    pub address: u8,
+    // The value after DAT for data labels, shown after the address
+    pub data: Option<Option<i16>>,
+}
+
+// Editor features that depend on what has been typed (completions and the outline), kept by each screen with an editor pane
+#[derive(Debug, Clone, Default)]
+pub struct Assist {
+    // Words that complete the word being typed, the first one is picked with tab
+    suggestions: Vec<String>,
+    // Labels and data locations in the program, kept from the last time the program could be parsed
+    outline: Vec<OutlineItem>,
+    // The modifier keys currently held down, ctrl-clicking a label goes to where it's defined
+    pub modifiers: keyboard::Modifiers,
+}
+
+// The line and column of a byte offset in the source code
+fn line_column(source: &str, offset: usize) -> Position {
+    let before = &source[..offset];
+
+    Position {
+        line: before.matches('\n').count(),
+        column: offset - before.rfind('\n').map_or(0, |newline| newline + 1),
+    }
+}
+
+impl Assist {
+    // The letters before the cursor (e.g. "BR" when the cursor is after "loop BR")
+    fn prefix(content: &text_editor::Content) -> String {
+        let Position { line, column } = content.cursor().position;
+
+        let Some(line) = content.line(line) else {
+            return String::new();
+        };
+        let before = line.text.get(..column).unwrap_or(&line.text);
+
+        let start = before
+            .rfind(|c: char| !c.is_ascii_alphabetic())
+            .map_or(0, |i| i + 1);
+
+        before[start..].to_string()
+    }
+
+    // The word under the cursor, including the letters after it (also used for context help in the instruction reference pane)
+    pub fn word(content: &text_editor::Content) -> String {
+        let Position { line, column } = content.cursor().position;
+
+        let after = content
+            .line(line)
+            .and_then(|line| {
+                let after = line.text.get(column..)?;
+                let end = after
+                    .find(|c: char| !c.is_ascii_alphabetic())
+                    .unwrap_or(after.len());
+
+                Some(after[..end].to_string())
+            })
+            .unwrap_or_default();
+
+        Self::prefix(content) + &after
+    }
+
+    // Rebuild the outline from the labels in the program, leaving it as it was if the program can't be parsed (e.g. while something is half typed)
+    fn update_outline(&mut self, source: &str, instruction_set: InstructionSet) {
+        let Ok(program) = compiler::parse(source, instruction_set) else {
+            return;
+        };
+
+        self.outline = program
+            .symbols()
+            .into_iter()
+            .map(|symbol| OutlineItem {
+                name: symbol.name.to_string(),
+                address: symbol.address,
+                data: match symbol.kind {
+                    SymbolKind::Code => None,
+                    SymbolKind::Data(value) => Some(value),
+                },
+            })
+            .collect();
+    }
+
+    // Suggest mnemonics, labels, and macros that start with the word being typed
+    fn update_suggestions(
+        &mut self,
+        content: &text_editor::Content,
+        instruction_set: InstructionSet,
+    ) {
+        let prefix = Self::prefix(content);
+        self.suggestions.clear();
+
+        if prefix.is_empty() {
+            return;
+        }
+
+        let document = Document::new(content.text(), instruction_set);
+
+        let keywords = instruction_set
+            .mnemonics()
+            .iter()
+            .flat_map(|mnemonic| {
+                std::iter::once(mnemonic.name).chain(mnemonic.aliases.iter().copied())
+            })
+            .chain(["DAT", "MACRO", "ENDM"])
+            .map(str::to_string);
+
+        let names = document
+            .labels()
+            .into_iter()
+            .map(|(label, _)| label.to_string())
+            .chain(document.macros().into_keys().map(|name| name.to_string()));
+
+        for word in keywords.chain(names) {
+            if word.len() > prefix.len()
+                && word.to_lowercase().starts_with(&prefix.to_lowercase())
+                && !self.suggestions.contains(&word)
+                && self.suggestions.len() < MAX_SUGGESTIONS
+            {
+                self.suggestions.push(word);
+            }
+        }
+    }
+
+    // Move the cursor to where a label is defined, selecting the label
+    pub fn go_to_definition(
+        &mut self,
+        content: &mut text_editor::Content,
+        name: &str,
+        instruction_set: InstructionSet,
+    ) {
+        let source = content.text();
+
+        if let Some(span) = Document::new(source.clone(), instruction_set).definition(name) {
+            content.move_to(Cursor {
+                position: line_column(&source, span.end),
+                selection: Some(line_column(&source, span.start)),
+            });
+        }
+    }
+
+    // Perform an action on the text editor, updating the suggestions and outline after edits and going to the definition of a label on ctrl-click
+    pub fn perform(
+        &mut self,
+        content: &mut text_editor::Content,
+        action: Action,
+        instruction_set: InstructionSet,
+    ) {
+        let is_edit = action.is_edit();
+        let go_to_definition = matches!(action, Action::Click(_)) && self.modifiers.command();
+
+        content.perform(action);
+
+        if is_edit {
+            self.update_suggestions(content, instruction_set);
+            self.update_outline(&content.text(), instruction_set);
+        } else {
+            self.suggestions.clear();
+        }
+
+        if go_to_definition {
+            let word = Self::word(content);
+            self.go_to_definition(content, &word, instruction_set);
+        }
+    }
+
+    // Replace the word being typed with a suggestion
+    pub fn accept(
+        &mut self,
+        content: &mut text_editor::Content,
+        index: usize,
+        instruction_set: InstructionSet,
+    ) {
+        let Some(suggestion) = self.suggestions.get(index).cloned() else {
+            return;
+        };
+
+        for _ in 0..Self::prefix(content).len() {
+            content.perform(Action::Edit(Edit::Backspace));
+        }
+        content.perform(Action::Edit(Edit::Paste(Arc::new(suggestion))));
+
+        self.suggestions.clear();
+        self.update_outline(&content.text(), instruction_set);
+    }
+
+    // Hide the suggestions without picking one
+    pub fn dismiss(&mut self) {
+        self.suggestions.clear();
+    }
+
+    // Fill in the outline for text that wasn't typed (e.g. an opened file)
+    pub fn reload(&mut self, content: &text_editor::Content, instruction_set: InstructionSet) {
+        self.suggestions.clear();
+        self.outline.clear();
+        self.update_outline(&content.text(), instruction_set);
+    }
+}
 
 // Gets the path of a file asynchronously
 pub async fn open_lmc() -> Option<String> {
     Some(
         AsyncFileDialog::new()
-            .set_title("Pick LMC file...")
+            .set_title(tr("editor-pick-file"))
             // Only allow opening files with the .lmc or .asm extension
             .add_filter("LMC", &["lmc", "asm"])
             .pick_file()
@@ -28,7 +252,7 @@ pub async fn open_lmc() -> Option<String> {
 pub async fn save_lmc() -> Option<String> {
     Some(
         AsyncFileDialog::new()
-            .set_title("Save LMC file...")
+            .set_title(tr("editor-save-file"))
             // Ditto extension comment
             .add_filter("LMC", &["lmc", "asm"])
             .save_file()
@@ -41,17 +265,48 @@ pub async fn save_lmc() -> Option<String> {
     )
 }
 
+// Ditto save_lmc comment but for exporting a trace table
+pub async fn save_trace() -> Option<String> {
+    Some(
+        AsyncFileDialog::new()
+            .set_title(tr("editor-export-file"))
+            .add_filter("CSV", &["csv"])
+            .add_filter("JSON", &["json"])
+            .save_file()
+            // Ditto open_lmc message
+            .await?
+            .path()
+            .to_str()
+            .to_owned()?
+            .to_owned(),
+    )
+}
+
 // Message files specific to the editor pane, conveted to screen-specific messages using the map method
 #[derive(Debug, Clone)]
 pub enum Message {
     OpenClicked,
     SaveClicked,
+    // Always ask where to save, even if the program was opened from or saved to a file already
+    SaveAsClicked,
+    // Export a table of every instruction run to a CSV or JSON file
+    ExportTraceClicked,
     AssembleClicked,
+    // Tidy up the source code in the text editor (see src/backend/compiler/formatter.rs)
+    FormatClicked,
     RunClicked,
+    // Run a single instruction while the program is stopped
+    StepClicked,
     StopClicked,
     ResetClicked,
     // Event for when any action is performed in a text editor
     ContentChanged(text_editor::Action),
+    // A completion was picked by clicking it or pressing tab (the first suggestion)
+    SuggestionPicked(usize),
+    // Escape was pressed while there were suggestions
+    SuggestionsDismissed,
+    // A label in the outline was clicked
+    OutlineClicked(String),
     InputChanged(String),
     InputSubmitted,
 }
@@ -59,8 +314,79 @@ pub enum Message {
 pub fn editor<'a>(
     editor_content: &'a text_editor::Content,
     text_size: u32,
+    warnings: &'a [String],
+    assist: &'a Assist,
     input_content: Option<&String>,
+    highlighter: highlighter::Settings,
+    keymap: &'a Keymap,
 ) -> Element<'a, Message> {
+    let has_suggestions = !assist.suggestions.is_empty();
+
+    // Tab picks the first suggestion and escape hides them, keys in the keymap are left for the shortcut (e.g. Ctrl+Enter assembles rather than adding a line), otherwise keys do what they normally do
+    let key_binding = move |key_press: text_editor::KeyPress| {
+        if has_suggestions && matches!(key_press.status, text_editor::Status::Focused { .. }) {
+            match key_press.key {
+                keyboard::Key::Named(key::Named::Tab) => {
+                    return Some(Binding::Custom(Message::SuggestionPicked(0)));
+                }
+                keyboard::Key::Named(key::Named::Escape) => {
+                    return Some(Binding::Custom(Message::SuggestionsDismissed));
+                }
+                _ => {}
+            }
+        }
+
+        if shortcuts::binding(&key_press.key, key_press.modifiers)
+            .is_some_and(|binding| keymap.command(&binding).is_some())
+        {
+            return None;
+        }
+
+        Binding::from_key_press(key_press)
+    };
+
+    // Labels in address order with their address (and value for data)
+    let outline = scrollable(
+        column(assist.outline.iter().map(|item| {
+            let label = match item.data {
+                None => format!("{:02} {}", item.address, item.name),
+                Some(Some(value)) => format!("{:02} {} = {value}", item.address, item.name),
+                Some(None) => format!("{:02} {} = ?", item.address, item.name),
+            };
+
+            button(text(label).size(text_size.saturating_sub(2)))
+                .style(button::text)
+                .padding(2)
+                .width(Length::Fill)
+                .on_press(Message::OutlineClicked(item.name.clone()))
+                .into()
+        }))
+        .spacing(2),
+    )
+    .width(Length::Fixed(140f32))
+    .height(Length::Fill);
+
+    // Completions for the word being typed, shown just under the text editor
+    let suggestions = has_suggestions.then(|| {
+        row(assist
+            .suggestions
+            .iter()
+            .enumerate()
+            .map(|(i, suggestion)| {
+                button(text(suggestion).size(text_size.saturating_sub(2)))
+                    .style(if i == 0 {
+                        button::primary
+                    } else {
+                        button::secondary
+                    })
+                    .padding([2, 6])
+                    .on_press(Message::SuggestionPicked(i))
+                    .into()
+            }))
+        .spacing(4)
+        .width(Length::Fill)
+    });
+
     container(column![
         container(
             column![
@@ -69,25 +395,45 @@ pub fn editor<'a>(
                     input_content.is_some().then(|| {
                         container(
                             row![
-                                button("Open").on_press(Message::OpenClicked),
-                                button("Save").on_press(Message::SaveClicked),
+                                button(text(tr("editor-open"))).on_press(Message::OpenClicked),
+                                button(text(tr("save"))).on_press(Message::SaveClicked),
+                                button(text(tr("editor-save-as"))).on_press(Message::SaveAsClicked),
+                                button(text(tr("editor-export-trace")))
+                                    .on_press(Message::ExportTraceClicked),
                                 space::horizontal()
                             ]
                             .spacing(4),
                         )
                     }),
-                    button("Assemble").on_press(Message::AssembleClicked),
-                    button("Run").on_press(Message::RunClicked),
-                    button("Stop").on_press(Message::StopClicked),
-                    button("Reset").on_press(Message::ResetClicked)
+                    button(text(tr("editor-format"))).on_press(Message::FormatClicked),
+                    button(text(tr("editor-assemble"))).on_press(Message::AssembleClicked),
+                    button(text(tr("editor-run"))).on_press(Message::RunClicked),
+                    button(text(tr("editor-step"))).on_press(Message::StepClicked),
+                    button(text(tr("editor-stop"))).on_press(Message::StopClicked),
+                    button(text(tr("editor-reset"))).on_press(Message::ResetClicked)
                 ]
                 .spacing(4),
-                text_editor(editor_content)
-                    .size(text_size)
-                    .height(Length::Fill)
-                    .on_action(Message::ContentChanged)
-                    // Use python syntax highlighting as an approximation of assembly
-                    .highlight("py", iced::highlighter::Theme::Base16Ocean)
+                row![
+                    text_editor(editor_content)
+                        .id(ID)
+                        .size(text_size)
+                        .height(Length::Fill)
+                        .on_action(Message::ContentChanged)
+                        .key_binding(key_binding)
+                        // Colour mnemonics, labels, numbers, and comments with the theme's editor colours
+                        .highlight_with::<Highlighter>(highlighter, highlighter::format),
+                    outline
+                ]
+                .spacing(6),
+                suggestions,
+                // List warnings from the last time the program was assembled under the text editor
+                column(warnings.iter().map(|warning| {
+                    text(tr_with("warning", &[("warning", warning)]))
+                        .style(style::warning)
+                        .into()
+                }))
+                .width(Length::Fill)
+                .spacing(2)
             ]
             .spacing(6)
             .align_x(alignment::Horizontal::Right)
@@ -97,7 +443,7 @@ pub fn editor<'a>(
         // Ditto save and show options comment but with the input box instead
         input_content.is_some().then(|| {
             container(
-                text_input("Input...", input_content.unwrap_or(&String::new()))
+                text_input(&tr("editor-input"), input_content.unwrap_or(&String::new()))
                     .on_input(Message::InputChanged)
                     .on_submit(Message::InputSubmitted),
             )
@@ -0,0 +1,120 @@
+use iced::{
+    Element, Length, Padding,
+    widget::{Column, column, container, scrollable, text},
+};
+
+use crate::{
+    frontend::{pane::style, util::font::Font},
+    shared::{
+        isa::{InstructionSet, Mnemonic},
+        locale::{tr, tr_with},
+    },
+};
+
+// No messages required but provide a mapping for future maintainability
+#[derive(Debug, Clone)]
+pub enum Message {}
+
+// The name of an instruction with its aliases (e.g. "HLT (COB)")
+fn title(mnemonic: &Mnemonic) -> String {
+    if mnemonic.aliases.is_empty() {
+        mnemonic.name.to_string()
+    } else {
+        format!("{} ({})", mnemonic.name, mnemonic.aliases.join(", "))
+    }
+}
+
+// Everything about a single instruction: its machine code, what it does, how it changes the registers and memory, and an example
+fn entry<'a>(mnemonic: &Mnemonic, text_size: u32) -> Column<'a, Message> {
+    let operation = mnemonic.operation;
+
+    column![
+        text(format!("{}  {}", title(mnemonic), mnemonic.machine_code()))
+            .font(Font::Bold)
+            .size(text_size),
+        text(operation.description()).size(text_size),
+        text(operation.effect())
+            .size(text_size)
+            .style(style::terminal_out),
+        text(operation.example())
+            .size(text_size.saturating_sub(2))
+            .font(Font::Italic),
+    ]
+    .spacing(2)
+}
+
+// Ditto entry comment but for DAT, which isn't an instruction so isn't part of any instruction set
+fn data_entry<'a>(text_size: u32) -> Column<'a, Message> {
+    column![
+        text("DAT").font(Font::Bold).size(text_size),
+        text(tr("description-data")).size(text_size),
+        text(tr("effect-data"))
+            .size(text_size)
+            .style(style::terminal_out),
+        text("X DAT 5\nY DAT")
+            .size(text_size.saturating_sub(2))
+            .font(Font::Italic),
+    ]
+    .spacing(2)
+}
+
+// Lists every instruction in the instruction set, with help for the word under the cursor in the editor (if it's an instruction) at the top
+pub fn reference<'a>(
+    instruction_set: InstructionSet,
+    word: &str,
+    text_size: u32,
+) -> Element<'a, Message> {
+    let current = if word.eq_ignore_ascii_case("DAT") {
+        Some(data_entry(text_size))
+    } else {
+        instruction_set
+            .mnemonic(word)
+            .map(|mnemonic| entry(mnemonic, text_size))
+    };
+
+    container(scrollable(
+        column![
+            // Context help for the mnemonic under the cursor, highlighted with a border
+            current.map(|current| {
+                column![
+                    text(tr("reference-under-cursor")).size(text_size),
+                    container(current)
+                        .style(style::grid_pane_focused)
+                        .padding(6)
+                        .width(Length::Fill),
+                ]
+                .spacing(4)
+            }),
+            text(tr_with(
+                "reference-instruction-set",
+                &[("instruction_set", &instruction_set)]
+            ))
+            .size(text_size),
+            column(
+                instruction_set
+                    .mnemonics()
+                    .iter()
+                    .map(|mnemonic| entry(mnemonic, text_size).into())
+            )
+            .spacing(12),
+            data_entry(text_size),
+        ]
+        .spacing(12)
+        .padding(Padding {
+            top: 0f32,
+            right: 12f32,
+            bottom: 0f32,
+            left: 0f32,
+        }),
+    ))
+    // Padding as to not interfere with the title bar
+    .padding(Padding {
+        top: 6f32,
+        right: 8f32,
+        bottom: 6f32,
+        left: 8f32,
+    })
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .into()
+}
@@ -1,77 +1,479 @@
+use std::fmt::Display;
+
 use iced::{
     Alignment, Element, Font, Length, Padding, alignment,
-    widget::{column, container, rich_text, row, scrollable, span, text},
+    widget::{
+        checkbox, column, container, pick_list, rich_text, row, scrollable, span, text, text_input,
+        tooltip,
+    },
 };
 
 use crate::{
+    backend::compiler::{self, generator::Location},
     frontend::{pane::style, util::widgets::separator},
-    shared::vm::Computer,
+    shared::{
+        isa::InstructionSet,
+        locale::{tr, tr_with},
+        vm::{Cell, Computer, Register},
+    },
 };
 
-// No messages required but provide a mapping for future maintainability
+// Lets the program counter be given keyboard focus when the state viewer pane is focused with the keyboard (it can only be typed into while the program is paused)
+pub const ID: &str = "state-viewer";
+
+// How the value in each memory location is shown
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum MemoryFormat {
+    // The 4 digit number (e.g. 0503)
+    #[default]
+    Decimal,
+    // The instruction the machine code decodes to (e.g. LDA 03), data is shown as a number
+    Mnemonic,
+    // The number with its sign (e.g. +503, -7)
+    Signed,
+    // Ditto Mnemonic comment but with addresses replaced by the labels in the program (e.g. LDA ONE)
+    Labelled,
+}
+
+impl MemoryFormat {
+    // Represents a list of memory formats (used in drop-down menus)
+    pub const ALL: &'static [MemoryFormat] = &[
+        MemoryFormat::Decimal,
+        MemoryFormat::Mnemonic,
+        MemoryFormat::Signed,
+        MemoryFormat::Labelled,
+    ];
+
+    // Only numbers can be typed into memory, so editing is only allowed when memory is shown as numbers
+    fn editable(&self) -> bool {
+        matches!(self, MemoryFormat::Decimal | MemoryFormat::Signed)
+    }
+
+    // Mnemonics need more space than numbers
+    fn width(&self) -> f32 {
+        match self {
+            MemoryFormat::Decimal | MemoryFormat::Signed => 45f32,
+            MemoryFormat::Mnemonic | MemoryFormat::Labelled => 75f32,
+        }
+    }
+
+    // Show a memory location in this format
+    fn show(
+        &self,
+        location: Location,
+        instruction_set: InstructionSet,
+        labels: &[(u8, String)],
+    ) -> String {
+        let number = match location {
+            Location::Instruction(instruction) => {
+                instruction.opcode as i16 * 100 + <i16>::from(instruction.operand)
+            }
+            Location::Data(number) => number,
+        };
+
+        match (self, location) {
+            (MemoryFormat::Decimal, _) => format!("{location}"),
+            (MemoryFormat::Signed, _) => format!("{number:+}"),
+            (_, Location::Data(number)) => format!("{number}"),
+            (
+                MemoryFormat::Mnemonic | MemoryFormat::Labelled,
+                Location::Instruction(instruction),
+            ) => {
+                // Machine code that isn't part of the instruction set can only be shown as a number
+                let Some(mnemonic) =
+                    instruction_set.disassemble(instruction.opcode, instruction.operand)
+                else {
+                    return format!("{location}");
+                };
+
+                if !mnemonic.operation.has_operand() {
+                    return mnemonic.name.to_string();
+                }
+
+                match label(labels, instruction.operand) {
+                    Some(label) if *self == MemoryFormat::Labelled => {
+                        format!("{} {label}", mnemonic.name)
+                    }
+                    _ => format!("{} {:02}", mnemonic.name, instruction.operand),
+                }
+            }
+        }
+    }
+}
+
+// Allow formatting and printing out of strings (for drop-down menus)
+impl Display for MemoryFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            MemoryFormat::Decimal => tr("memory-format-decimal"),
+            MemoryFormat::Mnemonic => tr("memory-format-mnemonic"),
+            MemoryFormat::Signed => tr("memory-format-signed"),
+            MemoryFormat::Labelled => tr("memory-format-labelled"),
+        };
+
+        write!(f, "{text}")
+    }
+}
+
+// How memory is laid out in the state viewer
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MemoryView {
+    pub format: MemoryFormat,
+    // Show memory as a 10 by 10 table with row and column headers instead of wrapping it to fit the pane
+    pub table: bool,
+    // Describe the registers and the memory in use as text instead of showing the RAM grid, so it can be read out (e.g. by a screen reader)
+    pub summary: bool,
+}
+
+// The labels of a program and their addresses, used to annotate memory (empty if the program can't be parsed)
+pub fn labels(source: &str, instruction_set: InstructionSet) -> Vec<(u8, String)> {
+    compiler::parse(source, instruction_set)
+        .map(|program| {
+            program
+                .symbols()
+                .into_iter()
+                .map(|symbol| (symbol.address, symbol.name.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// The label at an address
+fn label(labels: &[(u8, String)], address: u8) -> Option<&str> {
+    labels
+        .iter()
+        .find(|(label_address, _)| *label_address == address)
+        .map(|(_, name)| name.as_str())
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    // Switch between running whole instructions and one part of the fetch-decode-execute cycle per tick
+    MicroStepsToggled(bool),
+    // Typing into a register or memory location while the program is paused
+    EditChanged(Cell, String),
+    // Pressing enter to put the typed value into the register or memory location
+    EditSubmitted,
+    // Picking how memory is shown
+    MemoryFormatSelected(MemoryFormat),
+    TableToggled(bool),
+    SummaryToggled(bool),
+}
+
+// A value being typed into a register or memory location, kept until it is submitted
 #[derive(Debug, Clone)]
-pub enum Message {}
+pub struct Edit {
+    pub cell: Cell,
+    pub value: String,
+}
+
+// An input box for a register or memory location, showing what's being typed if the user is editing it or the current value otherwise
+fn input<'a>(cell: Cell, value: String, editing: Option<&'a Edit>) -> Element<'a, Message> {
+    let value = match editing {
+        Some(edit) if edit.cell == cell => edit.value.clone(),
+        _ => value,
+    };
+
+    let input = text_input("", &value)
+        .on_input(move |value| Message::EditChanged(cell, value))
+        .on_submit(Message::EditSubmitted)
+        .padding(2);
+
+    // The program counter is the first thing that can be typed into
+    if cell == Cell::Register(Register::ProgramCounter) {
+        input.id(ID).into()
+    } else {
+        input.into()
+    }
+}
+
+// Style a register or memory location by whether the last part of the fetch-decode-execute cycle used it and whether it changed recently, showing the previous value when hovered over
+fn cell<'a>(
+    computer: &Computer,
+    content: impl Into<Element<'a, Message>>,
+    highlighted: bool,
+    cell: Cell,
+    width: f32,
+) -> Element<'a, Message> {
+    let change = computer.change(cell);
+    let change_age = change.map(|change| change.age);
+
+    let cell = container(content)
+        .width(Length::Fixed(width))
+        .align_x(alignment::Horizontal::Center)
+        .style(move |theme| style::state_cell(theme, highlighted, change_age));
+
+    match change {
+        Some(change) => tooltip(
+            cell,
+            container(text(tr_with("state-was", &[("value", &change.previous)])).size(12))
+                .padding(4)
+                .style(style::tooltip),
+            tooltip::Position::Bottom,
+        )
+        .into(),
+        None => cell.into(),
+    }
+}
+
+// A register value with its name underneath, PC and ACC can be edited while the program is paused (None when running)
+fn register<'a>(
+    computer: &Computer,
+    register: Register,
+    value: String,
+    name: &'a str,
+    editing: Option<Option<&'a Edit>>,
+) -> Element<'a, Message> {
+    let editable = matches!(register, Register::ProgramCounter | Register::Accumulator);
+
+    let value = match editing {
+        Some(editing) if editable => input(Cell::Register(register), value, editing),
+        _ => text(value).into(),
+    };
+
+    cell(
+        computer,
+        column![value, text(name).size(12)].align_x(alignment::Horizontal::Center),
+        computer.highlight.registers.contains(&register),
+        Cell::Register(register),
+        45f32,
+    )
+}
+
+// Ditto register comment but with every memory location being editable when shown as a number, the address is shown underneath unless it's in the table's headers
+fn memory<'a>(
+    computer: &Computer,
+    address: u8,
+    editing: Option<Option<&'a Edit>>,
+    memory_view: MemoryView,
+    labels: &[(u8, String)],
+) -> Element<'a, Message> {
+    let format = memory_view.format;
+    let value = format.show(
+        computer.memory[address as usize],
+        computer.instruction_set,
+        labels,
+    );
+
+    let value = match editing {
+        Some(editing) if format.editable() => input(Cell::Memory(address), value, editing),
+        // Use rich text for underline feature
+        _ => rich_text![span::<(), Font>(value).underline(address == computer.program_counter)]
+            .into(),
+    };
+
+    // Labelled memory also shows the label of the location (e.g. 4 ONE)
+    let label = label(labels, address).filter(|_| format == MemoryFormat::Labelled);
+    let caption = match (memory_view.table, label) {
+        (false, Some(label)) => format!("{address} {label}"),
+        (false, None) => format!("{address}"),
+        (true, label) => label.unwrap_or_default().to_string(),
+    };
+
+    cell(
+        computer,
+        column![value, text(caption).size(8)].align_x(alignment::Horizontal::Center),
+        computer.highlight.address == Some(address),
+        Cell::Memory(address),
+        format.width(),
+    )
+}
+
+// All 100 memory locations, either wrapped to fit the pane or in a 10 by 10 table with the tens of the address down the side and the ones along the top
+fn memory_grid<'a>(
+    computer: &Computer,
+    editing: Option<Option<&'a Edit>>,
+    memory_view: MemoryView,
+    labels: &[(u8, String)],
+) -> Element<'a, Message> {
+    let cell = |address| memory(computer, address, editing, memory_view, labels);
+
+    if !memory_view.table {
+        return row((0..100).map(cell)).spacing(16).wrap().into();
+    }
+
+    let header = |content: String, width: f32| {
+        container(text(content).size(12))
+            .width(Length::Fixed(width))
+            .align_x(alignment::Horizontal::Center)
+            .into()
+    };
+
+    let columns = row(std::iter::once(header(String::new(), 30f32))
+        .chain((0..10).map(|column| header(format!("{column}"), memory_view.format.width()))))
+    .spacing(4);
+
+    let rows = (0..10).map(|tens: u8| {
+        row(std::iter::once(header(format!("{:02}", tens * 10), 30f32))
+            .chain((0..10).map(|ones| cell(tens * 10 + ones))))
+        .spacing(4)
+        .align_y(alignment::Vertical::Center)
+        .into()
+    });
+
+    column(std::iter::once(columns.into()).chain(rows))
+        .spacing(4)
+        .into()
+}
+
+// The registers, what happens next, and every memory location that isn't 0 written out in sentences, memory is shown in the format picked for the RAM grid
+pub fn summary(
+    computer: &Computer,
+    micro_steps: bool,
+    format: MemoryFormat,
+    labels: &[(u8, String)],
+) -> String {
+    let show = |address: u8| {
+        let value = format.show(
+            computer.memory[address as usize],
+            computer.instruction_set,
+            labels,
+        );
+
+        match label(labels, address) {
+            Some(label) => format!("{address:02} ({label}): {value}"),
+            None => format!("{address:02}: {value}"),
+        }
+    };
+
+    let mut lines = vec![
+        tr_with(
+            "summary-program-counter",
+            &[
+                ("value", &computer.program_counter),
+                ("next", &show(computer.program_counter)),
+            ],
+        ),
+        tr_with("summary-accumulator", &[("value", &computer.accumulator)]),
+        tr_with(
+            "summary-registers",
+            &[
+                ("cir", &computer.current_instruction_register),
+                ("mar", &computer.memory_address_register),
+                ("mdr", &computer.memory_data_register),
+            ],
+        ),
+    ];
+
+    if micro_steps {
+        lines.push(tr_with("summary-next-step", &[("stage", &computer.stage)]));
+    }
+
+    let in_use = (0..100u8)
+        .filter(|address| computer.memory[*address as usize] != Location::Data(0))
+        .collect::<Vec<_>>();
+
+    if in_use.is_empty() {
+        lines.push(tr("summary-memory-empty"));
+    } else {
+        lines.push(tr_with(
+            "summary-memory-in-use",
+            &[("count", &in_use.len())],
+        ));
+        lines.extend(in_use.into_iter().map(show));
+    }
+
+    lines.join("\n")
+}
+
+// Shows the registers and memory of the computer, with editable values if the program is paused (i.e. editing is Some)
+pub fn state_viewer<'a>(
+    computer: &Computer,
+    micro_steps: bool,
+    editing: Option<Option<&'a Edit>>,
+    memory_view: MemoryView,
+    labels: &[(u8, String)],
+) -> Element<'a, Message> {
+    // Show which part of the cycle just ran and which runs next
+    let stage = match computer.last_stage {
+        Some(last) if micro_steps => text(tr_with(
+            "state-last-stage",
+            &[("last", &last), ("next", &computer.stage)],
+        )),
+        _ if micro_steps => text(tr_with("state-next-stage", &[("next", &computer.stage)])),
+        _ => text(""),
+    }
+    .size(12);
 
-pub fn state_viewer<'a>(computer: &Computer) -> Element<'a, Message> {
     container(
         scrollable(
             column![
-                text("CPU:"),
+                text(tr("state-cpu")),
                 separator::horizontal(),
                 row![
-                    column![
-                        // Force PC register display to be 2 digits wide
-                        text(format!("{:02}", computer.program_counter)),
-                        text("PC").size(12)
-                    ]
-                    .align_x(alignment::Horizontal::Center)
-                    .width(Length::Fixed(45f32)),
-                    column![
-                        // Force accumulator register display to be 4 digits wide (as to be mindful for plus and minus sign prefixes)
-                        text(format!("{:04}", computer.accumulator)),
-                        text("ACC").size(12)
-                    ]
-                    .align_x(alignment::Horizontal::Center)
-                    .width(Length::Fixed(45f32)),
-                    column![
-                        // CIR register only needs to be one digit wide as there are less than 10 opcodes in the LMC ISA
-                        text(format!("{:01}", computer.current_instruction_register)),
-                        text("CIR").size(12)
-                    ]
-                    .align_x(alignment::Horizontal::Center)
-                    .width(Length::Fixed(45f32)),
-                    column![
-                        // 100 memory addresses starting from 0 (i.e. highest address is 99) so only 2 digits are required
-                        text(format!("{:02}", computer.memory_address_register)),
-                        text("MAR").size(12)
-                    ]
-                    .align_x(alignment::Horizontal::Center)
-                    .width(Length::Fixed(45f32)),
-                    column![
-                        // Ditto accumulator comment
-                        text(format!("{:04}", computer.memory_data_register)),
-                        text("MDR").size(12)
-                    ]
-                    .align_x(alignment::Horizontal::Center)
-                    .width(Length::Fixed(45f32))
+                    // Force PC register display to be 2 digits wide
+                    register(
+                        computer,
+                        Register::ProgramCounter,
+                        format!("{:02}", computer.program_counter),
+                        "PC",
+                        editing
+                    ),
+                    // Force accumulator register display to be 4 digits wide (as to be mindful for plus and minus sign prefixes)
+                    register(
+                        computer,
+                        Register::Accumulator,
+                        format!("{:04}", computer.accumulator),
+                        "ACC",
+                        editing
+                    ),
+                    // CIR register only needs to be one digit wide as there are less than 10 opcodes in the LMC ISA
+                    register(
+                        computer,
+                        Register::CurrentInstruction,
+                        format!("{:01}", computer.current_instruction_register),
+                        "CIR",
+                        editing
+                    ),
+                    // 100 memory addresses starting from 0 (i.e. highest address is 99) so only 2 digits are required
+                    register(
+                        computer,
+                        Register::MemoryAddress,
+                        format!("{:02}", computer.memory_address_register),
+                        "MAR",
+                        editing
+                    ),
+                    // Ditto accumulator comment
+                    register(
+                        computer,
+                        Register::MemoryData,
+                        format!("{:04}", computer.memory_data_register),
+                        "MDR",
+                        editing
+                    )
                 ]
                 .spacing(16),
-                text("RAM:"),
-                separator::horizontal(),
-                // Display all memory locations and addresses, underline the location currently in use by the program counter
-                row(computer.memory.iter().enumerate().map(|(i, value)| column![
-                    // Use rich text for underline feature
-                    rich_text![
-                        span::<(), Font>(format!("{value}"))
-                            .underline(i as u8 == computer.program_counter)
-                    ],
-                    text(format!("{i}")).size(8)
+                checkbox(micro_steps)
+                    .label(tr("state-micro-steps"))
+                    .on_toggle(Message::MicroStepsToggled)
+                    .text_size(12),
+                stage,
+                row![
+                    text(tr("state-ram")),
+                    pick_list(
+                        MemoryFormat::ALL,
+                        Some(memory_view.format),
+                        Message::MemoryFormatSelected
+                    )
+                    .text_size(12),
+                    checkbox(memory_view.table)
+                        .label(tr("state-table"))
+                        .on_toggle(Message::TableToggled)
+                        .text_size(12),
+                    checkbox(memory_view.summary)
+                        .label(tr("state-text-summary"))
+                        .on_toggle(Message::SummaryToggled)
+                        .text_size(12)
                 ]
-                .width(Length::Fixed(45f32))
-                .align_x(alignment::Horizontal::Center)
-                .into()))
                 .spacing(16)
-                .wrap()
+                .align_y(alignment::Vertical::Center),
+                separator::horizontal(),
+                // Display all memory locations and addresses, underline the location currently in use by the program counter and highlight the locations used or changed recently
+                if memory_view.summary {
+                    text(summary(computer, micro_steps, memory_view.format, labels)).into()
+                } else {
+                    memory_grid(computer, editing, memory_view, labels)
+                }
             ]
             .padding(6)
             .spacing(16),
@@ -93,3 +495,71 @@ pub fn state_viewer<'a>(computer: &Computer) -> Element<'a, Message> {
     .align_x(Alignment::Center)
     .into()
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::backend::compiler::{
+        self,
+        generator::{InstructionLocation, Location},
+    };
+    use crate::frontend::pane::state_viewer::{MemoryFormat, labels, summary};
+    use crate::shared::{isa::InstructionSet, vm::Computer};
+
+    // 14.1
+    #[test]
+    fn memory_format_show() {
+        let labels = labels("LDA ONE\nOUT\nHLT\nONE DAT 1", InstructionSet::Classic);
+        let show = |format: MemoryFormat, location| {
+            format.show(location, InstructionSet::Classic, &labels)
+        };
+        let load = Location::Instruction(InstructionLocation::new(5, 3));
+
+        assert_eq!(show(MemoryFormat::Decimal, load), "0503");
+        assert_eq!(show(MemoryFormat::Signed, load), "+503");
+        assert_eq!(show(MemoryFormat::Mnemonic, load), "LDA 03");
+        assert_eq!(show(MemoryFormat::Labelled, load), "LDA ONE");
+
+        // Instructions without an address are only their mnemonic, and data is always a number
+        let output = Location::Instruction(InstructionLocation::new(9, 2));
+        assert_eq!(show(MemoryFormat::Labelled, output), "OUT");
+        assert_eq!(show(MemoryFormat::Mnemonic, Location::Data(-7)), "-7");
+        assert_eq!(show(MemoryFormat::Signed, Location::Data(-7)), "-7");
+
+        // Machine code that isn't part of the instruction set falls back to the number
+        let load_indirect = Location::Instruction(InstructionLocation::new(4, 3));
+        assert_eq!(show(MemoryFormat::Mnemonic, load_indirect), "0403");
+    }
+
+    // 14.2
+    #[test]
+    fn state_summary() {
+        let source = "LDA ONE\nOUT\nHLT\nONE DAT 1";
+        let labels = labels(source, InstructionSet::Classic);
+        let mut computer = Computer::default();
+
+        assert_eq!(
+            summary(&computer, false, MemoryFormat::Decimal, &[]),
+            "Program counter 0, next instruction at 00: 0000\n\
+            Accumulator 0\n\
+            Current instruction register 0, memory address register 0, memory data register 0\n\
+            Every memory location is 0"
+        );
+
+        // Only the memory locations in use are listed, with their labels
+        computer.memory = compiler::compile(source).unwrap();
+        computer.accumulator = 1;
+
+        assert_eq!(
+            summary(&computer, true, MemoryFormat::Labelled, &labels),
+            "Program counter 0, next instruction at 00: LDA ONE\n\
+            Accumulator 1\n\
+            Current instruction register 0, memory address register 0, memory data register 0\n\
+            Next step: Fetch: PC → MAR\n\
+            4 memory location(s) in use, the rest are 0:\n\
+            00: LDA ONE\n\
+            01: OUT\n\
+            02: HLT\n\
+            03 (ONE): 1"
+        );
+    }
+}
@@ -3,26 +3,61 @@ use iced::{
     widget::{column, container, scrollable, text},
 };
 
-use crate::frontend::pane::style;
+use crate::{
+    frontend::pane::style,
+    shared::{locale::tr_with, runtime::Output},
+};
 
 // No messages required but provide a mapping for future maintainability
 #[derive(Debug, Clone)]
 pub enum Message {}
 
-pub fn terminal<'a>(output: &'a Vec<Box<str>>, error: &'a String) -> Element<'a, Message> {
+// Turn the outputs into the lines shown in the terminal, numbers and messages get a line to themselves while characters are joined together until a newline character
+fn lines(output: &[Output]) -> Vec<String> {
+    let mut lines = Vec::new();
+    // The line of characters currently being joined together, None if the last output wasn't a character
+    let mut current: Option<String> = None;
+
+    for output in output {
+        match output {
+            Output::Character('\n') => lines.push(current.take().unwrap_or_default()),
+            Output::Character(character) => current.get_or_insert_default().push(*character),
+            Output::Number(number) => {
+                lines.extend(current.take());
+                lines.push(number.to_string());
+            }
+            Output::Message(message) => {
+                lines.extend(current.take());
+                lines.push(message.to_string());
+            }
+        }
+    }
+
+    lines.extend(current);
+    lines
+}
+
+pub fn terminal<'a>(
+    output: &'a [Output],
+    warnings: &'a [String],
+    error: &'a str,
+) -> Element<'a, Message> {
     container(
         scrollable(
             column![
                 column(
-                    // For every output display text in pure white
-                    output
-                        .iter()
-                        /* Dereference output (i.e. get Box<str>)
-                         Dereference again to unbox (i.e. get str)
-                         And take a reference again as the size of str by itself is unknown at compile time (i.e. get &str) */
-                        .map(|output| { text(&**output).style(style::terminal_out).into() })
+                    // For every line of output display text in pure white
+                    lines(output)
+                        .into_iter()
+                        .map(|line| text(line).style(style::terminal_out).into())
                 ),
-                // Show errors below output
+                // Show warnings below output
+                column(warnings.iter().map(|warning| {
+                    text(tr_with("warning", &[("warning", warning)]))
+                        .style(style::terminal_warn)
+                        .into()
+                })),
+                // Show errors below warnings
                 text(error).style(style::terminal_err)
             ]
             .padding(6)
@@ -38,3 +73,32 @@ pub fn terminal<'a>(output: &'a Vec<Box<str>>, error: &'a String) -> Element<'a,
     .align_x(Alignment::Center)
     .into()
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::frontend::pane::terminal::lines;
+    use crate::shared::runtime::Output;
+
+    // 13.1
+    #[test]
+    fn terminal_lines() {
+        let output = [
+            Output::Character('H'),
+            Output::Character('i'),
+            Output::Character('\n'),
+            Output::Character('\n'),
+            Output::Number(5),
+            Output::Character('O'),
+            Output::Character('K'),
+            Output::Message("Waiting for input...".into()),
+            Output::Character('!'),
+        ];
+
+        // Characters are joined until a newline, a number, or a message, and a newline on its own is an empty line
+        assert_eq!(
+            lines(&output),
+            vec!["Hi", "", "5", "OK", "Waiting for input...", "!"]
+        );
+        assert!(lines(&[]).is_empty());
+    }
+}
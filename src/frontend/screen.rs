@@ -1,6 +1,8 @@
 use iced::Element;
 
-use crate::backend::config::Config;
+use crate::backend::{config::Config, keymap::Keymap};
+use crate::frontend::util::{shortcuts::Shortcut, theme::EditorColours};
+use crate::shared::vm::Cell;
 
 pub mod lesson_select;
 pub mod lesson_view;
@@ -23,16 +25,29 @@ pub enum Event {
     SetConfig(Config),
 
     PickLessonsDirectory(settings::State),
-    OpenLMC(sandbox::State),
-    SaveLMC(sandbox::State),
+    OpenLMC,
+    SaveLMC(usize, Option<String>, String),
+    ExportTrace,
 
     ToSettings,
     ToSandbox,
     ToLessonSelect,
+    // Going back to the menu goes through main so the session can be saved and the menu can show it
+    ToMenu,
+    ReopenSession,
+    OpenRecent(String),
+    RestoreRecovered,
+    DiscardRecovered,
+    ResetConfig,
+    DismissConfigError,
     Run,
+    Step,
     Stop,
     Reset,
+    Poke(Cell, i16),
     SubmitInput(String),
+    // Give keyboard focus to a widget by its id
+    Focus(&'static str),
 }
 
 // Type bundling together the states of each section of the program
@@ -46,14 +61,36 @@ pub enum Screen {
 }
 
 impl Screen {
-    pub fn view(&self) -> Element<'_, Message> {
+    // Running is passed along to the screens with a virtual machine so the state viewer can only be edited while paused, settings is whether their settings buttons are shown (see Policy in src/backend/config.rs), and colours are the editor colours of the theme
+    pub fn view<'a>(
+        &'a self,
+        running: bool,
+        settings: bool,
+        colours: EditorColours,
+        keymap: &'a Keymap,
+    ) -> Element<'a, Message> {
         // Switch screen being shown depending on what the enum is
         match self {
             Screen::Menu(state) => state.view().map(Message::Menu),
             Screen::Settings(state) => state.view().map(Message::Settings),
-            Screen::Sandbox(state) => state.view().map(Message::Sandbox),
+            Screen::Sandbox(state) => state
+                .view(running, settings, colours, keymap)
+                .map(Message::Sandbox),
             Screen::LessonSelect(state) => state.view().map(Message::LessonSelect),
-            Screen::LessonView(state) => state.view().map(Message::LessonView),
+            Screen::LessonView(state) => state
+                .view(running, settings, colours, keymap)
+                .map(Message::LessonView),
+        }
+    }
+
+    // Turn a keyboard shortcut into the message for what it does on the screen being shown, if it does anything there
+    pub fn shortcut(&mut self, shortcut: Shortcut) -> Option<Message> {
+        match self {
+            Screen::Menu(state) => state.shortcut(shortcut).map(Message::Menu),
+            Screen::Settings(state) => state.shortcut(shortcut).map(Message::Settings),
+            Screen::Sandbox(state) => state.shortcut(shortcut).map(Message::Sandbox),
+            Screen::LessonSelect(state) => state.shortcut(shortcut).map(Message::LessonSelect),
+            Screen::LessonView(state) => state.shortcut(shortcut).map(Message::LessonView),
         }
     }
 
@@ -67,6 +104,12 @@ impl Screen {
                         menu::Event::ToLessonSelect => return Some(Event::ToLessonSelect),
                         menu::Event::ToSettings => return Some(Event::ToSettings),
                         menu::Event::ToSandbox => return Some(Event::ToSandbox),
+                        menu::Event::ReopenSession => return Some(Event::ReopenSession),
+                        menu::Event::OpenRecent(path) => return Some(Event::OpenRecent(path)),
+                        menu::Event::RestoreRecovered => return Some(Event::RestoreRecovered),
+                        menu::Event::DiscardRecovered => return Some(Event::DiscardRecovered),
+                        menu::Event::ResetConfig => return Some(Event::ResetConfig),
+                        menu::Event::DismissConfigError => return Some(Event::DismissConfigError),
                     }
                 }
             }
@@ -94,20 +137,25 @@ impl Screen {
                 {
                     match event {
                         sandbox::Event::Run => return Some(Event::Run),
+                        sandbox::Event::Step => return Some(Event::Step),
                         sandbox::Event::Stop => return Some(Event::Stop),
                         sandbox::Event::Reset => return Some(Event::Reset),
+                        sandbox::Event::Poke(cell, value) => {
+                            return Some(Event::Poke(cell, value));
+                        }
 
                         sandbox::Event::SubmitInput(input) => {
                             return Some(Event::SubmitInput(input));
                         }
 
-                        sandbox::Event::OpenLMC(state) => return Some(Event::OpenLMC(state)),
-                        sandbox::Event::SaveLMC(state) => return Some(Event::SaveLMC(state)),
-
-                        // Ditto change in place comment
-                        sandbox::Event::ToMenu => {
-                            *self = Screen::Menu(menu::State {});
+                        sandbox::Event::OpenLMC => return Some(Event::OpenLMC),
+                        sandbox::Event::SaveLMC(id, path, text) => {
+                            return Some(Event::SaveLMC(id, path, text));
                         }
+                        sandbox::Event::ExportTrace => return Some(Event::ExportTrace),
+                        sandbox::Event::Focus(id) => return Some(Event::Focus(id)),
+
+                        sandbox::Event::ToMenu => return Some(Event::ToMenu),
                         sandbox::Event::ToSettings => return Some(Event::ToSettings),
                     }
                 }
@@ -121,9 +169,7 @@ impl Screen {
                         lesson_select::Event::ToLessonView(lesson) => {
                             *self = Screen::LessonView(lesson)
                         }
-                        lesson_select::Event::ToMenu => {
-                            *self = Screen::Menu(menu::State {});
-                        }
+                        lesson_select::Event::ToMenu => return Some(Event::ToMenu),
                     }
                 }
             }
@@ -133,8 +179,13 @@ impl Screen {
                 {
                     match event {
                         lesson_view::Event::Run => return Some(Event::Run),
+                        lesson_view::Event::Step => return Some(Event::Step),
                         lesson_view::Event::Stop => return Some(Event::Stop),
                         lesson_view::Event::Reset => return Some(Event::Reset),
+                        lesson_view::Event::Poke(cell, value) => {
+                            return Some(Event::Poke(cell, value));
+                        }
+                        lesson_view::Event::Focus(id) => return Some(Event::Focus(id)),
 
                         lesson_view::Event::ToSettings => {
                             return Some(Event::ToSettings);
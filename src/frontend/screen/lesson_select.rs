@@ -4,12 +4,23 @@ use std::{
 };
 
 use crate::{
-    backend::lesson_parser::Lesson,
+    backend::{compiler::formatter::AliasStyle, lesson_parser::Lesson},
     frontend::{
         screen::lesson_view,
-        util::{font::Font, widgets::separator},
+        util::{
+            font::Font,
+            shortcuts::Shortcut,
+            widgets::{
+                focus::{Focus, ring},
+                separator,
+            },
+        },
+    },
+    shared::{
+        locale::{tr, tr_with},
+        runtime::Input,
+        vm::Computer,
     },
-    shared::{runtime::Input, vm::Computer},
 };
 
 use iced::{
@@ -35,17 +46,17 @@ pub enum Event {
 #[derive(Debug, Clone)]
 pub struct State {
     lessons: Result<Vec<lesson_view::State>, &'static str>,
+    // The start button of each lesson then the back button can be given keyboard focus
+    focus: Focus,
 }
 
 impl State {
     /* Convert I/O error from get_lesssons associated function to user-friendly string and wrap lessons around state
-    As this code is not chained together with other fallible code, I don't need to implement Error and can have the error type just be a static string */
+    As this code is not chained together with other fallible code, I don't need to implement Error and can have the error type just be the id of its message (see src/shared/locale.rs) */
     pub fn new(lessons: io::Result<Vec<lesson_view::State>>) -> Self {
         State {
-            lessons: lessons.map_err(|_| {
-                "Encountered an error while opening directory...\n\
-                Failed to read from lessons directory, are you sure the directory exists?"
-            }),
+            lessons: lessons.map_err(|_| "lesson-select-directory-error"),
+            focus: Focus::default(),
         }
     }
 
@@ -55,6 +66,7 @@ impl State {
         computer: Arc<Mutex<Computer>>,
         sender: Arc<Mutex<Sender<Input>>>,
         text_size: u32,
+        format_aliases: AliasStyle,
     ) -> io::Result<Vec<lesson_view::State>> {
         // Try and read from the directory, if this fails, the map method won't run and the error will be bubbled up to the constructor
         fs::read_dir(directory).map(|entries| {
@@ -79,7 +91,13 @@ impl State {
                 })
                 // Turn Lesson struct into lesson viewer screen state
                 .map(|lesson: Lesson| {
-                    lesson_view::State::new(lesson, computer.clone(), sender.clone(), text_size)
+                    lesson_view::State::new(
+                        lesson,
+                        computer.clone(),
+                        sender.clone(),
+                        text_size,
+                        format_aliases,
+                    )
                 })
                 // Convert iterator into Vec<lesson_view::State>
                 .collect()
@@ -88,6 +106,26 @@ impl State {
 }
 
 impl State {
+    // Move between the start buttons and the back button and press them with the keyboard (see src/frontend/util/shortcuts.rs)
+    pub fn shortcut(&mut self, shortcut: Shortcut) -> Option<Message> {
+        let lessons = self.lessons.as_ref().map_or(&[][..], |lessons| lessons);
+
+        match shortcut {
+            Shortcut::FocusNext => self.focus.next(lessons.len() + 1),
+            Shortcut::FocusPrevious => self.focus.previous(lessons.len() + 1),
+            Shortcut::Activate => {
+                return Some(match lessons.get(self.focus.get()?) {
+                    Some(lesson) => Message::StartButtonClicked(lesson.clone()),
+                    None => Message::BackClicked,
+                });
+            }
+            Shortcut::Back => return Some(Message::BackClicked),
+            _ => {}
+        }
+
+        None
+    }
+
     pub fn update(&self, message: Message) -> Option<Event> {
         match message {
             // When the user clicks back, return an event to send them to the menu
@@ -100,15 +138,16 @@ impl State {
     pub fn view(&self) -> Element<'_, Message> {
         column![
             // Title text
-            text("Lessons").font(Font::Bold).size(32),
+            text(tr("menu-lessons")).font(Font::Bold).size(32),
             separator::horizontal(),
             column(
                 self.lessons
                     .clone()
                     .map(|lessons| lessons
                         .iter()
+                        .enumerate()
                         // For every lesson, show lesson information and a start button
-                        .map(|state| column![
+                        .map(|(i, state)| column![
                             row![
                                 // Show the lesson title in large bold text
                                 text(
@@ -117,26 +156,32 @@ impl State {
                                         .lesson
                                         .head
                                         .title
-                                        .unwrap_or(String::from("Untitled Lesson"))
+                                        .unwrap_or_else(|| tr("lesson-select-untitled"))
                                 )
                                 .font(Font::Bold)
                                 .size(24),
                                 // Leave the widest possible horizontal gap between the previous and next element
                                 space::horizontal(),
                                 // Start button bundling in the lesson state as a tuple struct
-                                button("Start")
-                                    .on_press(Message::StartButtonClicked(state.clone()))
+                                ring(
+                                    button(text(tr("lesson-select-start")))
+                                        .on_press(Message::StartButtonClicked(state.clone())),
+                                    self.focus.is(i)
+                                )
                             ],
                             // Show the amount of slides
-                            text(format!("{} slide(s)", state.lesson.body.slides.len()))
-                                .font(Font::Italic)
+                            text(tr_with(
+                                "lesson-select-slides",
+                                &[("count", &state.lesson.body.slides.len())]
+                            ))
+                            .font(Font::Italic)
                         ]
                         .spacing(8)
                         .into())
                         .collect::<Vec<_>>())
                     // If there was an error opening the directory, show it in red text instead of showing the list of columns
                     .unwrap_or_else(|e| vec![
-                        text(e)
+                        text(tr(e))
                             .style(|_| text::Style {
                                 color: Some(Color::from_rgb(1f32, 0f32, 0f32))
                             })
@@ -146,8 +191,12 @@ impl State {
             .spacing(16),
             // Leave as much vertical space between the previous and next element
             space::vertical(),
-            // Back button (bottom left corner)
-            button("Back").on_press(Message::BackClicked)
+            // Back button (bottom left corner), after every lesson when moving with the keyboard
+            ring(
+                button(text(tr("back"))).on_press(Message::BackClicked),
+                self.focus
+                    .is(self.lessons.as_ref().map_or(0, |lessons| lessons.len()))
+            )
         ]
         .height(Length::Fill)
         .width(Length::Fill)
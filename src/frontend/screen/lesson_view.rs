@@ -1,23 +1,39 @@
 use std::sync::{Arc, Mutex};
 
 use crate::{
-    backend::lesson_parser::Lesson,
+    backend::{
+        compiler::formatter::{self, AliasStyle},
+        keymap::Keymap,
+        lesson_parser::{Lesson, TraceTable},
+        recovery::Buffer,
+    },
     frontend::{
         pane::{
+            self,
             editor::{self, editor},
+            reference::{self, reference},
             state_viewer::{self, state_viewer},
             style,
             terminal::{self, terminal},
         },
-        util::font::Font,
+        util::{font::Font, highlighter, shortcuts::Shortcut, theme::EditorColours},
+    },
+    shared::{
+        isa::InstructionSet,
+        locale::{tr, tr_with},
+        runtime::{self, Input, Output},
+        trace::Row,
+        vm::{Cell, Computer},
     },
-    shared::{runtime::Input, vm::Computer},
 };
 
 use iced::{
     Element, Padding,
     futures::channel::mpsc::Sender,
-    widget::{button, column, container, pane_grid, row, space, text, text_editor},
+    widget::{
+        button, column, container, pane_grid, radio, row, scrollable, space, text, text_editor,
+        text_input,
+    },
 };
 
 // Messages specific to lesson view screen
@@ -27,16 +43,26 @@ pub enum Message {
     PaneClicked(pane_grid::Pane),
     PaneDragged(pane_grid::DragEvent),
     PaneResized(pane_grid::ResizeEvent),
+    // Focus moved to the next pane (or the previous one if false) with the keyboard
+    PaneCycled(bool),
     // Text editor pane messages
     Editor(editor::Message),
     // State viewer pane messages
     StateViewer(state_viewer::Message),
     // Terminal pane messages
     Terminal(terminal::Message),
+    // Instruction reference pane messages
+    Reference(reference::Message),
     // Back button in lesson pane clicked
     BackLessonClicked,
     // Next button in lesson pane clicked
     NextLessonClicked,
+    // Typing into a cell (row, column) of a trace table slide
+    TraceCellChanged(usize, usize, String),
+    // Picking a choice of a multiple choice quiz slide
+    ChoiceSelected(usize),
+    // Typing the answer to a numeric quiz slide
+    QuizAnswerChanged(String),
     BackClicked,
     SettingsClicked,
 }
@@ -45,25 +71,91 @@ pub enum Message {
 pub enum Event {
     // Result from clicking run button in editor pane
     Run,
+    // Ditto for step button
+    Step,
     // Ditto for stop button
     Stop,
     // Ditto for reset button
     Reset,
+    // Result from editing a register or memory location in the state viewer pane
+    Poke(Cell, i16),
+    // Give keyboard focus to the widget with this id (see Pane::widget)
+    Focus(&'static str),
     // Result from clicking back button
     ToLessonSelect,
     // Result from clicking settings button
     ToSettings,
 }
 
+// A trace table being filled in on the current slide
+#[derive(Debug, Clone)]
+struct TraceExercise {
+    // The rows from running the program (the first row is before the program starts), or why it couldn't be run
+    expected: Result<Vec<Row>, String>,
+    answers: Vec<Vec<String>>,
+    // Whether each cell was right the last time the table was checked
+    marks: Option<Vec<Vec<bool>>>,
+}
+
+impl TraceExercise {
+    // Run the program of the slide to find how many rows the table has
+    fn new(table: &TraceTable, instruction_set: InstructionSet, inputs: &[i16]) -> Self {
+        let expected = table.run(instruction_set, inputs);
+        let rows = expected.as_ref().map_or(0, |rows| rows.len() - 1);
+
+        Self {
+            expected,
+            answers: vec![vec![String::new(); table.columns().len()]; rows],
+            marks: None,
+        }
+    }
+
+    // Mark each cell, returning whether the whole table is right
+    fn check(&mut self) -> bool {
+        let Ok(expected) = &self.expected else {
+            return false;
+        };
+
+        let marks = TraceTable::check(expected, &self.answers);
+        let correct = marks.iter().flatten().all(|mark| *mark);
+        self.marks = Some(marks);
+
+        correct
+    }
+}
+
+// The answer being given on a quiz slide
+#[derive(Debug, Clone, Default)]
+struct QuizAnswer {
+    choice: Option<usize>,
+    answer: String,
+    // Whether the answer was right the last time Next was clicked
+    correct: Option<bool>,
+}
+
 // Represents individual panes in the screen
 #[derive(Debug, Clone)]
 pub enum Pane {
     Editor,
     StateViewer,
     Terminal,
+    Reference,
     Lesson,
 }
 
+impl Pane {
+    // Ditto Pane::widget comment in src/frontend/screen/sandbox.rs
+    fn widget(&self) -> &'static str {
+        match self {
+            Pane::Editor => editor::ID,
+            Pane::StateViewer => state_viewer::ID,
+            Pane::Terminal => "terminal",
+            Pane::Reference => "reference",
+            Pane::Lesson => "lesson",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct State {
     pub lesson: Lesson,
@@ -79,12 +171,30 @@ pub struct State {
     // Text content of the text editor pane
     content: text_editor::Content,
     pub text_size: u32,
+    // Mnemonics the Format button uses for instructions with an alias
+    pub format_aliases: AliasStyle,
     pub computer: Arc<Mutex<Computer>>,
     sender: Arc<Mutex<Sender<Input>>>,
     // Terminal pane outputs
-    pub output: Vec<Box<str>>,
+    pub output: Vec<Output>,
     // Terminal pane error
     pub error: String,
+    // Likely mistakes found when assembling, shown in the terminal and editor panes
+    pub warnings: Vec<String>,
+    // Completions and the outline of labels in the editor pane
+    pub assist: editor::Assist,
+    // Whether the program runs one part of the fetch-decode-execute cycle per tick instead of one instruction
+    pub micro_steps: bool,
+    // Value being typed into a register or memory location in the state viewer pane
+    editing: Option<state_viewer::Edit>,
+    // How memory is shown in the state viewer pane
+    memory_view: state_viewer::MemoryView,
+    // Labels of the assembled program and their addresses, for the labelled memory format
+    labels: Vec<(u8, String)>,
+    // The trace table being filled in, if the current slide is a trace table exercise
+    trace: Option<TraceExercise>,
+    // Ditto for a quiz question
+    quiz: Option<QuizAnswer>,
 }
 
 impl State {
@@ -94,12 +204,16 @@ impl State {
         computer: Arc<Mutex<Computer>>,
         sender: Arc<Mutex<Sender<Input>>>,
         text_size: u32,
+        format_aliases: AliasStyle,
     ) -> Self {
         // Start with editor pane
         let (mut panes, pane) = pane_grid::State::new(Pane::Editor);
 
         // Split vertically (editor pane on left, lesson pane on the right)
-        panes.split(pane_grid::Axis::Vertical, pane, Pane::Lesson);
+        if let Some((lesson, _)) = panes.split(pane_grid::Axis::Vertical, pane, Pane::Lesson) {
+            // Split the lesson pane horizontally (lesson pane on top, instruction reference below)
+            panes.split(pane_grid::Axis::Horizontal, lesson, Pane::Reference);
+        }
         // Split horizontally (editor pane on top, state viewer pane below, lesson pane on the right)
         panes.split(pane_grid::Axis::Horizontal, pane, Pane::StateViewer);
         // Split horizontally again (editor pane on top, followed by terminal and state viewer below, lesson pane on the right)
@@ -114,21 +228,200 @@ impl State {
             pane_focused: None,
             content: text_editor::Content::new(),
             text_size,
+            format_aliases,
             computer,
             sender,
             output: Vec::new(),
             error: String::new(),
+            warnings: Vec::new(),
+            assist: editor::Assist::default(),
+            micro_steps: false,
+            editing: None,
+            memory_view: state_viewer::MemoryView::default(),
+            labels: Vec::new(),
+            trace: None,
+            quiz: None,
         }
+        .with_slide(0)
+    }
+
+    // The program being written, to be autosaved in case the application crashes (nothing if the editor is empty)
+    pub fn buffer(&self) -> Option<Buffer> {
+        let text = self.content.text();
+
+        (!text.trim().is_empty()).then(|| Buffer {
+            name: tr_with(
+                "lesson-buffer",
+                &[(
+                    "title",
+                    &self
+                        .lesson
+                        .head
+                        .title
+                        .clone()
+                        .unwrap_or_else(|| tr("untitled")),
+                )],
+            ),
+            path: None,
+            text,
+        })
+    }
+
+    // Move to a slide, starting a new trace table or quiz if it has one
+    pub fn with_slide(mut self, slide: usize) -> Self {
+        self.set_slide(slide);
+        self
+    }
+
+    fn set_slide(&mut self, slide: usize) {
+        self.slide = slide;
+
+        let slide = &self.lesson.body.slides[slide];
+        self.trace = slide.trace_table.as_ref().map(|table| {
+            TraceExercise::new(table, self.lesson.head.instruction_set, &slide.inputs.items)
+        });
+        self.quiz = slide.quiz.as_ref().map(|_| QuizAnswer::default());
+    }
+
+    // The question with either its choices or a box to type the answer in, and the feedback if the answer was wrong
+    fn quiz(&self) -> Option<Element<'_, Message>> {
+        let quiz = self.lesson.body.slides[self.slide].quiz.as_ref()?;
+        let answer = self.quiz.as_ref()?;
+
+        let input: Element<'_, Message> = if quiz.choices.is_empty() {
+            text_input(&tr("lesson-answer"), &answer.answer)
+                .on_input(Message::QuizAnswerChanged)
+                .width(120)
+                .into()
+        } else {
+            column(quiz.choices.iter().enumerate().map(|(i, choice)| {
+                radio(&choice.text, i, answer.choice, Message::ChoiceSelected).into()
+            }))
+            .spacing(6)
+            .into()
+        };
+
+        let result = answer.correct.map(|correct| {
+            if correct {
+                column![text(tr("lesson-correct"))]
+            } else {
+                column![
+                    text(tr("lesson-incorrect")).style(style::warning),
+                    quiz.feedback.as_deref().map(text)
+                ]
+                .spacing(4)
+            }
+        });
+
+        Some(
+            column![text(&quiz.question).font(Font::Bold), input, result]
+                .spacing(8)
+                .into(),
+        )
+    }
+
+    // The program, an empty table for the student to fill in, and whether it was right when Next was clicked
+    fn trace_table(&self) -> Option<Element<'_, Message>> {
+        let table = self.lesson.body.slides[self.slide].trace_table.as_ref()?;
+        let trace = self.trace.as_ref()?;
+
+        let rows = match &trace.expected {
+            Ok(_) => trace.answers.iter().enumerate().map(|(i, answers)| {
+                row(
+                    std::iter::once(text(format!("{}", i + 1)).width(30).into()).chain(
+                        answers.iter().enumerate().map(|(j, answer)| {
+                            let mark = trace
+                                .marks
+                                .as_ref()
+                                .and_then(|marks| marks.get(i)?.get(j).copied());
+
+                            text_input("", answer)
+                                .on_input(move |value| Message::TraceCellChanged(i, j, value))
+                                .padding(2)
+                                .width(60)
+                                .style(move |theme, status| {
+                                    style::marked_input(theme, status, mark)
+                                })
+                                .into()
+                        }),
+                    ),
+                )
+                .spacing(4)
+                .into()
+            }),
+            // Shown to the lesson author rather than the student
+            Err(e) => {
+                return Some(text(tr_with("lesson-trace-error", &[("error", e)])).into());
+            }
+        };
+
+        let header = row(std::iter::once(text("").width(30).into()).chain(
+            table
+                .columns()
+                .into_iter()
+                .map(|column| text(column).font(Font::Bold).width(60).into()),
+        ))
+        .spacing(4);
+
+        let result = trace.marks.as_ref().map(|marks| {
+            let wrong = marks.iter().flatten().filter(|mark| !**mark).count();
+
+            if wrong == 0 {
+                text(tr("lesson-trace-correct"))
+            } else {
+                text(tr_with("lesson-trace-wrong", &[("wrong", &wrong)])).style(style::warning)
+            }
+        });
+
+        Some(
+            column![
+                text(&table.program),
+                header,
+                scrollable(column(rows).spacing(4)).height(iced::Length::Fill),
+                result
+            ]
+            .spacing(6)
+            .into(),
+        )
     }
 }
 
 impl State {
+    // Ditto State::shortcut comment in src/frontend/screen/sandbox.rs, with shortcuts to move between slides too
+    pub fn shortcut(&self, shortcut: Shortcut) -> Option<Message> {
+        Some(match shortcut {
+            // Lessons aren't saved to files, so there is nothing for Save to do
+            Shortcut::Assemble => Message::Editor(editor::Message::AssembleClicked),
+            Shortcut::Run => Message::Editor(editor::Message::RunClicked),
+            Shortcut::Step => Message::Editor(editor::Message::StepClicked),
+            Shortcut::Stop => Message::Editor(editor::Message::StopClicked),
+            Shortcut::Reset => Message::Editor(editor::Message::ResetClicked),
+            Shortcut::NextSlide => Message::NextLessonClicked,
+            Shortcut::PreviousSlide => Message::BackLessonClicked,
+            Shortcut::NextPane => Message::PaneCycled(true),
+            Shortcut::PreviousPane => Message::PaneCycled(false),
+            Shortcut::ToggleSummary => Message::StateViewer(state_viewer::Message::SummaryToggled(
+                !self.memory_view.summary,
+            )),
+            Shortcut::Back => Message::BackClicked,
+            _ => return None,
+        })
+    }
+
     pub fn update(&mut self, message: Message) -> Option<Event> {
         match message {
             // Pane interactivity boilerplate
             Message::PaneClicked(pane) => {
                 self.pane_focused = Some(pane);
             }
+            // Give keyboard focus to what can be typed into in the pane, so the keyboard can be used without clicking
+            Message::PaneCycled(forward) => {
+                self.pane_focused = pane::cycle(&self.panes, self.pane_focused, forward);
+
+                if let Some(pane) = self.pane_focused.and_then(|pane| self.panes.get(pane)) {
+                    return Some(Event::Focus(pane.widget()));
+                }
+            }
             Message::PaneDragged(pane_grid::DragEvent::Dropped { pane, target }) => {
                 self.panes.drop(pane, target);
             }
@@ -137,42 +430,110 @@ impl State {
             }
 
             Message::Editor(message) => match message {
-                editor::Message::ContentChanged(action) => self.content.perform(action),
+                editor::Message::ContentChanged(action) => {
+                    self.assist
+                        .perform(&mut self.content, action, self.lesson.head.instruction_set)
+                }
+                editor::Message::SuggestionPicked(index) => {
+                    self.assist
+                        .accept(&mut self.content, index, self.lesson.head.instruction_set)
+                }
+                editor::Message::SuggestionsDismissed => self.assist.dismiss(),
+                editor::Message::OutlineClicked(label) => self.assist.go_to_definition(
+                    &mut self.content,
+                    &label,
+                    self.lesson.head.instruction_set,
+                ),
 
                 editor::Message::AssembleClicked => {
                     self.error = String::new();
+                    self.labels = state_viewer::labels(
+                        &self.content.text(),
+                        self.lesson.head.instruction_set,
+                    );
 
                     // Send a message to the sender to compile the program with the source code in the text editor
-                    if let Ok(mut sender) = self.sender.lock() {
-                        sender
-                            .try_send(Input::AssembleClicked(self.content.text()))
-                            .unwrap()
+                    runtime::send(
+                        &self.sender,
+                        Input::AssembleClicked(
+                            self.content.text(),
+                            self.lesson.head.instruction_set,
+                        ),
+                    );
+                }
+
+                editor::Message::FormatClicked => {
+                    // Replace the text in the editor with the formatted text, or show why the source code couldn't be formatted
+                    match formatter::format(
+                        &self.content.text(),
+                        self.lesson.head.instruction_set,
+                        self.format_aliases,
+                    ) {
+                        Ok(formatted) => {
+                            self.error = String::new();
+                            self.content = text_editor::Content::with_text(&formatted);
+                            self.assist
+                                .reload(&self.content, self.lesson.head.instruction_set);
+                        }
+                        Err(e) => self.error = e.to_string(),
                     }
                 }
 
                 editor::Message::ResetClicked => return Some(Event::Reset),
+                editor::Message::StepClicked => return Some(Event::Step),
                 editor::Message::StopClicked => return Some(Event::Stop),
                 editor::Message::RunClicked => return Some(Event::Run),
 
                 _ => {}
             },
 
+            Message::StateViewer(message) => match message {
+                state_viewer::Message::MicroStepsToggled(micro_steps) => {
+                    self.micro_steps = micro_steps
+                }
+                state_viewer::Message::MemoryFormatSelected(format) => {
+                    self.memory_view.format = format
+                }
+                state_viewer::Message::TableToggled(table) => self.memory_view.table = table,
+                state_viewer::Message::SummaryToggled(summary) => {
+                    self.memory_view.summary = summary
+                }
+                state_viewer::Message::EditChanged(cell, value) => {
+                    self.editing = Some(state_viewer::Edit { cell, value })
+                }
+                // Only whole numbers can be put into the computer, anything else is left to be corrected
+                state_viewer::Message::EditSubmitted => {
+                    if let Some(edit) = &self.editing
+                        && let Ok(value) = edit.value.trim().parse()
+                    {
+                        let cell = edit.cell;
+                        self.editing = None;
+                        return Some(Event::Poke(cell, value));
+                    }
+                }
+            },
+
             Message::SettingsClicked => return Some(Event::ToSettings),
             Message::BackClicked => return Some(Event::ToLessonSelect),
 
             Message::NextLessonClicked => {
-                // Only allow the user to progress to the next lesson if the outputs match the list of outputs defined in the lesson
-                if self
-                    .output
-                    .iter()
-                    .map(|x| x.parse::<i16>().unwrap_or(0))
-                    .collect::<Vec<i16>>()
-                    == self.lesson.body.slides[self.slide].outputs.items
-                    && self.error == String::new()
-                {
+                let slide = &self.lesson.body.slides[self.slide];
+
+                // Only allow the user to progress to the next lesson if the trace table or quiz answer is right, or for programming exercises if the outputs match the list of outputs defined in the lesson
+                let passed = match (&mut self.trace, &mut self.quiz, &slide.quiz) {
+                    (Some(trace), _, _) => trace.check(),
+                    (_, Some(answer), Some(quiz)) => {
+                        let correct = quiz.check(answer.choice, &answer.answer);
+                        answer.correct = Some(correct);
+                        correct
+                    }
+                    _ => slide.outputs.matches(&self.output) && self.error == String::new(),
+                };
+
+                if passed {
                     // Complete the lesson if there are no more slides
                     if self.slide < self.lesson.body.slides.len() - 1 {
-                        self.slide += 1
+                        self.set_slide(self.slide + 1)
                     } else {
                         self.completed = true
                     }
@@ -180,7 +541,26 @@ impl State {
             }
             Message::BackLessonClicked => {
                 if self.slide != 0 {
-                    self.slide -= 1
+                    self.set_slide(self.slide - 1)
+                }
+            }
+            Message::ChoiceSelected(choice) => {
+                if let Some(answer) = &mut self.quiz {
+                    answer.choice = Some(choice)
+                }
+            }
+            Message::QuizAnswerChanged(value) => {
+                if let Some(answer) = &mut self.quiz {
+                    answer.answer = value
+                }
+            }
+            Message::TraceCellChanged(row, column, value) => {
+                if let Some(cell) = self
+                    .trace
+                    .as_mut()
+                    .and_then(|trace| trace.answers.get_mut(row)?.get_mut(column))
+                {
+                    *cell = value;
                 }
             }
 
@@ -189,19 +569,26 @@ impl State {
         None
     }
 
-    pub fn view(&self) -> Element<'_, Message> {
+    pub fn view<'a>(
+        &'a self,
+        running: bool,
+        settings: bool,
+        colours: EditorColours,
+        keymap: &'a Keymap,
+    ) -> Element<'a, Message> {
         column![
             container(
                 pane_grid(&self.panes, |pane, state, _is_maximized| {
                     let focused = self.pane_focused == Some(pane);
 
-                    // Convert Pane to static string
-                    let title = match state {
-                        Pane::Editor => "Editor",
-                        Pane::StateViewer => "State Viewer",
-                        Pane::Terminal => "Terminal",
-                        Pane::Lesson => "Lesson",
-                    };
+                    // Convert Pane to its title
+                    let title = tr(match state {
+                        Pane::Editor => "pane-editor",
+                        Pane::StateViewer => "pane-state-viewer",
+                        Pane::Terminal => "pane-terminal",
+                        Pane::Reference => "pane-reference",
+                        Pane::Lesson => "pane-lesson",
+                    });
 
                     // Add title to title bar
                     let title_bar = pane_grid::TitleBar::new(
@@ -215,15 +602,40 @@ impl State {
 
                     pane_grid::Content::new(match state {
                         // Use pane widgets to display content, passing in relevant values
-                        Pane::Editor => {
-                            editor(&self.content, self.text_size, None).map(Message::Editor)
-                        }
+                        Pane::Editor => editor(
+                            &self.content,
+                            self.text_size,
+                            &self.warnings,
+                            &self.assist,
+                            None,
+                            highlighter::Settings {
+                                colours,
+                                instruction_set: self.lesson.head.instruction_set,
+                            },
+                            keymap,
+                        )
+                        .map(Message::Editor),
                         Pane::StateViewer => {
-                            state_viewer(&self.computer.lock().unwrap()).map(Message::StateViewer)
-                        }
-                        Pane::Terminal => {
-                            terminal(&self.output, &self.error).map(Message::Terminal)
+                            // Only allow editing while the program is paused
+                            state_viewer(
+                                &self.computer.lock().unwrap(),
+                                self.micro_steps,
+                                (!running).then_some(self.editing.as_ref()),
+                                self.memory_view,
+                                &self.labels,
+                            )
+                            .map(Message::StateViewer)
                         }
+                        Pane::Terminal => terminal(&self.output, &self.warnings, &self.error)
+                            .map(Message::Terminal),
+
+                        // Show help for the instruction under the cursor in the editor
+                        Pane::Reference => reference(
+                            self.lesson.head.instruction_set,
+                            &editor::Assist::word(&self.content),
+                            self.text_size,
+                        )
+                        .map(Message::Reference),
 
                         Pane::Lesson => column![
                             // Show lesson slide content with navigation buttons if the lesson is not completed
@@ -231,17 +643,22 @@ impl State {
                                 container(column![
                                     // Turn slide into Element<'_, Message> via parse method in src/backend/lesson_parser.rs
                                     self.lesson.body.slides[self.slide].parse(),
+                                    self.trace_table(),
+                                    self.quiz(),
                                     space::vertical(),
                                     row![
-                                        button("Back").on_press(Message::BackLessonClicked),
+                                        button(text(tr("back")))
+                                            .on_press(Message::BackLessonClicked),
                                         space::horizontal(),
-                                        button("Next").on_press(Message::NextLessonClicked)
+                                        button(text(tr("lesson-next")))
+                                            .on_press(Message::NextLessonClicked)
                                     ]
                                 ])
                             }),
                             // ... otherwise show some text indicating the lesson is complete
-                            self.completed
-                                .then(|| { text("Lesson Completed!").font(Font::Bold).size(24) }),
+                            self.completed.then(|| {
+                                text(tr("lesson-completed")).font(Font::Bold).size(24)
+                            }),
                         ]
                         // Padding as to not cause elements to appear behind the title bar
                         .padding(Padding {
@@ -268,9 +685,9 @@ impl State {
             )
             .padding([8, 0]),
             row![
-                button("Back").on_press(Message::BackClicked),
+                button(text(tr("back"))).on_press(Message::BackClicked),
                 space::horizontal(),
-                button("Settings").on_press(Message::SettingsClicked),
+                settings.then(|| button(text(tr("settings"))).on_press(Message::SettingsClicked)),
             ]
         ]
         .padding(12)
@@ -1,13 +1,35 @@
+use std::path::Path;
+
 use iced::border::Radius;
-use iced::widget::{button, column, container, row, text, text::Shaping};
-use iced::{Alignment, Background, Border, Color, Element, Length, Theme, alignment};
+use iced::widget::{button, column, container, row, space, text, text::Shaping};
+use iced::{Alignment, Background, Border, Color, Element, Length, Theme};
+
+use crate::backend::{
+    config::Policy,
+    recovery::Buffer,
+    session::{LastScreen, Session},
+};
+use crate::frontend::util::{
+    shortcuts::Shortcut,
+    widgets::focus::{Focus, ring},
+};
+use crate::shared::locale::{tr, tr_with};
 
 // The messages specific to the main menu screen
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Message {
     SandboxButtonClicked,
     LessonsButtonClicked,
     SettingsButtonClicked,
+    ReopenButtonClicked,
+    // A file in the recent files list was clicked
+    RecentFileClicked(String),
+    // Answers to the prompt shown when unsaved work was autosaved before the application last closed
+    RestoreClicked,
+    DiscardClicked,
+    // Answers to the notice shown when the config couldn't be loaded
+    ResetConfigClicked,
+    DismissConfigClicked,
 }
 
 // What can be returned from the update method (used by src/frontend/screen.rs)
@@ -15,11 +37,37 @@ pub enum Event {
     ToLessonSelect,
     ToSandbox,
     ToSettings,
+    // Go back to the screen (and sandbox files) the user was using when the application was last closed
+    ReopenSession,
+    // Open the sandbox with a recent file
+    OpenRecent(String),
+    // Open the autosaved buffers in the sandbox
+    RestoreRecovered,
+    DiscardRecovered,
+    // Replace a config that couldn't be loaded with the default config
+    ResetConfig,
+    DismissConfigError,
 }
 
-// The menu has no state it needs to store but does have functions so I create a unit struct (a Zero-Sized Type) which takes up 0 bytes at runtime
-#[derive(Debug, Clone)]
-pub struct State;
+// The menu only needs to know what it can offer to reopen from the session (see src/backend/session.rs)
+#[derive(Debug, Clone, Default)]
+pub struct State {
+    // Most recently opened or saved first
+    recent_files: Vec<String>,
+    // Whether there is a last session to reopen
+    can_reopen: bool,
+    // Names of the autosaved buffers that haven't been restored or thrown away yet
+    recovered: Vec<String>,
+    // Why the config couldn't be loaded, if it couldn't
+    config_error: Option<String>,
+    // Ditto for the system config, which the user can't reset or dismiss
+    system_error: Option<String>,
+    // Whether the system config has hidden the sandbox or settings buttons
+    hide_sandbox: bool,
+    hide_settings: bool,
+    // The button that has keyboard focus
+    focus: Focus,
+}
 
 // A style for the menu containers (big indicators above buttons), slightly transparent using the primary theme colours and rounded corners only on the top edges (to leave no ugly pixel gaps as these containers are right up against the buttons)
 pub fn menu_container(theme: &Theme) -> container::Style {
@@ -41,6 +89,91 @@ pub fn menu_container(theme: &Theme) -> container::Style {
 }
 
 impl State {
+    // Recent files, reopening the sandbox, and restoring autosaved sandbox tabs all open the sandbox, so they are left out when it is hidden
+    pub fn new(
+        session: &Session,
+        recovered: &[Buffer],
+        config_error: Option<&str>,
+        system_error: Option<&str>,
+        policy: &Policy,
+    ) -> Self {
+        let hide_sandbox = policy.hide_sandbox;
+
+        Self {
+            config_error: config_error.map(str::to_string),
+            system_error: system_error.map(str::to_string),
+            recent_files: if hide_sandbox {
+                Vec::new()
+            } else {
+                session.recent_files.clone()
+            },
+            can_reopen: session.can_reopen()
+                && !(hide_sandbox
+                    && matches!(session.screen, LastScreen::Sandbox | LastScreen::Menu)),
+            recovered: if hide_sandbox {
+                Vec::new()
+            } else {
+                recovered.iter().map(|buffer| buffer.name.clone()).collect()
+            },
+            hide_sandbox,
+            hide_settings: policy.hide_settings,
+            focus: Focus::default(),
+        }
+    }
+
+    // The messages of the buttons in the order they are shown, so the keyboard can move between them
+    fn buttons(&self) -> Vec<Message> {
+        let mut buttons = Vec::new();
+
+        if self.config_error.is_some() {
+            buttons.extend([Message::ResetConfigClicked, Message::DismissConfigClicked]);
+        }
+        if !self.recovered.is_empty() {
+            buttons.extend([Message::RestoreClicked, Message::DiscardClicked]);
+        }
+
+        buttons.push(Message::LessonsButtonClicked);
+        if !self.hide_sandbox {
+            buttons.push(Message::SandboxButtonClicked);
+        }
+
+        buttons.extend(
+            self.recent_files
+                .iter()
+                .cloned()
+                .map(Message::RecentFileClicked),
+        );
+        if self.can_reopen {
+            buttons.push(Message::ReopenButtonClicked);
+        }
+        if !self.hide_settings {
+            buttons.push(Message::SettingsButtonClicked);
+        }
+
+        buttons
+    }
+
+    // Whether the button that sends a message has keyboard focus
+    fn focused(&self, message: &Message) -> bool {
+        self.focus
+            .get()
+            .is_some_and(|i| self.buttons().get(i) == Some(message))
+    }
+
+    // Move between the buttons and press them with the keyboard (see src/frontend/util/shortcuts.rs)
+    pub fn shortcut(&mut self, shortcut: Shortcut) -> Option<Message> {
+        let buttons = self.buttons();
+
+        match shortcut {
+            Shortcut::FocusNext => self.focus.next(buttons.len()),
+            Shortcut::FocusPrevious => self.focus.previous(buttons.len()),
+            Shortcut::Activate => return buttons.get(self.focus.get()?).cloned(),
+            _ => {}
+        }
+
+        None
+    }
+
     // Update function (similar to one in main, used by src/frontend/screen.rs)
     pub fn update(&self, message: Message) -> Option<Event> {
         match message {
@@ -50,12 +183,74 @@ impl State {
             Message::LessonsButtonClicked => Some(Event::ToLessonSelect),
             // Ditto for settings button and screen
             Message::SettingsButtonClicked => Some(Event::ToSettings),
+            Message::ReopenButtonClicked => Some(Event::ReopenSession),
+            Message::RecentFileClicked(path) => Some(Event::OpenRecent(path)),
+            Message::RestoreClicked => Some(Event::RestoreRecovered),
+            Message::DiscardClicked => Some(Event::DiscardRecovered),
+            Message::ResetConfigClicked => Some(Event::ResetConfig),
+            Message::DismissConfigClicked => Some(Event::DismissConfigError),
         }
     }
 
     // Ditto update comment but for view instead
     pub fn view(&self) -> Element<'_, Message> {
         column![
+            // Explain that the system config is broken, there is nothing the user can do about it so there are no buttons
+            self.system_error.as_ref().map(|e| {
+                container(text(e.as_str()).width(Length::Fill))
+                    .padding(12)
+                    .style(menu_container)
+            }),
+            // Explain that the default settings are being used and offer to overwrite the config with them
+            self.config_error.as_ref().map(|e| {
+                container(
+                    row![
+                        text(tr_with("menu-config-error", &[("error", e)])).width(Length::Fill),
+                        ring(
+                            button(text(tr("menu-reset-config")))
+                                .style(button::danger)
+                                .on_press(Message::ResetConfigClicked),
+                            self.focused(&Message::ResetConfigClicked)
+                        ),
+                        ring(
+                            button(text(tr("menu-dismiss")))
+                                .style(button::secondary)
+                                .on_press(Message::DismissConfigClicked),
+                            self.focused(&Message::DismissConfigClicked)
+                        ),
+                    ]
+                    .spacing(8)
+                    .align_y(Alignment::Center),
+                )
+                .padding(12)
+                .style(menu_container)
+            }),
+            // Offer to restore unsaved work across the top of the screen
+            (!self.recovered.is_empty()).then(|| {
+                container(
+                    row![
+                        text(tr_with(
+                            "menu-recovered",
+                            &[("files", &self.recovered.join(", "))]
+                        ))
+                        .width(Length::Fill),
+                        ring(
+                            button(text(tr("menu-restore"))).on_press(Message::RestoreClicked),
+                            self.focused(&Message::RestoreClicked)
+                        ),
+                        ring(
+                            button(text(tr("menu-discard")))
+                                .style(button::danger)
+                                .on_press(Message::DiscardClicked),
+                            self.focused(&Message::DiscardClicked)
+                        ),
+                    ]
+                    .spacing(8)
+                    .align_y(Alignment::Center),
+                )
+                .padding(12)
+                .style(menu_container)
+            }),
             container(
                 row![
                     column![
@@ -67,11 +262,14 @@ impl State {
                             .align_x(Alignment::Center)
                             .align_y(Alignment::Center)
                             .style(menu_container),
-                        button("Lessons")
-                            .width(Length::Fill)
-                            .on_press(Message::LessonsButtonClicked)
+                        ring(
+                            button(text(tr("menu-lessons")))
+                                .width(Length::Fill)
+                                .on_press(Message::LessonsButtonClicked),
+                            self.focused(&Message::LessonsButtonClicked)
+                        )
                     ],
-                    column![
+                    (!self.hide_sandbox).then(|| column![
                         // Ditto advanced shaping comment
                         container(text("🛠️").shaping(Shaping::Advanced).size(96))
                             .width(Length::Fill)
@@ -80,10 +278,13 @@ impl State {
                             .align_x(Alignment::Center)
                             .align_y(Alignment::Center)
                             .style(menu_container),
-                        button("Sandbox")
-                            .width(Length::Fill)
-                            .on_press(Message::SandboxButtonClicked)
-                    ]
+                        ring(
+                            button(text(tr("menu-sandbox")))
+                                .width(Length::Fill)
+                                .on_press(Message::SandboxButtonClicked),
+                            self.focused(&Message::SandboxButtonClicked)
+                        )
+                    ])
                 ]
                 .spacing(32),
             )
@@ -94,11 +295,46 @@ impl State {
             .align_y(Alignment::Center)
             // Wide gap
             .padding(128),
-            // Settings button in bottom right
-            container(button("Settings").on_press(Message::SettingsButtonClicked))
-                .width(Length::Fill)
-                .align_x(alignment::Horizontal::Right)
-                .padding(12)
+            // Recent files in the bottom left, reopen and settings buttons in bottom right
+            row![
+                (!self.recent_files.is_empty()).then(|| {
+                    column![text(tr("menu-recent-files"))].extend(self.recent_files.iter().map(
+                        |path| {
+                            let message = Message::RecentFileClicked(path.clone());
+
+                            // Show just the file name, the full path is passed along
+                            ring(
+                                button(text(
+                                    Path::new(path)
+                                        .file_name()
+                                        .map(|name| name.to_string_lossy().into_owned())
+                                        .unwrap_or_else(|| path.clone()),
+                                ))
+                                .style(button::text)
+                                .padding([2, 0])
+                                .on_press(message.clone()),
+                                self.focused(&message),
+                            )
+                        },
+                    ))
+                }),
+                space::horizontal(),
+                self.can_reopen.then(|| {
+                    ring(
+                        button(text(tr("menu-reopen"))).on_press(Message::ReopenButtonClicked),
+                        self.focused(&Message::ReopenButtonClicked),
+                    )
+                }),
+                (!self.hide_settings).then(|| {
+                    ring(
+                        button(text(tr("settings"))).on_press(Message::SettingsButtonClicked),
+                        self.focused(&Message::SettingsButtonClicked),
+                    )
+                }),
+            ]
+            .spacing(8)
+            .align_y(Alignment::End)
+            .padding(12)
         ]
         // Turn Column struct into Element struct
         .into()
@@ -1,16 +1,31 @@
+use std::fs;
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 
-use crate::shared::vm::Computer;
+use crate::backend::compiler::formatter::{self, AliasStyle};
+use crate::backend::keymap::Keymap;
+use crate::backend::recovery::Buffer;
+use crate::backend::session::{Layout, OpenFile, SandboxSession};
+use crate::shared::{
+    isa::InstructionSet,
+    locale::{tr, tr_with},
+    vm::{Cell, Computer},
+};
 use crate::{
     frontend::pane::{
+        self,
         editor::{self, editor},
+        reference::{self, reference},
         state_viewer::{self, state_viewer},
         style,
         terminal::{self, terminal},
     },
-    shared::runtime::Input,
+    frontend::util::{highlighter, shortcuts::Shortcut, theme::EditorColours},
+    shared::runtime::{self, Input, Output},
 };
 
+use serde::{Deserialize, Serialize};
+
 use iced::{
     Element,
     futures::channel::mpsc::Sender,
@@ -24,12 +39,24 @@ pub enum Message {
     PaneClicked(pane_grid::Pane),
     PaneDragged(pane_grid::DragEvent),
     PaneResized(pane_grid::ResizeEvent),
+    // Focus moved to the next pane (or the previous one if false) with the keyboard
+    PaneCycled(bool),
     // Text editor pane messages
     Editor(editor::Message),
     // State viewer pane messages
     StateViewer(state_viewer::Message),
     // Terminal pane messages
     Terminal(terminal::Message),
+    // Instruction reference pane messages
+    Reference(reference::Message),
+    // Tab bar messages, tabs are referred to by their position
+    TabSelected(usize),
+    TabCloseClicked(usize),
+    NewTabClicked,
+    // Answers to the save changes prompt
+    PromptSaveClicked,
+    PromptDiscardClicked,
+    PromptCancelClicked,
     BackClicked,
     SettingsClicked,
 }
@@ -37,15 +64,23 @@ pub enum Message {
 // Events specific to sandbox screen
 pub enum Event {
     // Result from clicking the open button (to load a file)
-    OpenLMC(State),
-    // Result from clicking the save button
-    SaveLMC(State),
+    OpenLMC,
+    // Result from clicking the save button, with the id of the tab, the path it was last saved to (None to ask where to save it), and the text to write
+    SaveLMC(usize, Option<String>, String),
+    // Result from clicking the export trace button
+    ExportTrace,
     // Result from clicking the run button in editor pane
     Run,
+    // Ditto for step button
+    Step,
     // Ditto for stop button
     Stop,
     // Ditto for reset button
     Reset,
+    // Result from editing a register or memory location in the state viewer pane
+    Poke(Cell, i16),
+    // Give keyboard focus to the widget with this id (see Pane::widget)
+    Focus(&'static str),
     // Result from sending input in the input box in the editor pane
     SubmitInput(String),
     // Result from clicking back button
@@ -54,12 +89,72 @@ pub enum Event {
     ToSettings,
 }
 
-// Represents individual panes in the screen
-#[derive(Debug, Clone)]
+// Represents individual panes in the screen, saved in the session to restore the layout
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub enum Pane {
     Editor,
     StateViewer,
     Terminal,
+    Reference,
+}
+
+impl Pane {
+    // The id of the widget given keyboard focus when the pane is focused with the keyboard, no widget has the id of the terminal or reference panes so focusing them takes focus away from the editor
+    fn widget(&self) -> &'static str {
+        match self {
+            Pane::Editor => editor::ID,
+            Pane::StateViewer => state_viewer::ID,
+            Pane::Terminal => "terminal",
+            Pane::Reference => "reference",
+        }
+    }
+}
+
+// A program open in the editor pane
+#[derive(Debug, Clone)]
+pub struct Tab {
+    // Tabs can be closed while a file dialog is open, so saving finds the tab again by its id rather than its position
+    pub id: usize,
+    // Text content of the text editor pane
+    pub content: text_editor::Content,
+    // The file the program was opened from or last saved to (None for a new program)
+    pub path: Option<String>,
+    // Whether there are changes that haven't been saved
+    pub dirty: bool,
+}
+
+impl Tab {
+    fn new(id: usize, text: &str, path: Option<String>) -> Self {
+        Self {
+            id,
+            content: text_editor::Content::with_text(text),
+            path,
+            dirty: false,
+        }
+    }
+
+    // The file name shown in the tab bar
+    pub fn name(&self) -> String {
+        self.path
+            .as_deref()
+            .and_then(|path| Path::new(path).file_name())
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| tr("untitled"))
+    }
+
+    // A new program with nothing typed into it, which can be replaced by an opened file without losing anything
+    fn is_blank(&self) -> bool {
+        self.path.is_none() && !self.dirty && self.content.text().trim().is_empty()
+    }
+}
+
+// What would lose unsaved changes if the user carries on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Prompt {
+    // Closing the tab with this id
+    Close(usize),
+    // Going back to the menu
+    Leave,
 }
 
 #[derive(Debug, Clone)]
@@ -67,17 +162,41 @@ pub struct State {
     panes: pane_grid::State<Pane>,
     // Current pane the user is interacting with
     pane_focused: Option<pane_grid::Pane>,
-    // Text content of the text editor pane
-    pub content: text_editor::Content,
+    // Programs open in the editor pane, there is always at least one
+    pub tabs: Vec<Tab>,
+    // Position of the tab shown in the editor pane
+    pub active: usize,
+    // Id given to the next tab opened
+    next_id: usize,
+    // Shown instead of the back and settings buttons when the user would lose unsaved changes
+    pub prompt: Option<Prompt>,
+    // Id of the tab to close once it has been saved (when Save was picked in the prompt)
+    closing: Option<usize>,
     pub text_size: u32,
+    // Instruction set programs are assembled with, picked in the settings screen
+    pub instruction_set: InstructionSet,
+    // Mnemonics the Format button uses for instructions with an alias
+    pub format_aliases: AliasStyle,
     pub computer: Arc<Mutex<Computer>>,
     sender: Arc<Mutex<Sender<Input>>>,
     // Input in the input box in the editor pane
     input: String,
     // Terminal pane outputs
-    pub output: Vec<Box<str>>,
+    pub output: Vec<Output>,
     // Terminal pane error
     pub error: String,
+    // Likely mistakes found when assembling, shown in the terminal and editor panes
+    pub warnings: Vec<String>,
+    // Completions and the outline of labels in the editor pane
+    pub assist: editor::Assist,
+    // Whether the program runs one part of the fetch-decode-execute cycle per tick instead of one instruction
+    pub micro_steps: bool,
+    // Value being typed into a register or memory location in the state viewer pane
+    editing: Option<state_viewer::Edit>,
+    // How memory is shown in the state viewer pane
+    memory_view: state_viewer::MemoryView,
+    // Labels of the assembled program and their addresses, for the labelled memory format
+    labels: Vec<(u8, String)>,
 }
 
 impl State {
@@ -86,36 +205,293 @@ impl State {
         computer: Arc<Mutex<Computer>>,
         sender: Arc<Mutex<Sender<Input>>>,
         text_size: u32,
+        instruction_set: InstructionSet,
+        format_aliases: AliasStyle,
     ) -> Self {
         // Start with editor pane
         let (mut panes, pane) = pane_grid::State::new(Pane::Editor);
 
-        // Split vertically (editor pane on left, lesson pane on the right)
-        panes.split(pane_grid::Axis::Vertical, pane, Pane::StateViewer);
-        // Split horizontally (editor pane on top, terminal pane below, lesson pane on the right)
+        // Split vertically (editor pane on left, state viewer pane on the right)
+        if let Some((state_viewer, _)) =
+            panes.split(pane_grid::Axis::Vertical, pane, Pane::StateViewer)
+        {
+            // Split the state viewer horizontally (state viewer on top, instruction reference below)
+            panes.split(pane_grid::Axis::Horizontal, state_viewer, Pane::Reference);
+        }
+        // Split horizontally (editor pane on top, terminal pane below, state viewer and instruction reference panes on the right)
         panes.split(pane_grid::Axis::Horizontal, pane, Pane::Terminal);
 
         Self {
             panes,
             pane_focused: None,
-            content: text_editor::Content::new(),
+            tabs: vec![Tab::new(0, "", None)],
+            active: 0,
+            next_id: 1,
+            prompt: None,
+            closing: None,
             text_size,
+            instruction_set,
+            format_aliases,
             computer,
             sender,
             input: String::new(),
             output: Vec::new(),
             error: String::new(),
+            warnings: Vec::new(),
+            assist: editor::Assist::default(),
+            micro_steps: false,
+            editing: None,
+            memory_view: state_viewer::MemoryView::default(),
+            labels: Vec::new(),
+        }
+    }
+
+    // The program shown in the editor pane
+    pub fn content(&self) -> &text_editor::Content {
+        &self.tabs[self.active].content
+    }
+
+    // Open a file in a new tab, switching to it instead if it is already open, and replacing the current tab if nothing has been typed into it
+    pub fn open(&mut self, path: String, text: &str) {
+        if let Some(index) = self
+            .tabs
+            .iter()
+            .position(|tab| tab.path.as_ref() == Some(&path))
+        {
+            self.select(index);
+            return;
+        }
+
+        let tab = Tab::new(self.next_id, text, Some(path));
+        self.next_id += 1;
+
+        if self.tabs[self.active].is_blank() {
+            self.tabs[self.active] = tab;
+        } else {
+            self.tabs.push(tab);
+            self.active = self.tabs.len() - 1;
+        }
+        self.assist
+            .reload(&self.tabs[self.active].content, self.instruction_set);
+    }
+
+    // Called once a save has finished, with the path written to or None if it was cancelled or failed
+    pub fn saved(&mut self, id: usize, path: Option<String>) {
+        let closing = self.closing.take();
+
+        let Some(path) = path else {
+            return;
+        };
+
+        if let Some(index) = self.tabs.iter().position(|tab| tab.id == id) {
+            self.tabs[index].path = Some(path);
+            self.tabs[index].dirty = false;
+
+            if closing == Some(id) {
+                self.close(index);
+            }
+        }
+    }
+
+    // Save the tab at a position, only asking where to save it if it has never been saved or save as was clicked
+    fn save(&self, index: usize, ask: bool) -> Event {
+        let tab = &self.tabs[index];
+        Event::SaveLMC(
+            tab.id,
+            if ask { None } else { tab.path.clone() },
+            tab.content.text(),
+        )
+    }
+
+    fn select(&mut self, index: usize) {
+        self.active = index;
+        self.assist
+            .reload(&self.tabs[self.active].content, self.instruction_set);
+    }
+
+    // Close the tab at a position without asking, leaving a new program if it was the last one
+    fn close(&mut self, index: usize) {
+        self.tabs.remove(index);
+
+        if self.tabs.is_empty() {
+            self.tabs.push(Tab::new(self.next_id, "", None));
+            self.next_id += 1;
+        }
+
+        // Keep the same tab shown unless it was the one closed
+        if self.active > index || self.active == self.tabs.len() {
+            self.active = self.active.saturating_sub(1);
+        }
+        self.assist
+            .reload(&self.tabs[self.active].content, self.instruction_set);
+    }
+
+    // The files open in each tab, the cursor in each, and how the panes are arranged, to be saved when the sandbox is left
+    pub fn session(&self) -> SandboxSession {
+        let files = self
+            .tabs
+            .iter()
+            .filter_map(|tab| {
+                let position = tab.content.cursor().position;
+                Some(OpenFile {
+                    path: tab.path.clone()?,
+                    line: position.line,
+                    column: position.column,
+                })
+            })
+            .collect();
+
+        // Tabs without a file aren't saved, so count the active tab's position among the ones that are
+        let active = self.tabs[..self.active]
+            .iter()
+            .filter(|tab| tab.path.is_some())
+            .count();
+
+        SandboxSession {
+            files,
+            active,
+            layout: layout(&self.panes, self.panes.layout()),
+        }
+    }
+
+    // Tabs with unsaved changes, to be autosaved in case the application crashes
+    pub fn buffers(&self) -> Vec<Buffer> {
+        self.tabs
+            .iter()
+            .filter(|tab| tab.dirty)
+            .map(|tab| Buffer {
+                name: tab.name(),
+                path: tab.path.clone(),
+                text: tab.content.text(),
+            })
+            .collect()
+    }
+
+    // Open autosaved buffers in new tabs, still marked as unsaved as they may differ from their files
+    pub fn recover(&mut self, buffers: &[Buffer]) {
+        for buffer in buffers {
+            let mut tab = Tab::new(self.next_id, &buffer.text, buffer.path.clone());
+            tab.dirty = true;
+            self.next_id += 1;
+
+            if self.tabs[self.active].is_blank() {
+                self.tabs[self.active] = tab;
+            } else {
+                self.tabs.push(tab);
+                self.active = self.tabs.len() - 1;
+            }
+        }
+        self.assist
+            .reload(&self.tabs[self.active].content, self.instruction_set);
+    }
+
+    // Reopen the files and layout of a saved session, files that have been moved or deleted since are skipped
+    pub fn restore(&mut self, session: &SandboxSession) {
+        self.panes = pane_grid::State::with_configuration(configuration(&session.layout));
+
+        for file in &session.files {
+            if let Ok(text) = fs::read_to_string(&file.path) {
+                self.open(file.path.clone(), &text);
+                self.tabs[self.active].content.move_to(text_editor::Cursor {
+                    position: text_editor::Position {
+                        line: file.line,
+                        column: file.column,
+                    },
+                    selection: None,
+                });
+            }
+        }
+
+        // Only pick the tab if every file could be opened, as otherwise the positions have shifted
+        if self.tabs.len() == session.files.len() && session.active < self.tabs.len() {
+            self.select(session.active);
+        }
+    }
+}
+
+// Copy pane_grid's layout into one that can be saved
+fn layout(panes: &pane_grid::State<Pane>, node: &pane_grid::Node) -> Layout {
+    match node {
+        pane_grid::Node::Split {
+            axis, ratio, a, b, ..
+        } => Layout::Split {
+            vertical: *axis == pane_grid::Axis::Vertical,
+            ratio: *ratio,
+            a: Box::new(layout(panes, a)),
+            b: Box::new(layout(panes, b)),
+        },
+        // Every pane in the layout is in the state, so this never falls back to the editor
+        pane_grid::Node::Pane(pane) => {
+            Layout::Pane(panes.get(*pane).cloned().unwrap_or(Pane::Editor))
         }
     }
 }
 
+// Ditto layout comment but the other way round
+fn configuration(layout: &Layout) -> pane_grid::Configuration<Pane> {
+    match layout {
+        Layout::Split {
+            vertical,
+            ratio,
+            a,
+            b,
+        } => pane_grid::Configuration::Split {
+            axis: if *vertical {
+                pane_grid::Axis::Vertical
+            } else {
+                pane_grid::Axis::Horizontal
+            },
+            ratio: *ratio,
+            a: Box::new(configuration(a)),
+            b: Box::new(configuration(b)),
+        },
+        Layout::Pane(pane) => pane_grid::Configuration::Pane(pane.clone()),
+    }
+}
+
 impl State {
+    // What a keyboard shortcut does on this screen (see src/frontend/util/shortcuts.rs), a prompt takes enter (its first button) and escape (cancel) while it is open
+    pub fn shortcut(&self, shortcut: Shortcut) -> Option<Message> {
+        if let Some(prompt) = self.prompt {
+            return match (shortcut, prompt) {
+                (Shortcut::Activate, Prompt::Close(_)) => Some(Message::PromptSaveClicked),
+                (Shortcut::Activate, Prompt::Leave) => Some(Message::PromptDiscardClicked),
+                (Shortcut::Back, _) => Some(Message::PromptCancelClicked),
+                _ => None,
+            };
+        }
+
+        Some(match shortcut {
+            Shortcut::Save => Message::Editor(editor::Message::SaveClicked),
+            Shortcut::Assemble => Message::Editor(editor::Message::AssembleClicked),
+            Shortcut::Run => Message::Editor(editor::Message::RunClicked),
+            Shortcut::Step => Message::Editor(editor::Message::StepClicked),
+            Shortcut::Stop => Message::Editor(editor::Message::StopClicked),
+            Shortcut::Reset => Message::Editor(editor::Message::ResetClicked),
+            Shortcut::NextPane => Message::PaneCycled(true),
+            Shortcut::PreviousPane => Message::PaneCycled(false),
+            Shortcut::ToggleSummary => Message::StateViewer(state_viewer::Message::SummaryToggled(
+                !self.memory_view.summary,
+            )),
+            Shortcut::Back => Message::BackClicked,
+            _ => return None,
+        })
+    }
+
     pub fn update(&mut self, message: Message) -> Option<Event> {
         match message {
             // Pane interactivity boilerplate
             Message::PaneClicked(pane) => {
                 self.pane_focused = Some(pane);
             }
+            // Give keyboard focus to what can be typed into in the pane, so the keyboard can be used without clicking
+            Message::PaneCycled(forward) => {
+                self.pane_focused = pane::cycle(&self.panes, self.pane_focused, forward);
+
+                if let Some(pane) = self.pane_focused.and_then(|pane| self.panes.get(pane)) {
+                    return Some(Event::Focus(pane.widget()));
+                }
+            }
             Message::PaneDragged(pane_grid::DragEvent::Dropped { pane, target }) => {
                 self.panes.drop(pane, target);
             }
@@ -124,7 +500,24 @@ impl State {
             }
 
             Message::Editor(message) => match message {
-                editor::Message::ContentChanged(action) => self.content.perform(action),
+                editor::Message::ContentChanged(action) => {
+                    let tab = &mut self.tabs[self.active];
+                    tab.dirty |= action.is_edit();
+                    self.assist
+                        .perform(&mut tab.content, action, self.instruction_set)
+                }
+                editor::Message::SuggestionPicked(index) => {
+                    let tab = &mut self.tabs[self.active];
+                    tab.dirty = true;
+                    self.assist
+                        .accept(&mut tab.content, index, self.instruction_set)
+                }
+                editor::Message::SuggestionsDismissed => self.assist.dismiss(),
+                editor::Message::OutlineClicked(label) => self.assist.go_to_definition(
+                    &mut self.tabs[self.active].content,
+                    &label,
+                    self.instruction_set,
+                ),
                 editor::Message::InputChanged(input) => self.input = input,
                 editor::Message::InputSubmitted => {
                     return Some(Event::SubmitInput(self.input.clone()));
@@ -132,43 +525,142 @@ impl State {
 
                 editor::Message::AssembleClicked => {
                     self.error = String::new();
+                    self.labels =
+                        state_viewer::labels(&self.content().text(), self.instruction_set);
 
                     // Send a message to the sender to compile the program with the source code in the text editor
-                    if let Ok(mut sender) = self.sender.lock() {
-                        sender
-                            .try_send(Input::AssembleClicked(self.content.text()))
-                            .unwrap()
+                    runtime::send(
+                        &self.sender,
+                        Input::AssembleClicked(self.content().text(), self.instruction_set),
+                    );
+                }
+
+                editor::Message::FormatClicked => {
+                    // Replace the text in the editor with the formatted text, or show why the source code couldn't be formatted
+                    match formatter::format(
+                        &self.content().text(),
+                        self.instruction_set,
+                        self.format_aliases,
+                    ) {
+                        Ok(formatted) => {
+                            self.error = String::new();
+                            let tab = &mut self.tabs[self.active];
+                            if formatted != tab.content.text() {
+                                tab.content = text_editor::Content::with_text(&formatted);
+                                tab.dirty = true;
+                            }
+                            self.assist
+                                .reload(&self.tabs[self.active].content, self.instruction_set);
+                        }
+                        Err(e) => self.error = e.to_string(),
                     }
                 }
 
-                editor::Message::OpenClicked => return Some(Event::OpenLMC(self.clone())),
-                editor::Message::SaveClicked => return Some(Event::SaveLMC(self.clone())),
+                editor::Message::OpenClicked => return Some(Event::OpenLMC),
+                editor::Message::SaveClicked => return Some(self.save(self.active, false)),
+                editor::Message::SaveAsClicked => return Some(self.save(self.active, true)),
+                editor::Message::ExportTraceClicked => return Some(Event::ExportTrace),
 
                 editor::Message::ResetClicked => return Some(Event::Reset),
+                editor::Message::StepClicked => return Some(Event::Step),
                 editor::Message::StopClicked => return Some(Event::Stop),
                 editor::Message::RunClicked => return Some(Event::Run),
             },
 
+            Message::StateViewer(message) => match message {
+                state_viewer::Message::MicroStepsToggled(micro_steps) => {
+                    self.micro_steps = micro_steps
+                }
+                state_viewer::Message::MemoryFormatSelected(format) => {
+                    self.memory_view.format = format
+                }
+                state_viewer::Message::TableToggled(table) => self.memory_view.table = table,
+                state_viewer::Message::SummaryToggled(summary) => {
+                    self.memory_view.summary = summary
+                }
+                state_viewer::Message::EditChanged(cell, value) => {
+                    self.editing = Some(state_viewer::Edit { cell, value })
+                }
+                // Only whole numbers can be put into the computer, anything else is left to be corrected
+                state_viewer::Message::EditSubmitted => {
+                    if let Some(edit) = &self.editing
+                        && let Ok(value) = edit.value.trim().parse()
+                    {
+                        let cell = edit.cell;
+                        self.editing = None;
+                        return Some(Event::Poke(cell, value));
+                    }
+                }
+            },
+
+            Message::TabSelected(index) => self.select(index),
+            Message::NewTabClicked => {
+                self.tabs.push(Tab::new(self.next_id, "", None));
+                self.next_id += 1;
+                self.select(self.tabs.len() - 1);
+            }
+            // Ask before throwing away unsaved changes
+            Message::TabCloseClicked(index) => {
+                if self.tabs[index].dirty {
+                    self.prompt = Some(Prompt::Close(self.tabs[index].id));
+                } else {
+                    self.close(index);
+                }
+            }
+
+            Message::PromptSaveClicked => {
+                if let Some(Prompt::Close(id)) = self.prompt.take()
+                    && let Some(index) = self.tabs.iter().position(|tab| tab.id == id)
+                {
+                    // The tab is closed by saved once the file has been written
+                    self.closing = Some(id);
+                    return Some(self.save(index, false));
+                }
+            }
+            Message::PromptDiscardClicked => match self.prompt.take() {
+                Some(Prompt::Close(id)) => {
+                    if let Some(index) = self.tabs.iter().position(|tab| tab.id == id) {
+                        self.close(index);
+                    }
+                }
+                Some(Prompt::Leave) => return Some(Event::ToMenu),
+                None => {}
+            },
+            Message::PromptCancelClicked => self.prompt = None,
+
             Message::SettingsClicked => return Some(Event::ToSettings),
-            Message::BackClicked => return Some(Event::ToMenu),
+            Message::BackClicked => {
+                if self.tabs.iter().any(|tab| tab.dirty) {
+                    self.prompt = Some(Prompt::Leave);
+                } else {
+                    return Some(Event::ToMenu);
+                }
+            }
 
             _ => {}
         }
         None
     }
 
-    pub fn view(&self) -> Element<'_, Message> {
+    pub fn view<'a>(
+        &'a self,
+        running: bool,
+        settings: bool,
+        colours: EditorColours,
+        keymap: &'a Keymap,
+    ) -> Element<'a, Message> {
         column![
             container(
                 pane_grid(&self.panes, |pane, state, _is_maximized| {
                     let focused = self.pane_focused == Some(pane);
 
-                    // Convert Pane to static string
-                    let title = match state {
-                        Pane::Editor => "Editor",
-                        Pane::StateViewer => "State Viewer",
-                        Pane::Terminal => "Terminal",
-                    };
+                    // Convert Pane to its title
+                    let title = tr(match state {
+                        Pane::Editor => "pane-editor",
+                        Pane::StateViewer => "pane-state-viewer",
+                        Pane::Terminal => "pane-terminal",
+                        Pane::Reference => "pane-reference",
+                    });
 
                     // Add title to title bar
                     let title_bar = pane_grid::TitleBar::new(
@@ -184,16 +676,47 @@ impl State {
                         // Use pane widgets to display content, passing in relevant values
 
                         // Pass in the input attribute as a Some value to tell the pane widget to show an input box and open and save buttons
-                        Pane::Editor => editor(&self.content, self.text_size, Some(&self.input))
-                            .map(Message::Editor),
+                        Pane::Editor => column![
+                            self.tab_bar(),
+                            editor(
+                                self.content(),
+                                self.text_size,
+                                &self.warnings,
+                                &self.assist,
+                                Some(&self.input),
+                                highlighter::Settings {
+                                    colours,
+                                    instruction_set: self.instruction_set,
+                                },
+                                keymap,
+                            )
+                            .map(Message::Editor)
+                        ]
+                        .spacing(4)
+                        .into(),
 
                         Pane::StateViewer => {
-                            state_viewer(&self.computer.lock().unwrap()).map(Message::StateViewer)
+                            // Only allow editing while the program is paused
+                            state_viewer(
+                                &self.computer.lock().unwrap(),
+                                self.micro_steps,
+                                (!running).then_some(self.editing.as_ref()),
+                                self.memory_view,
+                                &self.labels,
+                            )
+                            .map(Message::StateViewer)
                         }
 
-                        Pane::Terminal => {
-                            terminal(&self.output, &self.error).map(Message::Terminal)
-                        }
+                        Pane::Terminal => terminal(&self.output, &self.warnings, &self.error)
+                            .map(Message::Terminal),
+
+                        // Show help for the instruction under the cursor in the editor
+                        Pane::Reference => reference(
+                            self.instruction_set,
+                            &editor::Assist::word(self.content()),
+                            self.text_size,
+                        )
+                        .map(Message::Reference),
                     })
                     .style(if focused {
                         style::grid_pane_focused
@@ -210,13 +733,202 @@ impl State {
                 .on_resize(10, Message::PaneResized)
             )
             .padding([8, 0]),
+            match self.prompt {
+                Some(prompt) => self.prompt(prompt),
+                None => row![
+                    button(text(tr("back"))).on_press(Message::BackClicked),
+                    space::horizontal(),
+                    settings
+                        .then(|| button(text(tr("settings"))).on_press(Message::SettingsClicked)),
+                ]
+                .into(),
+            }
+        ]
+        .padding(12)
+        .into()
+    }
+
+    // A button for each open program (marked with a * if it has unsaved changes) with a button to close it, and a button to start a new program
+    fn tab_bar(&self) -> Element<'_, Message> {
+        row(self.tabs.iter().enumerate().map(|(i, tab)| {
             row![
-                button("Back").on_press(Message::BackClicked),
-                space::horizontal(),
-                button("Settings").on_press(Message::SettingsClicked),
+                button(text(if tab.dirty {
+                    format!("{}*", tab.name())
+                } else {
+                    tab.name()
+                }))
+                .style(if i == self.active {
+                    button::primary
+                } else {
+                    button::secondary
+                })
+                .padding([2, 8])
+                .on_press(Message::TabSelected(i)),
+                button("x")
+                    .style(button::text)
+                    .padding([2, 6])
+                    .on_press(Message::TabCloseClicked(i)),
             ]
+            .into()
+        }))
+        .push(
+            button("+")
+                .style(button::text)
+                .padding([2, 6])
+                .on_press(Message::NewTabClicked),
+        )
+        .spacing(4)
+        .wrap()
+        .into()
+    }
+
+    // Ask whether to save changes before closing a tab, or whether to throw them away before leaving the sandbox
+    fn prompt(&self, prompt: Prompt) -> Element<'_, Message> {
+        let (question, save) = match prompt {
+            Prompt::Close(id) => (
+                tr_with(
+                    "sandbox-save-changes",
+                    &[(
+                        "name",
+                        &self
+                            .tabs
+                            .iter()
+                            .find(|tab| tab.id == id)
+                            .map(Tab::name)
+                            .unwrap_or_default(),
+                    )],
+                ),
+                true,
+            ),
+            Prompt::Leave => (tr("sandbox-leave-unsaved"), false),
+        };
+
+        row![
+            text(question),
+            space::horizontal(),
+            save.then(|| button(text(tr("save"))).on_press(Message::PromptSaveClicked)),
+            button(text(tr(if save {
+                "sandbox-dont-save"
+            } else {
+                "sandbox-leave"
+            })))
+            .style(button::danger)
+            .on_press(Message::PromptDiscardClicked),
+            button(text(tr("cancel")))
+                .style(button::secondary)
+                .on_press(Message::PromptCancelClicked),
         ]
-        .padding(12)
+        .spacing(4)
+        .align_y(iced::Center)
         .into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use iced::futures::channel::mpsc;
+
+    use crate::backend::compiler::formatter::AliasStyle;
+    use crate::frontend::screen::sandbox::{Event, Message, Prompt, State};
+    use crate::frontend::util::shortcuts::Shortcut;
+    use crate::shared::{isa::InstructionSet, vm::Computer};
+
+    // A sandbox that isn't connected to a runtime
+    fn sandbox() -> State {
+        let (sender, _) = mpsc::channel(1);
+
+        State::new(
+            Arc::new(Mutex::new(Computer::default())),
+            Arc::new(Mutex::new(sender)),
+            16,
+            InstructionSet::Classic,
+            AliasStyle::default(),
+        )
+    }
+
+    // The path of every tab, in order
+    fn paths(state: &State) -> Vec<Option<&str>> {
+        state.tabs.iter().map(|tab| tab.path.as_deref()).collect()
+    }
+
+    // 15.1
+    #[test]
+    fn sandbox_tabs() {
+        let mut state = sandbox();
+
+        // Opening a file replaces the blank program, and opening it again switches to it
+        state.open("a.lmc".into(), "HLT");
+        state.open("b.lmc".into(), "OUT");
+        assert_eq!(paths(&state), vec![Some("a.lmc"), Some("b.lmc")]);
+        assert_eq!(state.active, 1);
+
+        state.open("a.lmc".into(), "HLT");
+        assert_eq!(state.active, 0);
+
+        // Closing a tab before the one shown keeps the same program shown
+        state.update(Message::TabSelected(1));
+        state.update(Message::TabCloseClicked(0));
+        assert_eq!(paths(&state), vec![Some("b.lmc")]);
+        assert_eq!(state.active, 0);
+
+        // Closing the last tab leaves a new program
+        state.update(Message::TabCloseClicked(0));
+        assert_eq!(paths(&state), vec![None]);
+        assert!(state.prompt.is_none());
+    }
+
+    // 15.2
+    #[test]
+    fn sandbox_prompts() {
+        let mut state = sandbox();
+        state.open("a.lmc".into(), "HLT");
+        state.tabs[0].dirty = true;
+        let id = state.tabs[0].id;
+
+        // Closing a tab with unsaved changes asks first, and enter saves it before closing it
+        state.update(Message::TabCloseClicked(0));
+        assert_eq!(state.prompt, Some(Prompt::Close(id)));
+        assert!(matches!(
+            state.shortcut(Shortcut::Activate),
+            Some(Message::PromptSaveClicked)
+        ));
+        assert!(matches!(
+            state.update(Message::PromptSaveClicked),
+            Some(Event::SaveLMC(saved, Some(path), _)) if saved == id && path == "a.lmc"
+        ));
+
+        state.saved(id, Some("a.lmc".into()));
+        assert_eq!(paths(&state), vec![None]);
+
+        // Leaving with unsaved changes asks first, enter leaves and escape stays
+        state.tabs[0].dirty = true;
+        state.update(Message::BackClicked);
+        assert_eq!(state.prompt, Some(Prompt::Leave));
+        assert!(matches!(
+            state.shortcut(Shortcut::Activate),
+            Some(Message::PromptDiscardClicked)
+        ));
+        assert!(matches!(
+            state.shortcut(Shortcut::Back),
+            Some(Message::PromptCancelClicked)
+        ));
+        assert!(matches!(
+            state.update(Message::PromptDiscardClicked),
+            Some(Event::ToMenu)
+        ));
+    }
+    // 15.3
+    #[test]
+    fn sandbox_summary() {
+        let mut state = sandbox();
+
+        // The shortcut switches between the RAM grid and the text summary
+        for summary in [true, false] {
+            let message = state.shortcut(Shortcut::ToggleSummary).unwrap();
+            state.update(message);
+            assert_eq!(state.memory_view.summary, summary);
+        }
+    }
+}
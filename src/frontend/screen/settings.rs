@@ -1,31 +1,97 @@
-use std::env;
-use std::path::PathBuf;
+use std::{collections::BTreeMap, path::PathBuf};
 
-use crate::backend::config::{self, Config, RunSpeed};
+use crate::backend::compiler::formatter::AliasStyle;
+use crate::backend::config::{Config, RunSpeed};
+use crate::backend::keymap::{Binding, Command, Keymap};
 use crate::frontend::screen::Screen;
-use crate::frontend::util::{font::Font, theme::Theme, widgets::separator};
+use crate::frontend::util::{
+    font::Font,
+    highlighter,
+    shortcuts::Shortcut,
+    theme::{CustomTheme, Theme},
+    widgets::{
+        focus::{Focus, ring},
+        separator,
+    },
+};
+use crate::shared::isa::InstructionSet;
+use crate::shared::locale::{Language, tr, tr_with};
 
 use iced::{
     Alignment, Element, Length,
-    widget::{button, column, pick_list, radio, row, space, text, text_input},
+    widget::{
+        Text, button, column, container, pick_list, radio, rich_text, row, scrollable, space, span,
+        text, text_input, themer,
+    },
 };
 use iced_aw::widgets::number_input;
 
 use rfd::AsyncFileDialog;
 
-// Opens a file dialog selector specific to the operating system and gets the directory the user picks, closing the dialog returns the value from the config
-pub async fn browse_directory() -> String {
-    let mut config_path = env::home_dir().unwrap();
-    config_path.push(config::CONFIG_PATH);
+// The program shown in the theme preview, using every kind of token the editor colours
+const PREVIEW_PROGRAM: &str = "// Add two numbers
+        INP
+        STA first
+        INP
+        ADD first
+        OUT
+        HLT
+first   DAT 0";
+
+// The settings and buttons that can be given keyboard focus, in the order they are shown
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Item {
+    Theme,
+    Language,
+    FontSize,
+    // The browse button, as the text input takes the keyboard itself
+    LessonsDirectory,
+    RunSpeed,
+    InstructionSet,
+    FormatAliases,
+    // The button putting every shortcut back to its default, as the binding text inputs take the keyboard themselves
+    ResetKeymap,
+    Back,
+    Save,
+}
+
+const ITEMS: [Item; 10] = [
+    Item::Theme,
+    Item::Language,
+    Item::FontSize,
+    Item::LessonsDirectory,
+    Item::RunSpeed,
+    Item::InstructionSet,
+    Item::FormatAliases,
+    Item::ResetKeymap,
+    Item::Back,
+    Item::Save,
+];
+
+// The value after (or before) the current one in a list of values, wrapping around at either end
+fn cycle<T: PartialEq + Clone>(values: &[T], current: &T, forward: bool) -> T {
+    let count = values.len();
+    let i = values
+        .iter()
+        .position(|value| value == current)
+        .unwrap_or(0);
 
-    let config = Config::try_from(config_path).unwrap_or_default();
+    values[if forward {
+        (i + 1) % count
+    } else {
+        (i + count - 1) % count
+    }]
+    .clone()
+}
 
+// Opens a file dialog selector specific to the operating system and gets the directory the user picks, closing the dialog returns the directory it was before
+pub async fn browse_directory(lessons_directory: String) -> String {
     AsyncFileDialog::new()
-        .set_title("Pick lessons directory...")
+        .set_title(tr("settings-pick-lessons-directory"))
         .pick_folder()
         .await
         // If the user exits out, restore the previous value from the config
-        .unwrap_or(PathBuf::from(&config.lessons_directory).into())
+        .unwrap_or(PathBuf::from(&lessons_directory).into())
         .path()
         .to_str()
         .to_owned()
@@ -39,6 +105,8 @@ pub async fn browse_directory() -> String {
 pub enum Message {
     // Theme picked from drop-down menu
     ThemeSelected(Theme),
+    // Language picked from drop-down menu
+    LanguageSelected(Language),
     // Editor font size changed via buttons/text input
     EditorFontSizeChanged(u32),
     // Lessons directory changed via typing
@@ -47,12 +115,39 @@ pub enum Message {
     BrowseClicked,
     // Run speed selected from radio list
     RunSpeedSelected(RunSpeed),
+    // Instruction set picked from drop-down menu
+    InstructionSetSelected(InstructionSet),
+    // Mnemonics used by the formatter picked from drop-down menu
+    FormatAliasesSelected(AliasStyle),
+    // The binding of a keyboard shortcut changed via typing (e.g. "Ctrl+S")
+    BindingChanged(Command, String),
+    // Reset Shortcuts button clicked to put every binding back to its default
+    KeymapReset,
     // Back button clicked to go back (to previous screen, i.e. menu, lesson viewer, sandbox)
     BackClicked,
     // Save clicked (to set config in Algor struct and serialise changes and write to disk, using backend)
     SaveClicked,
 }
 
+impl Message {
+    // The config key the message changes, if any (used to ignore changes to keys locked by the system config)
+    fn key(&self) -> Option<&'static str> {
+        match self {
+            Message::ThemeSelected(_) => Some("theme"),
+            Message::LanguageSelected(_) => Some("language"),
+            Message::EditorFontSizeChanged(_) => Some("editor_font_size"),
+            Message::LessonsDirectoryChanged(_) | Message::BrowseClicked => {
+                Some("lessons_directory")
+            }
+            Message::RunSpeedSelected(_) => Some("run_speed"),
+            Message::InstructionSetSelected(_) => Some("instruction_set"),
+            Message::FormatAliasesSelected(_) => Some("format_aliases"),
+            Message::BindingChanged(..) | Message::KeymapReset => Some("keymap"),
+            Message::BackClicked | Message::SaveClicked => None,
+        }
+    }
+}
+
 // Outputs from this state's update method, used as inputs for Screen enum's update method
 pub enum Event {
     // Result from clicking "Back", providing the screen with the previous state
@@ -67,34 +162,263 @@ pub enum Event {
 #[derive(Debug, Clone)]
 pub struct State {
     pub theme: Theme,
+    pub language: Language,
     pub editor_font_size: u32,
     pub lessons_directory: String,
     pub run_speed: Option<RunSpeed>,
+    pub instruction_set: InstructionSet,
+    pub format_aliases: AliasStyle,
+    // The bindings that can be used, a binding being typed that isn't a key yet leaves the last one that was
+    pub keymap: Keymap,
+    // The text typed for each binding, so mistakes can be shown and fixed rather than thrown away
+    bindings: BTreeMap<Command, String>,
+    // Config keys the system config doesn't let the user change (see Policy in src/backend/config.rs)
+    pub locked: Vec<String>,
+    // Themes loaded from the themes directory, listed after the built-in themes
+    pub themes: Vec<CustomTheme>,
     // Allows for restoring the screen from which the user came from
     pub last_screen: Box<Screen>,
+    // The setting or button that has keyboard focus
+    focus: Focus,
 }
 
 // Helper associated functions for keep main.rs clean
 impl State {
     // Create a settings menu state from a Config and the previous screen
-    pub fn new(value: Config, last_screen: Box<Screen>) -> Self {
+    pub fn new(
+        value: Config,
+        locked: Vec<String>,
+        themes: Vec<CustomTheme>,
+        last_screen: Box<Screen>,
+    ) -> Self {
         Self {
             theme: value.theme,
+            language: value.language,
             editor_font_size: value.editor_font_size,
             lessons_directory: value.lessons_directory,
             run_speed: Some(value.run_speed),
+            instruction_set: value.instruction_set,
+            format_aliases: value.format_aliases,
+            bindings: Self::bindings(&value.keymap),
+            keymap: value.keymap,
+            locked,
+            themes,
             last_screen,
+            focus: Focus::default(),
+        }
+    }
+
+    // The text of every binding in a keymap
+    fn bindings(keymap: &Keymap) -> BTreeMap<Command, String> {
+        Command::ALL
+            .into_iter()
+            .map(|command| (command, keymap.get(command).to_string()))
+            .collect()
+    }
+
+    // Why the binding typed for a command can't be used, if it can't (i.e. it isn't a key or another command or the editor already uses it)
+    fn binding_error(&self, command: Command) -> Option<String> {
+        match Binding::try_from(self.bindings[&command].as_str()) {
+            Err(e) => Some(e.to_string()),
+            Ok(_) => self.keymap.conflict(command).map(|e| e.to_string()),
+        }
+    }
+
+    // The config can only be saved when every binding can be used
+    fn can_save(&self) -> bool {
+        Command::ALL
+            .into_iter()
+            .all(|command| self.binding_error(command).is_none())
+    }
+
+    // Whether a setting or button has keyboard focus
+    fn focused(&self, item: Item) -> bool {
+        self.focus.get().is_some_and(|i| ITEMS[i] == item)
+    }
+
+    // The message changing the setting with keyboard focus to its next (or previous) value, sent through update so locked settings stay as they are
+    fn change(&self, item: Item, forward: bool) -> Option<Message> {
+        Some(match item {
+            Item::Theme => {
+                Message::ThemeSelected(cycle(&Theme::all(&self.themes), &self.theme, forward))
+            }
+            Item::Language => {
+                Message::LanguageSelected(cycle(&Language::ALL, &self.language, forward))
+            }
+            Item::FontSize => Message::EditorFontSizeChanged(if forward {
+                (self.editor_font_size + 2).min(32)
+            } else {
+                self.editor_font_size.saturating_sub(2).max(8)
+            }),
+            Item::RunSpeed => Message::RunSpeedSelected(cycle(
+                &[
+                    RunSpeed::Slow,
+                    RunSpeed::Medium,
+                    RunSpeed::Fast,
+                    RunSpeed::Instant,
+                ],
+                &self.run_speed.unwrap_or_default(),
+                forward,
+            )),
+            Item::InstructionSet => Message::InstructionSetSelected(cycle(
+                InstructionSet::ALL,
+                &self.instruction_set,
+                forward,
+            )),
+            Item::FormatAliases => Message::FormatAliasesSelected(cycle(
+                AliasStyle::ALL,
+                &self.format_aliases,
+                forward,
+            )),
+            Item::LessonsDirectory | Item::ResetKeymap | Item::Back | Item::Save => return None,
+        })
+    }
+
+    // Tab and the arrow keys move between settings, the left and right arrows change the one with focus, and enter presses buttons (or changes the setting like the right arrow)
+    pub fn shortcut(&mut self, shortcut: Shortcut) -> Option<Message> {
+        match shortcut {
+            Shortcut::FocusNext => self.focus.next(ITEMS.len()),
+            Shortcut::FocusPrevious => self.focus.previous(ITEMS.len()),
+            Shortcut::Increase => return self.change(ITEMS[self.focus.get()?], true),
+            Shortcut::Decrease => return self.change(ITEMS[self.focus.get()?], false),
+            Shortcut::Activate => {
+                return match ITEMS[self.focus.get()?] {
+                    Item::LessonsDirectory => Some(Message::BrowseClicked),
+                    Item::ResetKeymap => Some(Message::KeymapReset),
+                    Item::Back => Some(Message::BackClicked),
+                    Item::Save => Some(Message::SaveClicked),
+                    item => self.change(item, true),
+                };
+            }
+            Shortcut::Back => return Some(Message::BackClicked),
+            _ => {}
+        }
+
+        None
+    }
+
+    fn is_locked(&self, key: &str) -> bool {
+        self.locked.iter().any(|locked| locked == key)
+    }
+
+    // The theme picked (before it is saved) applied to a few widgets and a highlighted program, so it can be tried out without leaving the settings screen
+    fn preview(&self) -> Element<'_, Message> {
+        let colours = self.theme.editor_colours(&self.themes);
+        let settings = highlighter::Settings {
+            colours,
+            instruction_set: self.instruction_set,
+        };
+
+        // Split each line into coloured tokens and the uncoloured text between them
+        let mut spans = Vec::new();
+        for line in PREVIEW_PROGRAM.lines() {
+            let mut end = 0;
+
+            for (range, colour) in highlighter::highlight(line, &settings) {
+                spans.push(span(&line[end..range.start]));
+                spans.push(span(&line[range.clone()]).color(colour));
+                end = range.end;
+            }
+
+            spans.push(span(&line[end..]));
+            spans.push(span("\n"));
+        }
+
+        themer(
+            Some(self.theme.to_iced(&self.themes)),
+            container(
+                column![
+                    text(tr("settings-preview")).font(Font::Bold).size(16),
+                    rich_text::<(), _, _, _>(spans).size(self.editor_font_size),
+                    row![
+                        button(text(tr("editor-run"))),
+                        button(text(tr("editor-stop"))).style(button::secondary),
+                        button(text(tr("editor-reset"))).style(button::danger),
+                    ]
+                    .spacing(4),
+                    text(tr("settings-preview-success")).style(text::success),
+                    text(tr("settings-preview-error")).style(text::danger),
+                ]
+                .spacing(8),
+            )
+            .style(container::bordered_box)
+            .padding(12)
+            .width(Length::Fill),
+        )
+        .text_color(|theme| theme.palette().text)
+        .background(|theme| theme.palette().background.into())
+        .into()
+    }
+
+    // Column with a text input for the binding of every keyboard shortcut, with why a binding can't be used under it
+    fn shortcuts(&self) -> Element<'_, Message> {
+        let locked = self.is_locked("keymap");
+
+        column![
+            text(tr("settings-keyboard-shortcuts"))
+                .font(Font::Bold)
+                .size(24),
+            self.label(&tr("settings-shortcuts"), "keymap"),
+            scrollable(
+                column(Command::ALL.into_iter().map(|command| {
+                    column![
+                        row![
+                            text(command.to_string()).width(Length::Fill),
+                            text_input("...", &self.bindings[&command])
+                                .on_input_maybe((!locked).then_some(move |binding| {
+                                    Message::BindingChanged(command, binding)
+                                }))
+                                .width(Length::Fixed(160f32))
+                        ]
+                        .spacing(8)
+                        .align_y(Alignment::Center),
+                        self.binding_error(command)
+                            .map(|e| text(e).size(14).style(text::danger))
+                    ]
+                    .spacing(4)
+                    .into()
+                }))
+                .spacing(8)
+            )
+            .height(Length::Fill),
+            ring(
+                button(text(tr("settings-reset-shortcuts")))
+                    .style(button::secondary)
+                    .on_press_maybe((!locked).then_some(Message::KeymapReset)),
+                self.focused(Item::ResetKeymap)
+            )
+        ]
+        .width(Length::Fill)
+        .spacing(16)
+        .into()
+    }
+
+    // The text labelling a setting, marking the ones that can't be changed
+    fn label(&self, label: &str, key: &str) -> Text<'_> {
+        if self.is_locked(key) {
+            text(tr_with("settings-locked", &[("label", &label)])).size(16)
+        } else {
+            text(format!("{label}:")).size(16)
         }
     }
 }
 
 impl State {
     pub fn update(&mut self, message: Message) -> Option<Event> {
+        // Locked settings are shown but can't be changed
+        if message.key().is_some_and(|key| self.is_locked(key)) {
+            return None;
+        }
+
         match message {
             // When the user selects a theme, reflect it in the state
             Message::ThemeSelected(theme) => {
                 self.theme = theme;
             }
+            // Ditto for language, which changes once the config is saved
+            Message::LanguageSelected(language) => {
+                self.language = language;
+            }
             // Ditto for lessons directory
             Message::LessonsDirectoryChanged(directory) => {
                 self.lessons_directory = directory;
@@ -107,11 +431,32 @@ impl State {
             Message::RunSpeedSelected(speed) => {
                 self.run_speed = Some(speed);
             }
+            // Ditto for instruction set
+            Message::InstructionSetSelected(instruction_set) => {
+                self.instruction_set = instruction_set;
+            }
+            // Ditto for formatter mnemonics
+            Message::FormatAliasesSelected(aliases) => {
+                self.format_aliases = aliases;
+            }
+            // Keep the text of the binding, only changing the keymap once it is a key
+            Message::BindingChanged(command, text) => {
+                if let Ok(binding) = Binding::try_from(text.as_str()) {
+                    self.keymap.set(command, binding);
+                }
+                self.bindings.insert(command, text);
+            }
+            Message::KeymapReset => {
+                self.keymap = Keymap::default();
+                self.bindings = Self::bindings(&self.keymap);
+            }
 
             // When the user clicks the browse button, bubble it up to the Screen update method
             Message::BrowseClicked => return Some(Event::PickLessonsDirectory(self.clone())),
             // Ditto for the save button
-            Message::SaveClicked => return Some(Event::SetConfig(self.into())),
+            Message::SaveClicked if self.can_save() => return Some(Event::SetConfig(self.into())),
+            // Bindings that can't be used are shown next to the binding, so there is nothing more to do
+            Message::SaveClicked => {}
             // Ditto for back button
             Message::BackClicked => {
                 return Some(match &*self.last_screen {
@@ -119,12 +464,15 @@ impl State {
                     Screen::LessonView(screen_state) => {
                         let mut new_state = screen_state.clone();
                         new_state.text_size = self.editor_font_size;
+                        new_state.format_aliases = self.format_aliases;
 
                         Event::GoBack(Box::new(Screen::LessonView(new_state)))
                     }
                     Screen::Sandbox(screen_state) => {
                         let mut new_state = screen_state.clone();
                         new_state.text_size = self.editor_font_size;
+                        new_state.instruction_set = self.instruction_set;
+                        new_state.format_aliases = self.format_aliases;
 
                         Event::GoBack(Box::new(Screen::Sandbox(new_state)))
                     }
@@ -142,38 +490,62 @@ impl State {
     pub fn view(&self) -> Element<'_, Message> {
         column![
             // Title text
-            text("Settings").font(Font::Bold).size(32),
+            text(tr("settings")).font(Font::Bold).size(32),
             // Horizontal quad
             separator::horizontal(),
             row![
                 // Column with settings related to appearance
                 column![
-                    text("Appearance").font(Font::Bold).size(24),
+                    text(tr("settings-appearance")).font(Font::Bold).size(24),
                     // "Theme" text labelling a theme selector
                     column![
-                        text("Theme:").size(16),
-                        pick_list(Theme::ALL, Some(self.theme.clone()), |theme| {
-                            Message::ThemeSelected(theme)
-                        })
-                        .width(Length::Fill)
+                        self.label(&tr("settings-theme"), "theme"),
+                        ring(
+                            pick_list(
+                                Theme::all(&self.themes),
+                                Some(self.theme.clone()),
+                                |theme| { Message::ThemeSelected(theme) }
+                            )
+                            .width(Length::Fill),
+                            self.focused(Item::Theme)
+                        )
                     ]
                     .spacing(8),
-                    // "Font Size" text labelling a bounded number input from 8px to 32px
+                    // "Language" text labelling a language selector, each language is written in itself
                     column![
-                        text("Font Size:").align_y(Alignment::Center).size(16),
-                        row![
-                            number_input(&self.editor_font_size, 8..=32, |size| {
-                                Message::EditorFontSizeChanged(size)
-                            })
-                            .style(iced_aw::style::number_input::primary)
-                            .step(2)
-                            .font(Font::Regular.into())
+                        self.label(&tr("settings-language"), "language"),
+                        ring(
+                            pick_list(
+                                Language::ALL,
+                                Some(self.language),
+                                Message::LanguageSelected
+                            )
                             .width(Length::Fill),
-                            text("px").width(Length::Fill)
-                        ]
-                        .spacing(8)
+                            self.focused(Item::Language)
+                        )
                     ]
-                    .spacing(8)
+                    .spacing(8),
+                    // "Font Size" text labelling a bounded number input from 8px to 32px
+                    column![
+                        self.label(&tr("settings-font-size"), "editor_font_size")
+                            .align_y(Alignment::Center),
+                        ring(
+                            row![
+                                number_input(&self.editor_font_size, 8..=32, |size| {
+                                    Message::EditorFontSizeChanged(size)
+                                })
+                                .style(iced_aw::style::number_input::primary)
+                                .step(2)
+                                .font(Font::Regular.into())
+                                .width(Length::Fill),
+                                text("px").width(Length::Fill)
+                            ]
+                            .spacing(8),
+                            self.focused(Item::FontSize)
+                        )
+                    ]
+                    .spacing(8),
+                    self.preview()
                 ]
                 .width(Length::Fill)
                 .spacing(32),
@@ -181,60 +553,114 @@ impl State {
                 separator::vertical(),
                 // Column with settings related to functionality
                 column![
-                    text("Functionality").font(Font::Bold).size(24),
+                    text(tr("settings-functionality")).font(Font::Bold).size(24),
                     // "Lesson Directory" text labelling text input and browse button
                     column![
-                        text("Lessons Directory:").size(16),
+                        self.label(&tr("settings-lessons-directory"), "lessons_directory"),
+                        // Disabled rather than just ignored, so it is clear the directory can't be changed
                         row![
-                            text_input("...", &self.lessons_directory)
-                                .on_input(Message::LessonsDirectoryChanged),
-                            button("Browse").on_press(Message::BrowseClicked)
+                            text_input("...", &self.lessons_directory).on_input_maybe(
+                                (!self.is_locked("lessons_directory"))
+                                    .then_some(Message::LessonsDirectoryChanged)
+                            ),
+                            ring(
+                                button(text(tr("settings-browse"))).on_press_maybe(
+                                    (!self.is_locked("lessons_directory"))
+                                        .then_some(Message::BrowseClicked)
+                                ),
+                                self.focused(Item::LessonsDirectory)
+                            )
                         ]
                         .spacing(8)
                     ]
                     .spacing(8),
                     // "Run Speed" text labelling radio menu
                     column![
-                        text("Run Speed:").size(16),
-                        radio(
-                            "Slow",
-                            RunSpeed::Slow,
-                            self.run_speed,
-                            Message::RunSpeedSelected,
-                        ),
-                        radio(
-                            "Medium",
-                            RunSpeed::Medium,
-                            self.run_speed,
-                            Message::RunSpeedSelected,
-                        ),
-                        radio(
-                            "Fast",
-                            RunSpeed::Fast,
-                            self.run_speed,
-                            Message::RunSpeedSelected,
-                        ),
-                        radio(
-                            "Instant",
-                            RunSpeed::Instant,
-                            self.run_speed,
-                            Message::RunSpeedSelected,
+                        self.label(&tr("settings-run-speed"), "run_speed"),
+                        ring(
+                            column![
+                                radio(
+                                    tr("run-speed-slow"),
+                                    RunSpeed::Slow,
+                                    self.run_speed,
+                                    Message::RunSpeedSelected,
+                                ),
+                                radio(
+                                    tr("run-speed-medium"),
+                                    RunSpeed::Medium,
+                                    self.run_speed,
+                                    Message::RunSpeedSelected,
+                                ),
+                                radio(
+                                    tr("run-speed-fast"),
+                                    RunSpeed::Fast,
+                                    self.run_speed,
+                                    Message::RunSpeedSelected,
+                                ),
+                                radio(
+                                    tr("run-speed-instant"),
+                                    RunSpeed::Instant,
+                                    self.run_speed,
+                                    Message::RunSpeedSelected,
+                                )
+                            ]
+                            .spacing(8)
+                            .width(Length::Fill),
+                            self.focused(Item::RunSpeed)
                         ),
                     ]
+                    .spacing(8),
+                    // "Instruction Set" text labelling an instruction set selector (lessons pick their own)
+                    column![
+                        self.label(&tr("settings-instruction-set"), "instruction_set"),
+                        ring(
+                            pick_list(
+                                InstructionSet::ALL,
+                                Some(self.instruction_set),
+                                Message::InstructionSetSelected
+                            )
+                            .width(Length::Fill),
+                            self.focused(Item::InstructionSet)
+                        )
+                    ]
+                    .spacing(8),
+                    // "Format Mnemonics" text labelling a selector for which mnemonics the Format button uses
+                    column![
+                        self.label(&tr("settings-format-mnemonics"), "format_aliases"),
+                        ring(
+                            pick_list(
+                                AliasStyle::ALL,
+                                Some(self.format_aliases),
+                                Message::FormatAliasesSelected
+                            )
+                            .width(Length::Fill),
+                            self.focused(Item::FormatAliases)
+                        )
+                    ]
                     .spacing(8)
                 ]
                 .width(Length::Fill)
-                .spacing(32)
+                .spacing(32),
+                // Ditto
+                separator::vertical(),
+                self.shortcuts()
             ]
             .height(Length::Fill)
             .width(Length::Fill)
             .spacing(64),
             // Back and save buttons on opposite bottom corners
             row![
-                button("Back").on_press(Message::BackClicked),
+                ring(
+                    button(text(tr("back"))).on_press(Message::BackClicked),
+                    self.focused(Item::Back)
+                ),
                 // Invisible widget that takes up as much space as possible
                 space::horizontal(),
-                button("Save").on_press(Message::SaveClicked)
+                ring(
+                    button(text(tr("save")))
+                        .on_press_maybe(self.can_save().then_some(Message::SaveClicked)),
+                    self.focused(Item::Save)
+                )
             ]
         ]
         .height(Length::Fill)
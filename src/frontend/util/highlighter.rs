@@ -0,0 +1,85 @@
+use std::ops::Range;
+
+use iced::advanced::text::{self, highlighter::Format};
+use iced::{Color, Font};
+
+use crate::backend::compiler::lexer::{Lexer, Token};
+use crate::frontend::util::theme::EditorColours;
+use crate::shared::isa::InstructionSet;
+
+// What the editor's syntax highlighting depends on, the instruction set decides which words are mnemonics
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Settings {
+    pub colours: EditorColours,
+    pub instruction_set: InstructionSet,
+}
+
+// Colour each token of a line of LMC by what kind of token it is, lines that can't be lexed aren't coloured
+pub fn highlight(line: &str, settings: &Settings) -> Vec<(Range<usize>, Color)> {
+    let Ok(tokens) = Lexer::with_instruction_set(line, settings.instruction_set).lex_spanned()
+    else {
+        return Vec::new();
+    };
+
+    tokens
+        .into_iter()
+        .filter_map(|(token, span)| {
+            let colour = match token {
+                Token::Number(_) => settings.colours.number,
+                Token::Identifier(_) => settings.colours.label,
+                Token::Comment(_) => settings.colours.comment,
+                Token::Newline => return None,
+                // Every other token is a mnemonic or directive
+                _ => settings.colours.instruction,
+            };
+
+            Some((span, colour))
+        })
+        .collect()
+}
+
+// Highlights the editor with the lexer, every line is lexed on its own as tokens never go over more than one line
+pub struct Highlighter {
+    settings: Settings,
+    current_line: usize,
+}
+
+impl text::Highlighter for Highlighter {
+    type Settings = Settings;
+    type Highlight = Color;
+    type Iterator<'a> = std::vec::IntoIter<(Range<usize>, Color)>;
+
+    fn new(settings: &Self::Settings) -> Self {
+        Self {
+            settings: *settings,
+            current_line: 0,
+        }
+    }
+
+    // Every line has to be highlighted again when the theme or instruction set changes
+    fn update(&mut self, new_settings: &Self::Settings) {
+        self.settings = *new_settings;
+        self.current_line = 0;
+    }
+
+    fn change_line(&mut self, line: usize) {
+        self.current_line = self.current_line.min(line);
+    }
+
+    fn highlight_line(&mut self, line: &str) -> Self::Iterator<'_> {
+        self.current_line += 1;
+        highlight(line, &self.settings).into_iter()
+    }
+
+    fn current_line(&self) -> usize {
+        self.current_line
+    }
+}
+
+// Turn the colour of a token into the format the editor draws it with
+pub fn format(colour: &Color, _theme: &iced::Theme) -> Format<Font> {
+    Format {
+        color: Some(*colour),
+        font: None,
+    }
+}
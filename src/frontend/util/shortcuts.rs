@@ -0,0 +1,200 @@
+use iced::keyboard::{Key, Modifiers, key::Named};
+
+use crate::backend::keymap::{Binding, Command, Keymap};
+
+// Something that can be done from the keyboard, passed to the screen being shown (see Screen::shortcut in src/frontend/screen.rs)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shortcut {
+    Save,
+    Assemble,
+    Run,
+    // Run a single instruction (or part of the fetch-decode-execute cycle) while the program is stopped
+    Step,
+    Stop,
+    Reset,
+    NextSlide,
+    PreviousSlide,
+    // Move focus between the panes of the sandbox and lesson viewer
+    NextPane,
+    PreviousPane,
+    // Move between and press the buttons of the menu, lesson select, and settings screens
+    FocusNext,
+    FocusPrevious,
+    Activate,
+    // Change the setting that has focus on the settings screen
+    Increase,
+    Decrease,
+    Back,
+    // Show the machine state as text that can be read out instead of the RAM grid
+    ToggleSummary,
+}
+
+impl From<Command> for Shortcut {
+    fn from(value: Command) -> Self {
+        match value {
+            Command::Save => Shortcut::Save,
+            Command::Assemble => Shortcut::Assemble,
+            Command::Run => Shortcut::Run,
+            Command::Step => Shortcut::Step,
+            Command::Stop => Shortcut::Stop,
+            Command::Reset => Shortcut::Reset,
+            Command::NextSlide => Shortcut::NextSlide,
+            Command::PreviousSlide => Shortcut::PreviousSlide,
+            Command::NextPane => Shortcut::NextPane,
+            Command::PreviousPane => Shortcut::PreviousPane,
+            Command::ToggleSummary => Shortcut::ToggleSummary,
+        }
+    }
+}
+
+// The binding of a key press as it is written in the keymap (see src/backend/keymap.rs), if the key can be bound
+pub fn binding(key: &Key, modifiers: Modifiers) -> Option<Binding> {
+    let key = match key.as_ref() {
+        Key::Character(c) => Binding::key_name(c)?,
+        Key::Named(named) => named_key(named)?.into(),
+        Key::Unidentified => return None,
+    };
+
+    Some(Binding {
+        ctrl: modifiers.command(),
+        alt: modifiers.alt(),
+        shift: modifiers.shift(),
+        key,
+    })
+}
+
+// The name the keymap uses for a key that doesn't type anything, None for keys that can't be bound (e.g. modifiers and media keys)
+fn named_key(named: Named) -> Option<&'static str> {
+    Some(match named {
+        Named::Enter => "Enter",
+        Named::Space => "Space",
+        Named::Tab => "Tab",
+        Named::Escape => "Escape",
+        Named::Backspace => "Backspace",
+        Named::Delete => "Delete",
+        Named::Insert => "Insert",
+        Named::Home => "Home",
+        Named::End => "End",
+        Named::PageUp => "PageUp",
+        Named::PageDown => "PageDown",
+        Named::ArrowUp => "Up",
+        Named::ArrowDown => "Down",
+        Named::ArrowLeft => "Left",
+        Named::ArrowRight => "Right",
+        Named::F1 => "F1",
+        Named::F2 => "F2",
+        Named::F3 => "F3",
+        Named::F4 => "F4",
+        Named::F5 => "F5",
+        Named::F6 => "F6",
+        Named::F7 => "F7",
+        Named::F8 => "F8",
+        Named::F9 => "F9",
+        Named::F10 => "F10",
+        Named::F11 => "F11",
+        Named::F12 => "F12",
+        _ => return None,
+    })
+}
+
+/* The shortcut a key press does, if any:
+- The commands in the keymap (e.g. Ctrl+S saves and F5 runs), which can be changed in the settings screen
+- Tab and Shift+Tab (or the down and up arrows) move between buttons, enter or space presses them, and the right and left arrows change a setting
+- Escape goes back
+Keys are only looked at when no widget used them (e.g. tab in the editor still indents) */
+pub fn shortcut(key: &Key, modifiers: Modifiers, keymap: &Keymap) -> Option<Shortcut> {
+    if let Some(command) = binding(key, modifiers).and_then(|binding| keymap.command(&binding)) {
+        return Some(command.into());
+    }
+
+    // Moving between and pressing buttons never uses ctrl or alt, so it can't get in the way of the keymap
+    if modifiers.command() || modifiers.alt() {
+        return None;
+    }
+
+    Some(match key.as_ref() {
+        Key::Named(Named::Tab) if modifiers.shift() => Shortcut::FocusPrevious,
+        Key::Named(Named::Tab | Named::ArrowDown) => Shortcut::FocusNext,
+        Key::Named(Named::ArrowUp) => Shortcut::FocusPrevious,
+        Key::Named(Named::ArrowRight) => Shortcut::Increase,
+        Key::Named(Named::ArrowLeft) => Shortcut::Decrease,
+        Key::Named(Named::Enter | Named::Space) => Shortcut::Activate,
+        Key::Named(Named::Escape) => Shortcut::Back,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use iced::keyboard::{Key, Modifiers, key::Named};
+
+    use crate::backend::keymap::{Binding, Keymap};
+    use crate::frontend::util::shortcuts::{Shortcut, binding, shortcut};
+
+    // 16.1
+    #[test]
+    fn shortcut_bindings() {
+        let key = |named| Key::Named(named);
+
+        assert_eq!(
+            binding(&Key::Character("s".into()), Modifiers::CTRL),
+            Binding::try_from("Ctrl+S").ok()
+        );
+        assert_eq!(
+            binding(&key(Named::F5), Modifiers::SHIFT),
+            Binding::try_from("Shift+F5").ok()
+        );
+        assert_eq!(
+            binding(&key(Named::ArrowRight), Modifiers::ALT),
+            Binding::try_from("Alt+Right").ok()
+        );
+        assert_eq!(
+            binding(&key(Named::PageUp), Modifiers::CTRL),
+            Binding::try_from("Ctrl+PageUp").ok()
+        );
+
+        // Keys that can't be written in the keymap
+        assert_eq!(binding(&key(Named::Shift), Modifiers::SHIFT), None);
+        assert_eq!(binding(&key(Named::F13), Modifiers::empty()), None);
+        assert_eq!(binding(&Key::Unidentified, Modifiers::empty()), None);
+    }
+
+    // 16.2
+    #[test]
+    fn shortcut_keys() {
+        let keymap = Keymap::default();
+        let press = |key: Key, modifiers| shortcut(&key, modifiers, &keymap);
+
+        // Commands in the keymap, including showing the machine state as text
+        assert_eq!(
+            press(Key::Character("m".into()), Modifiers::CTRL),
+            Some(Shortcut::ToggleSummary)
+        );
+        assert_eq!(
+            press(Key::Named(Named::F5), Modifiers::empty()),
+            Some(Shortcut::Run)
+        );
+
+        // Moving between and pressing buttons
+        assert_eq!(
+            press(Key::Named(Named::Tab), Modifiers::empty()),
+            Some(Shortcut::FocusNext)
+        );
+        assert_eq!(
+            press(Key::Named(Named::Tab), Modifiers::SHIFT),
+            Some(Shortcut::FocusPrevious)
+        );
+        assert_eq!(
+            press(Key::Named(Named::Space), Modifiers::empty()),
+            Some(Shortcut::Activate)
+        );
+        assert_eq!(
+            press(Key::Named(Named::Escape), Modifiers::empty()),
+            Some(Shortcut::Back)
+        );
+
+        // Ctrl and alt are only used by the keymap
+        assert_eq!(press(Key::Named(Named::Tab), Modifiers::CTRL), None);
+        assert_eq!(press(Key::Character("q".into()), Modifiers::empty()), None);
+    }
+}
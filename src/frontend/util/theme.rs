@@ -1,48 +1,416 @@
-use std::fmt::Display;
+use std::{
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+};
 
+use iced::{Color, theme::Palette};
 use serde::{Deserialize, Serialize};
 
-// Similar to iced theme but restricted to only light and dark themes for now, also implement the ability for (de)serialisation for config files
+use crate::shared::locale::{tr, tr_with};
+
+// Every one of iced's built-in themes plus themes loaded from files, also implement the ability for (de)serialisation for config files
 #[derive(PartialEq, Clone, Debug, Deserialize, Serialize)]
 pub enum Theme {
     Light,
     Dark,
+    Dracula,
+    Nord,
+    SolarizedLight,
+    SolarizedDark,
+    GruvboxLight,
+    GruvboxDark,
+    CatppuccinLatte,
+    CatppuccinFrappe,
+    CatppuccinMacchiato,
+    CatppuccinMocha,
+    TokyoNight,
+    TokyoNightStorm,
+    TokyoNightLight,
+    KanagawaWave,
+    KanagawaDragon,
+    KanagawaLotus,
+    Moonfly,
+    Nightfly,
+    Oxocarbon,
+    Ferra,
+    // A theme from the themes directory (see CustomTheme), written in the config as just its name (e.g. theme = "High Contrast")
+    #[serde(untagged)]
+    Custom(String),
 }
 
 // Represents a list of themes (used in drop-down menus)
 impl Theme {
-    pub const ALL: &'static [Theme] = &[Theme::Light, Theme::Dark];
-}
+    pub const ALL: &'static [Theme] = &[
+        Theme::Light,
+        Theme::Dark,
+        Theme::Dracula,
+        Theme::Nord,
+        Theme::SolarizedLight,
+        Theme::SolarizedDark,
+        Theme::GruvboxLight,
+        Theme::GruvboxDark,
+        Theme::CatppuccinLatte,
+        Theme::CatppuccinFrappe,
+        Theme::CatppuccinMacchiato,
+        Theme::CatppuccinMocha,
+        Theme::TokyoNight,
+        Theme::TokyoNightStorm,
+        Theme::TokyoNightLight,
+        Theme::KanagawaWave,
+        Theme::KanagawaDragon,
+        Theme::KanagawaLotus,
+        Theme::Moonfly,
+        Theme::Nightfly,
+        Theme::Oxocarbon,
+        Theme::Ferra,
+    ];
 
-// Convert Theme to iced theme
-impl From<Theme> for iced::Theme {
-    fn from(theme: Theme) -> Self {
-        match theme {
+    // The built-in themes followed by the custom themes that were loaded
+    pub fn all(custom: &[CustomTheme]) -> Vec<Theme> {
+        Theme::ALL
+            .iter()
+            .cloned()
+            .chain(custom.iter().map(|theme| Theme::Custom(theme.name.clone())))
+            .collect()
+    }
+
+    // Convert Theme to iced theme, custom themes are looked up by name and fall back to the light theme if the file has gone
+    pub fn to_iced(&self, custom: &[CustomTheme]) -> iced::Theme {
+        match self {
             Theme::Light => iced::Theme::Light,
             Theme::Dark => iced::Theme::Dark,
+            Theme::Dracula => iced::Theme::Dracula,
+            Theme::Nord => iced::Theme::Nord,
+            Theme::SolarizedLight => iced::Theme::SolarizedLight,
+            Theme::SolarizedDark => iced::Theme::SolarizedDark,
+            Theme::GruvboxLight => iced::Theme::GruvboxLight,
+            Theme::GruvboxDark => iced::Theme::GruvboxDark,
+            Theme::CatppuccinLatte => iced::Theme::CatppuccinLatte,
+            Theme::CatppuccinFrappe => iced::Theme::CatppuccinFrappe,
+            Theme::CatppuccinMacchiato => iced::Theme::CatppuccinMacchiato,
+            Theme::CatppuccinMocha => iced::Theme::CatppuccinMocha,
+            Theme::TokyoNight => iced::Theme::TokyoNight,
+            Theme::TokyoNightStorm => iced::Theme::TokyoNightStorm,
+            Theme::TokyoNightLight => iced::Theme::TokyoNightLight,
+            Theme::KanagawaWave => iced::Theme::KanagawaWave,
+            Theme::KanagawaDragon => iced::Theme::KanagawaDragon,
+            Theme::KanagawaLotus => iced::Theme::KanagawaLotus,
+            Theme::Moonfly => iced::Theme::Moonfly,
+            Theme::Nightfly => iced::Theme::Nightfly,
+            Theme::Oxocarbon => iced::Theme::Oxocarbon,
+            Theme::Ferra => iced::Theme::Ferra,
+            Theme::Custom(name) => match custom.iter().find(|theme| &theme.name == name) {
+                Some(theme) => iced::Theme::custom(theme.name.clone(), theme.palette()),
+                None => iced::Theme::Light,
+            },
+        }
+    }
+
+    // The colours used by the syntax highlighting in the editor, custom themes can pick their own
+    pub fn editor_colours(&self, custom: &[CustomTheme]) -> EditorColours {
+        match self {
+            Theme::Custom(name) => match custom.iter().find(|theme| &theme.name == name) {
+                Some(theme) => theme.editor_colours(),
+                None => EditorColours::from(Palette::LIGHT),
+            },
+            theme => EditorColours::from(theme.to_iced(&[]).palette()),
         }
     }
 }
 
-// Convert iced theme into Theme if the theme is supported
+// Convert iced theme into Theme, custom iced themes are assumed to have been loaded from the themes directory
 impl TryFrom<iced::Theme> for Theme {
-    type Error = &'static str;
+    type Error = String;
 
     fn try_from(iced_theme: iced::Theme) -> Result<Self, Self::Error> {
         match iced_theme {
-            iced::Theme::Light => Ok(Theme::Light),
-            iced::Theme::Dark => Ok(Theme::Dark),
-            _ => Err("Unsupported theme"),
+            iced::Theme::Custom(custom) => Ok(Theme::Custom(custom.to_string())),
+            iced_theme => Theme::ALL
+                .iter()
+                .find(|theme| theme.to_iced(&[]) == iced_theme)
+                .cloned()
+                .ok_or_else(|| tr("theme-unsupported")),
         }
     }
 }
 
-// Allow formatting and printing out of strings (also for drop-down menus)
+// Allow formatting and printing out of strings (also for drop-down menus), using iced's names for the built-in themes (e.g. "Solarized Light")
 impl Display for Theme {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Theme::Light => write!(f, "Light"),
-            Theme::Dark => write!(f, "Dark"),
+            Theme::Custom(name) => write!(f, "{name}"),
+            theme => write!(f, "{}", theme.to_iced(&[])),
         }
     }
 }
+
+// A colour written in a theme file as hex (e.g. "#1e1e2e" or "#fff")
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Colour(pub Color);
+
+impl TryFrom<String> for Colour {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let error = || tr_with("theme-invalid-colour", &[("colour", &value)]);
+
+        let hex = value.strip_prefix('#').ok_or_else(error)?;
+        let digits = hex
+            .chars()
+            .map(|digit| digit.to_digit(16).map(|digit| digit as u8))
+            .collect::<Option<Vec<u8>>>()
+            .ok_or_else(error)?;
+
+        // Short colours repeat each digit (i.e. #fff is #ffffff)
+        let [r, g, b] = match digits[..] {
+            [r, g, b] => [r * 17, g * 17, b * 17],
+            [r1, r2, g1, g2, b1, b2] => [r1 * 16 + r2, g1 * 16 + g2, b1 * 16 + b2],
+            _ => return Err(error()),
+        };
+
+        Ok(Colour(Color::from_rgb8(r, g, b)))
+    }
+}
+
+// The colours of each kind of token in the editor (see src/frontend/util/highlighter.rs)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EditorColours {
+    // Mnemonics (e.g. LDA) and directives (e.g. DAT)
+    pub instruction: Color,
+    pub label: Color,
+    pub number: Color,
+    pub comment: Color,
+}
+
+// Pick editor colours from the colours of a theme, for themes that don't pick their own
+impl From<Palette> for EditorColours {
+    fn from(palette: Palette) -> Self {
+        Self {
+            instruction: palette.primary,
+            label: palette.text,
+            number: palette.warning,
+            comment: palette.text.scale_alpha(0.6),
+        }
+    }
+}
+
+// The editor section of a theme file, any colours left out are picked from the rest of the theme
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct EditorTheme {
+    pub instruction: Option<Colour>,
+    pub label: Option<Colour>,
+    pub number: Option<Colour>,
+    pub comment: Option<Colour>,
+}
+
+/* A theme described in a TOML file in the themes directory, e.g.
+name = "High Contrast"
+background = "#000000"
+text = "#ffffff"
+primary = "#ffff00"
+success = "#00ff00"
+danger = "#ff4040"
+
+[editor]
+instruction = "#ffff00"
+number = "#00ffff" */
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct CustomTheme {
+    // Shown in the settings screen and written in the config, the file name is used if it isn't given
+    #[serde(default)]
+    pub name: String,
+    pub background: Colour,
+    pub text: Colour,
+    pub primary: Colour,
+    pub success: Colour,
+    pub danger: Colour,
+    // Not many widgets use a warning colour, so the danger colour is used if it isn't given
+    #[serde(default)]
+    pub warning: Option<Colour>,
+    #[serde(default)]
+    pub editor: EditorTheme,
+}
+
+impl CustomTheme {
+    // Read a theme from its file, naming it after the file if it has no name
+    pub fn parse(file: &str, file_name: &str) -> Result<Self, String> {
+        let mut theme = toml::from_str::<CustomTheme>(file).map_err(|e| e.to_string())?;
+
+        if theme.name.is_empty() {
+            theme.name = file_name.to_string();
+        }
+
+        // The config can't tell the difference between a custom theme and a built-in theme with the same name, whether that's the name shown (e.g. "Solarized Light") or the one written in the config (e.g. "SolarizedLight")
+        if Theme::ALL.iter().any(|built_in| {
+            built_in.to_string() == theme.name
+                || toml::Value::try_from(built_in)
+                    .is_ok_and(|value| value.as_str() == Some(theme.name.as_str()))
+        }) {
+            return Err(tr_with("theme-built-in-name", &[("name", &theme.name)]));
+        }
+
+        Ok(theme)
+    }
+
+    pub fn palette(&self) -> Palette {
+        Palette {
+            background: self.background.0,
+            text: self.text.0,
+            primary: self.primary.0,
+            success: self.success.0,
+            warning: self.warning.unwrap_or(self.danger).0,
+            danger: self.danger.0,
+        }
+    }
+
+    pub fn editor_colours(&self) -> EditorColours {
+        let colours = EditorColours::from(self.palette());
+
+        EditorColours {
+            instruction: self.editor.instruction.map_or(colours.instruction, |c| c.0),
+            label: self.editor.label.map_or(colours.label, |c| c.0),
+            number: self.editor.number.map_or(colours.number, |c| c.0),
+            comment: self.editor.comment.map_or(colours.comment, |c| c.0),
+        }
+    }
+}
+
+// Read every .toml file in the themes directory in order of name, themes that can't be read are returned as errors (with the file they came from) so the rest still load
+pub fn load(directory: &Path) -> (Vec<CustomTheme>, Vec<(PathBuf, String)>) {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return (Vec::new(), Vec::new());
+    };
+
+    let mut paths = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "toml")
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    let mut themes: Vec<CustomTheme> = Vec::new();
+    let mut errors = Vec::new();
+
+    for path in paths {
+        let file_name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let theme = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|file| CustomTheme::parse(&file, &file_name));
+
+        match theme {
+            Ok(theme) if themes.iter().any(|loaded| loaded.name == theme.name) => {
+                errors.push((path, format!("\"{}\" has already been loaded", theme.name)))
+            }
+            Ok(theme) => themes.push(theme),
+            Err(e) => errors.push((path, e)),
+        }
+    }
+
+    (themes, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use iced::Color;
+
+    use crate::backend::config::{Config, SystemConfig};
+    use crate::frontend::util::theme::{CustomTheme, Theme};
+
+    // 10.1
+    #[test]
+    fn theme_custom() {
+        let theme = CustomTheme::parse(
+            r##"background = "#000"
+            text = "#ffffff"
+            primary = "#ffff00"
+            success = "#00ff00"
+            danger = "#ff4040"
+
+            [editor]
+            number = "#00ffff""##,
+            "high-contrast",
+        )
+        .unwrap();
+
+        assert_eq!(theme.name, "high-contrast");
+        assert_eq!(theme.palette().background, Color::BLACK);
+        // Colours left out are picked from the rest of the theme
+        assert_eq!(theme.palette().warning, Color::from_rgb8(0xff, 0x40, 0x40));
+        assert_eq!(
+            theme.editor_colours().number,
+            Color::from_rgb8(0x00, 0xff, 0xff)
+        );
+        assert_eq!(
+            theme.editor_colours().instruction,
+            Color::from_rgb8(0xff, 0xff, 0x00)
+        );
+
+        let custom = [theme];
+        assert_eq!(
+            Theme::all(&custom).last(),
+            Some(&Theme::Custom("high-contrast".into()))
+        );
+        assert_eq!(
+            Theme::try_from(Theme::Custom("high-contrast".into()).to_iced(&custom)),
+            Ok(Theme::Custom("high-contrast".into()))
+        );
+
+        // Bad colours and names taken by built-in themes are rejected
+        assert!(CustomTheme::parse(r#"background = "black""#, "bad").is_err());
+        assert!(
+            CustomTheme::parse(
+                r##"name = "Nord"
+                background = "#000"
+                text = "#fff"
+                primary = "#ff0"
+                success = "#0f0"
+                danger = "#f00""##,
+                "nord"
+            )
+            .is_err()
+        );
+        assert!(
+            CustomTheme::parse(
+                r##"background = "#000"
+                text = "#fff"
+                primary = "#ff0"
+                success = "#0f0"
+                danger = "#f00""##,
+                "SolarizedLight"
+            )
+            .is_err()
+        );
+    }
+
+    // 10.2
+    #[test]
+    fn theme_config() {
+        let system = SystemConfig::default();
+
+        // Built-in themes are written by their variant name and custom themes by their own name
+        let (config, _) = Config::parse(r#"theme = "SolarizedDark""#, &system).unwrap();
+        assert_eq!(config.theme, Theme::SolarizedDark);
+        assert_eq!(config.theme.to_string(), "Solarized Dark");
+
+        let (config, _) = Config::parse(r#"theme = "High Contrast""#, &system).unwrap();
+        assert_eq!(config.theme, Theme::Custom("High Contrast".into()));
+        assert!(
+            toml::to_string(&config)
+                .unwrap()
+                .contains(r#"theme = "High Contrast""#)
+        );
+
+        // A custom theme that has gone falls back to the light theme
+        assert_eq!(config.theme.to_iced(&[]), iced::Theme::Light);
+    }
+}
@@ -26,3 +26,54 @@ pub mod separator {
         }
     }
 }
+
+pub mod focus {
+    use iced::widget::container;
+    use iced::{Border, Color, Element, Theme};
+
+    // Which button of a screen has keyboard focus (counting in the order they are shown), None until the keyboard is used so mouse users don't see a focus ring
+    #[derive(Debug, Clone, Copy, Default, PartialEq)]
+    pub struct Focus(Option<usize>);
+
+    impl Focus {
+        // Move focus to the next of a number of buttons, wrapping around to the first
+        pub fn next(&mut self, count: usize) {
+            self.0 = (count > 0).then(|| self.0.map_or(0, |i| (i + 1) % count));
+        }
+
+        // Ditto next comment but moving backwards, wrapping around to the last
+        pub fn previous(&mut self, count: usize) {
+            self.0 = (count > 0).then(|| self.0.map_or(count - 1, |i| (i + count - 1) % count));
+        }
+
+        pub fn get(&self) -> Option<usize> {
+            self.0
+        }
+
+        pub fn is(&self, index: usize) -> bool {
+            self.0 == Some(index)
+        }
+    }
+
+    // Draw a ring in the primary colour around a widget when it has keyboard focus, the space for the ring is always left so nothing moves when focus does
+    pub fn ring<'a, Message: 'a>(
+        content: impl Into<Element<'a, Message>>,
+        focused: bool,
+    ) -> Element<'a, Message> {
+        container(content)
+            .padding(2)
+            .style(move |theme: &Theme| container::Style {
+                border: Border {
+                    color: if focused {
+                        theme.palette().primary
+                    } else {
+                        Color::TRANSPARENT
+                    },
+                    width: 2f32,
+                    radius: 4.into(),
+                },
+                ..Default::default()
+            })
+            .into()
+    }
+}
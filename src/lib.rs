@@ -1,6 +1,8 @@
 pub mod frontend {
     pub mod util {
         pub mod font;
+        pub mod highlighter;
+        pub mod shortcuts;
         pub mod theme;
         pub mod widgets;
     }
@@ -10,12 +12,21 @@ pub mod frontend {
 }
 
 pub mod backend {
+    pub mod cli;
     pub mod compiler;
     pub mod config;
+    pub mod keymap;
     pub mod lesson_parser;
+    pub mod lsp;
+    pub mod paths;
+    pub mod recovery;
+    pub mod session;
 }
 
 pub mod shared {
+    pub mod isa;
+    pub mod locale;
     pub mod runtime;
+    pub mod trace;
     pub mod vm;
 }
@@ -1,22 +1,39 @@
 use std::{
     env, fs,
-    io::Write,
+    io::{self, Write},
     sync::{Arc, Mutex},
     time::Instant,
 };
 
+use algor::shared::vm::Computer;
 use algor::{
-    backend::config::{self, Config},
-    shared::runtime,
+    backend::{
+        cli,
+        config::{self, Config, Policy, SystemConfig},
+        paths::{Overrides, Paths},
+        recovery::{self, Buffer},
+        session::{LastScreen, Session},
+    },
+    shared::{
+        locale::{self, tr, tr_with},
+        runtime,
+    },
+};
+use algor::{
+    frontend::pane::editor,
+    shared::runtime::{Input, Output},
 };
-use algor::{frontend::pane::editor, shared::runtime::Input};
-use algor::{frontend::screen::sandbox, shared::vm::Computer};
 
-use iced::{Element, Settings, Subscription, Task, time};
-use iced::{futures::channel::mpsc::Sender, widget::text_editor};
+use iced::futures::channel::mpsc::{self, Sender};
+use iced::widget::operation;
+use iced::{Element, Event, Settings, Subscription, Task, event, keyboard, time, window};
 
-use algor::frontend::screen::{self, Screen, settings};
-use algor::frontend::util::font::{FAMILY_NAME, Font};
+use algor::frontend::screen::{self, Screen, lesson_view, menu, sandbox, settings};
+use algor::frontend::util::{
+    font::{FAMILY_NAME, Font},
+    shortcuts,
+    theme::{self, CustomTheme},
+};
 
 // The enum that defines the type of events that can happen during the execution of the program
 #[derive(Debug)]
@@ -24,23 +41,36 @@ enum Message {
     // These are messages relating to specific sections/menus (screens) of the application
     Screen(screen::Message),
     // The message that gets bubbled up when the user saves their config
-    ConfigSaved,
+    ConfigSaved(Result<(), config::ConfigError>),
     // The message that gets bubbled up when the user changes the lessons directory via the browse button
     LessonsDirectoryChanged(settings::State, String),
 
-    // The messages that get bubbleed up when the user tries to open/save an LMC file
-    SetContent(sandbox::State, Option<String>),
-    SaveContent(sandbox::State, Option<String>),
+    // The messages that get bubbleed up when the user tries to open/save an LMC file (saving carries the id of the tab and the text to write)
+    SetContent(Option<String>),
+    SaveContent(usize, String, Option<String>),
+    // The message that gets bubbled up when the user picks where to export a trace table to
+    TraceExportPicked(Option<String>),
 
     // Messages relating to the execution of code in the virtual machine
     Runtime(runtime::Event),
 
+    // The message that gets bubbled up when the user presses or releases ctrl, shift, alt, or the logo key (used for ctrl-clicking labels in the editor)
+    ModifiersChanged(keyboard::Modifiers),
+    // The message that gets bubbled up when the user presses a key that no widget used, which may be a shortcut (see src/frontend/util/shortcuts.rs)
+    KeyPressed(keyboard::Key, keyboard::Modifiers),
+
     // A message that occurs every time the state of the virtual machine is updated
     #[allow(dead_code)]
     Step(Instant),
+
+    // The message that gets bubbled up when the user closes the window, so the session can be saved first
+    CloseRequested,
+    // A message that occurs every so often to write unsaved editor buffers to the recovery directory
+    Autosave,
 }
 
 /* The entry point of the application:
+- Runs a command line subcommand instead if one is given (see src/backend/cli.rs)
 - Specifies the new (constructor), update (message/event handler), and view (UI displayed to user) functions to be that of the implementations in the main struct (Algor)
 - Loads the fonts and sets the default font family
 - Sets the title of the application shown in the OS's window manager to "algor"
@@ -49,17 +79,40 @@ enum Message {
 - Sets the theme function to the iced_them method of the Algor struct
 - Runs the application */
 fn main() -> iced::Result {
-    iced::application(Algor::new, Algor::update, Algor::view)
-        .settings(Settings {
-            fonts: vec![Font::Regular.into(), Font::Bold.into(), Font::Italic.into()],
-            default_font: iced::Font::with_name(FAMILY_NAME),
-            ..Settings::default()
-        })
-        .title("algor")
-        .subscription(Algor::subscription)
-        .font(Font::Regular)
-        .theme(Algor::iced_theme)
-        .run()
+    let args = env::args().skip(1).collect::<Vec<_>>();
+
+    if let Some(code) = cli::run(args.clone()) {
+        std::process::exit(code);
+    }
+
+    // Otherwise the arguments can only move where files are kept
+    let overrides = cli::app(args).unwrap_or_else(|code| std::process::exit(code));
+
+    iced::application(
+        move || Algor::new(overrides.clone()),
+        Algor::update,
+        Algor::view,
+    )
+    .settings(Settings {
+        fonts: vec![Font::Regular.into(), Font::Bold.into(), Font::Italic.into()],
+        default_font: iced::Font::with_name(FAMILY_NAME),
+        ..Settings::default()
+    })
+    .title("algor")
+    .subscription(Algor::subscription)
+    // Closing is handled by Algor::update so the session is saved before exiting
+    .exit_on_close_request(false)
+    .font(Font::Regular)
+    .theme(Algor::iced_theme)
+    .run()
+}
+
+// The screen the user is working in, looking through the settings screen to the screen it was opened from
+fn working_screen(screen: &Screen) -> &Screen {
+    match screen {
+        Screen::Settings(state) => working_screen(&state.last_screen),
+        screen => screen,
+    }
 }
 
 // Enum representing what computer is running
@@ -82,47 +135,154 @@ struct Algor {
     screen: Screen,
     // The user's configuration
     config: Config,
+    // Defaults and restrictions shared by every user of the computer (see src/backend/config.rs)
+    system: SystemConfig,
+    // Themes loaded from the themes directory (see src/frontend/util/theme.rs)
+    themes: Vec<CustomTheme>,
     // The virtual machines of the Sandbox and Lesson View screens
     computers: Computers,
     // The shared interior-mutable interface for asynchronous, biderectional communication between the frontend and backend
-    sender: Option<Arc<Mutex<Sender<Input>>>>,
+    sender: Arc<Mutex<Sender<Input>>>,
+    // Where the config, session, and autosaved work are kept
+    paths: Paths,
+    // Why the config couldn't be loaded (the default config is used instead), shown on the menu until it is reset or dismissed
+    config_error: Option<String>,
+    // Why the system config couldn't be used, shown on the menu until it is fixed as only whoever looks after the computer can fix it
+    system_error: Option<String>,
+    // Recent files and what the user was last doing, saved alongside the config (see src/backend/session.rs)
+    session: Session,
+    // Buffers autosaved before the application last closed that the user hasn't restored or thrown away yet (see src/backend/recovery.rs)
+    recovered: Vec<Buffer>,
+    // The buffers last written to the recovery directory, so nothing is written if nothing has changed
+    autosaved: Vec<Buffer>,
 }
 
 // Allow the ability to create a new program
 impl Default for Algor {
     fn default() -> Self {
         Self {
-            screen: Screen::Menu(screen::menu::State),
+            screen: Screen::Menu(menu::State::default()),
             config: Config::default(),
+            system: SystemConfig::default(),
+            themes: Vec::new(),
             computers: Computers {
                 sandbox: Arc::new(Mutex::new(Computer::default())),
                 lesson_viewer: Arc::new(Mutex::new(Computer::default())),
                 running: None,
                 input_needed: false,
             },
-            sender: None,
+            // Nothing is listening until the runtime is ready, so inputs sent before then are dropped (see runtime::send)
+            sender: Arc::new(Mutex::new(mpsc::channel(1).0)),
+            config_error: None,
+            system_error: None,
+            paths: Paths::fallback(),
+            session: Session::default(),
+            recovered: Vec::new(),
+            autosaved: Vec::new(),
         }
     }
 }
 
 impl Algor {
-    // Create a new program by deserialising the user config and falling back to the default config if necessary (see src/backend/config.rs)
-    fn new() -> (Self, Task<Message>) {
-        let mut path = env::home_dir().unwrap();
-        path.push(config::CONFIG_PATH);
-
-        (
-            Self {
-                config: Config::try_from(path).unwrap_or_default().into(),
-                ..Default::default()
-            },
-            Task::none(),
-        )
+    // Create a new program by deserialising the user config and falling back to the default config if necessary (see src/backend/config.rs), keeping files where the command line, environment, or platform says to (see src/backend/paths.rs)
+    fn new(overrides: Overrides) -> (Self, Task<Message>) {
+        let (paths, paths_error) = match Paths::resolve(overrides) {
+            Ok(paths) => (paths, None),
+            // Keep files in a temporary directory so the application still opens
+            Err(e) => (Paths::fallback(), Some(e)),
+        };
+
+        // A system config that can't be read is reported separately from the user's config, which is still loaded without the system config's defaults
+        let (mut system, system_error) = match SystemConfig::load(&paths.system) {
+            Ok(system) => (system, None),
+            Err(e) => (SystemConfig::default(), Some(e)),
+        };
+
+        let config = match paths_error {
+            Some(e) => Err(e),
+            None => Config::load(paths.config.clone(), &system),
+        };
+
+        // Ditto system comment, the user's config is loaded first so the locks only stop it being changed
+        if system_error.is_some() {
+            system.policy = Policy::locked_down();
+        }
+
+        let (config, config_error) = match config {
+            Ok(config) => (config, None),
+            Err(e) => (Self::default_config(&system), Some(e)),
+        };
+
+        // Show everything from here on (including why the config couldn't be loaded) in the language picked in the config
+        locale::set_language(config.language);
+
+        // Themes that can't be read are left out of the settings screen, a config using one falls back to the light theme
+        let (themes, theme_errors) = theme::load(&paths.themes());
+        for (path, e) in theme_errors {
+            eprintln!("Failed to load theme {}, {e}", path.display());
+        }
+
+        // Ditto config comment but for the session, which falls back to an empty session
+        let session = Session::load(&paths.session());
+
+        // Ditto session comment but for unsaved work, which is what is in the recovery directory to begin with
+        let recovered = recovery::load(&paths.recovery());
+
+        let mut algor = Self {
+            config,
+            system,
+            themes,
+            config_error: config_error.map(|e| e.to_string()),
+            system_error: system_error.map(|e| e.to_string()),
+            paths,
+            session,
+            autosaved: recovered.clone(),
+            recovered,
+            ..Default::default()
+        };
+        algor.override_lessons();
+        algor.screen = algor.menu();
+
+        (algor, Task::none())
+    }
+
+    // The config used when the user's can't be loaded, the system config's defaults still apply
+    fn default_config(system: &SystemConfig) -> Config {
+        Config::parse("", system)
+            .map(|(config, _)| config)
+            .unwrap_or_default()
+    }
+
+    // Lessons can be opened from a different directory for this run without touching the config file, unless the system config locks the directory
+    fn override_lessons(&mut self) {
+        if let Some(lessons) = &self.paths.lessons
+            && !self.system.policy.is_locked("lessons_directory")
+        {
+            self.config.lessons_directory = lessons.to_string_lossy().into_owned();
+        }
+    }
+
+    // The main menu, showing what the session and system config let the user do
+    fn menu(&self) -> Screen {
+        Screen::Menu(menu::State::new(
+            &self.session,
+            &self.recovered,
+            self.config_error.as_deref(),
+            self.system_error.as_deref(),
+            &self.system.policy,
+        ))
     }
 
     // Show the current screen
     fn view(&self) -> Element<'_, Message> {
-        self.screen.view().map(Message::Screen)
+        self.screen
+            .view(
+                self.computers.running.is_some(),
+                !self.system.policy.hide_settings,
+                self.config.theme.editor_colours(&self.themes),
+                &self.config.keymap,
+            )
+            .map(Message::Screen)
     }
 
     // Updates the state of the virtual machine
@@ -137,8 +297,27 @@ impl Algor {
             Subscription::none()
         };
 
+        // Keep track of the modifier keys even when a widget (e.g. the text editor) has focus, other keys are only shortcuts when no widget used them
+        let keyboard = event::listen_with(|event, status, _window| match event {
+            Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                Some(Message::ModifiersChanged(modifiers))
+            }
+            Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. })
+                if status == event::Status::Ignored =>
+            {
+                Some(Message::KeyPressed(key, modifiers))
+            }
+            _ => None,
+        });
+
         // Bundle the subscriptions to be ran at the same time
-        Subscription::batch(vec![run, step])
+        Subscription::batch(vec![
+            run,
+            step,
+            keyboard,
+            window::close_requests().map(|_| Message::CloseRequested),
+            time::every(recovery::AUTOSAVE_INTERVAL).map(|_| Message::Autosave),
+        ])
     }
 
     // Updates the state of the application
@@ -150,66 +329,149 @@ impl Algor {
                     match event {
                         // Save the config to the default path (dependant on the operating system)
                         screen::Event::SetConfig(config) => {
+                            locale::set_language(config.language);
                             self.config = config;
+                            return self.save_config();
+                        }
 
-                            let mut path = env::home_dir().unwrap();
-                            path.push(config::CONFIG_PATH);
-
-                            /* Use a task to run an asynchronous function that saves the config, ensuring the program doesn't freeze while the file is being serialised and written to disk
-                            If the function wasn't asynchronous, the program could hang and cause a "... is not responding" popup on Windows and potentially macOS and potentially cause a "Hall of Mirrors" effect when other windows pass above this window on other unix-like operating systems due to the framebuffer not updating properly */
-                            return Task::perform(self.config.clone().save(path), |_| {
-                                Message::ConfigSaved
-                            });
+                        // Overwrite the config that couldn't be loaded with the default config
+                        screen::Event::ResetConfig => {
+                            self.config = Self::default_config(&self.system);
+                            self.config_error = None;
+                            locale::set_language(self.config.language);
+                            self.override_lessons();
+                            self.screen = self.menu();
+
+                            // Ditto Config::try_from comment
+                            if let Err(e) = fs::create_dir_all(&self.config.lessons_directory) {
+                                eprintln!("Failed to create lessons directory, {e}");
+                            }
+                            return self.save_config();
+                        }
+                        // Keep using the default config without touching the config file
+                        screen::Event::DismissConfigError => {
+                            self.config_error = None;
+                            self.screen = self.menu();
                         }
 
                         // Pick the lessons directory and send another message with the path
                         screen::Event::PickLessonsDirectory(state) => {
-                            // Ditto SetConfig comment but while the user is searching for a directory instead
-                            return Task::perform(settings::browse_directory(), move |directory| {
-                                Message::LessonsDirectoryChanged(state.clone(), directory)
-                            });
+                            // Ditto save_config comment but while the user is searching for a directory instead
+                            return Task::perform(
+                                settings::browse_directory(state.lessons_directory.clone()),
+                                move |directory| {
+                                    Message::LessonsDirectoryChanged(state.clone(), directory)
+                                },
+                            );
                         }
 
                         // Open from a *.lmc file, taking ownership of the path returned
-                        screen::Event::OpenLMC(state) => {
-                            return Task::perform(editor::open_lmc(), move |path| {
-                                Message::SetContent(state.clone(), path)
-                            });
+                        screen::Event::OpenLMC => {
+                            return Task::perform(editor::open_lmc(), Message::SetContent);
+                        }
+                        // Save to a *.lmc file, writing straight back to the file the program came from if there is one
+                        screen::Event::SaveLMC(id, Some(path), text) => {
+                            return Task::done(Message::SaveContent(id, text, Some(path)));
                         }
-                        // Save to a *.lmc file, taking ownership of the path returned
-                        screen::Event::SaveLMC(state) => {
+                        // Otherwise ask where to save it, taking ownership of the path returned
+                        screen::Event::SaveLMC(id, None, text) => {
                             return Task::perform(editor::save_lmc(), move |path| {
-                                Message::SaveContent(state.clone(), path)
+                                Message::SaveContent(id, text.clone(), path)
                             });
                         }
+                        screen::Event::ExportTrace => {
+                            return Task::perform(editor::save_trace(), Message::TraceExportPicked);
+                        }
 
                         // Change to the settings screen, saving the current screen for the functionality of the "Back" button
-                        screen::Event::ToSettings => {
+                        screen::Event::ToSettings if !self.system.policy.hide_settings => {
                             self.screen = Screen::Settings(settings::State::new(
                                 self.config.clone(),
+                                self.system.policy.locked.clone(),
+                                self.themes.clone(),
                                 Box::new(self.screen.clone()),
                             ));
                         }
 
                         // Change to the Sandbox screen, passing along a "clone" (incrementing atomic reference count) of the sender and computer, sending the editor font size too
-                        screen::Event::ToSandbox => {
-                            self.screen = Screen::Sandbox(screen::sandbox::State::new(
-                                self.computers.sandbox.clone(),
-                                self.sender.clone().unwrap(),
-                                self.config.editor_font_size,
-                            ))
+                        screen::Event::ToSandbox if !self.system.policy.hide_sandbox => {
+                            self.screen = Screen::Sandbox(self.sandbox())
                         }
 
                         // Ditto Sandbox comment, also sending along the lesson directory
                         screen::Event::ToLessonSelect => {
-                            let lessons = screen::lesson_select::State::get_lessons(
-                                self.config.lessons_directory.clone(),
-                                self.computers.lesson_viewer.clone(),
-                                self.sender.clone().unwrap(),
-                                self.config.editor_font_size,
-                            );
-                            self.screen =
-                                Screen::LessonSelect(screen::lesson_select::State::new(lessons))
+                            self.remember();
+                            self.screen = Screen::LessonSelect(screen::lesson_select::State::new(
+                                self.lessons(),
+                            ))
+                        }
+
+                        // Remember what the user was doing before leaving it, so the menu can offer to reopen it
+                        screen::Event::ToMenu => {
+                            self.remember();
+                            self.save_session();
+
+                            // Unsaved sandbox programs are kept with the recovered buffers, so they are still autosaved and can be restored from the menu
+                            if let Screen::Sandbox(state) = working_screen(&self.screen) {
+                                self.recovered.extend(state.buffers());
+                            }
+                            self.screen = self.menu();
+                            self.autosave();
+                        }
+
+                        // Go back to the last screen, reopening the sandbox's files or finding the lesson by its title
+                        screen::Event::ReopenSession => {
+                            self.screen = match self.session.screen.clone() {
+                                LastScreen::LessonSelect => Screen::LessonSelect(
+                                    screen::lesson_select::State::new(self.lessons()),
+                                ),
+                                LastScreen::LessonView { title, slide } => {
+                                    let lessons = self.lessons();
+                                    let lesson = lessons.as_ref().ok().and_then(|lessons| {
+                                        lessons.iter().find(|lesson| {
+                                            lesson.lesson.head.title == title
+                                                && slide < lesson.lesson.body.slides.len()
+                                        })
+                                    });
+
+                                    match lesson {
+                                        Some(lesson) => {
+                                            Screen::LessonView(lesson.clone().with_slide(slide))
+                                        }
+                                        // The lesson has been removed or changed, so show the list instead
+                                        None => Screen::LessonSelect(
+                                            screen::lesson_select::State::new(lessons),
+                                        ),
+                                    }
+                                }
+                                LastScreen::Sandbox | LastScreen::Menu => {
+                                    let mut state = self.sandbox();
+                                    if let Some(session) = &self.session.sandbox {
+                                        state.restore(session);
+                                    }
+                                    Screen::Sandbox(state)
+                                }
+                            };
+                        }
+
+                        // Open the autosaved buffers in sandbox tabs, from then on they are autosaved as the sandbox's own
+                        screen::Event::RestoreRecovered if !self.system.policy.hide_sandbox => {
+                            let mut state = self.sandbox();
+                            state.recover(&self.recovered);
+                            self.recovered.clear();
+                            self.screen = Screen::Sandbox(state);
+                        }
+                        // Throw the autosaved buffers away, removing them from the recovery directory straight away
+                        screen::Event::DiscardRecovered => {
+                            self.recovered.clear();
+                            self.autosave();
+                            self.screen = self.menu();
+                        }
+
+                        // Open the sandbox with the file in a tab
+                        screen::Event::OpenRecent(path) if !self.system.policy.hide_sandbox => {
+                            self.screen = Screen::Sandbox(self.sandbox());
+                            return Task::done(Message::SetContent(Some(path)));
                         }
 
                         // Clear the output (and input index for Lesson View) and set the running computer
@@ -229,7 +491,17 @@ impl Algor {
                         // Set running computer to None
                         screen::Event::Stop => self.computers.running = None,
 
-                        // Reset error and outputs (and input index for Lesson View), send reset input to runtime
+                        // Run one step of the program, which only makes sense while it is stopped
+                        screen::Event::Step => {
+                            if self.computers.running.is_none() {
+                                self.step();
+                            }
+                        }
+
+                        // Give keyboard focus to a widget (e.g. the editor of the pane moved to), which takes it away from every other widget
+                        screen::Event::Focus(id) => return operation::focus(id),
+
+                        // Reset error, warnings, and outputs (and input index for Lesson View), send reset input to runtime
                         screen::Event::Reset => {
                             self.computers.running = None;
 
@@ -237,29 +509,31 @@ impl Algor {
                                 Screen::LessonView(state) => {
                                     state.input = 0;
                                     state.error = String::new();
+                                    state.warnings = Vec::new();
                                     state.output = Vec::new();
                                 }
                                 Screen::Sandbox(state) => {
                                     state.error = String::new();
+                                    state.warnings = Vec::new();
                                     state.output = Vec::new();
                                 }
                                 _ => unreachable!(),
                             }
 
-                            if let Some(sender) = &mut self.sender
-                                && let Ok(mut sender) = sender.lock()
-                            {
-                                sender.try_send(Input::Reset).unwrap();
+                            runtime::send(&self.sender, Input::Reset);
+                        }
+
+                        // Send a value typed into the state viewer to the runtime, only while the program is paused
+                        screen::Event::Poke(cell, value) => {
+                            if self.computers.running.is_none() {
+                                runtime::send(&self.sender, Input::Poke(cell, value));
                             }
                         }
 
                         // Send input event to runtime, reset input needed flag
                         screen::Event::SubmitInput(input) => {
-                            if self.computers.input_needed
-                                && let Some(sender) = &mut self.sender
-                                && let Ok(mut sender) = sender.lock()
-                            {
-                                sender.try_send(Input::SetInput(input)).unwrap();
+                            if self.computers.input_needed {
+                                runtime::send(&self.sender, Input::SetInput(input));
 
                                 self.computers.running = Some(match self.screen {
                                     Screen::Sandbox(_) => Running::Sandbox,
@@ -269,14 +543,24 @@ impl Algor {
 
                             self.computers.input_needed = false;
                         }
+
+                        // The settings screen or sandbox were hidden by the system config, so there is nowhere to go
+                        screen::Event::ToSettings
+                        | screen::Event::ToSandbox
+                        | screen::Event::RestoreRecovered
+                        | screen::Event::OpenRecent(_) => {}
                     }
                 }
             }
 
             // Virtual machine runtime related messages
             Message::Runtime(event) => match event {
-                // Set the sender when the program begins to run
-                runtime::Event::Ready(sender) => self.sender = Some(Arc::new(Mutex::new(sender))),
+                // Set the sender when the program begins to run, screens share the same sender so they start using it too
+                runtime::Event::Ready(sender) => {
+                    if let Ok(mut inner) = self.sender.lock() {
+                        *inner = sender;
+                    }
+                }
 
                 // Update the state of the computer (i.e. registers and RAM)
                 runtime::Event::UpdateState(computer) => match &mut self.screen {
@@ -295,6 +579,13 @@ impl Algor {
                     _ => unreachable!(),
                 },
 
+                // Set the warnings shown in the terminal and editor panes
+                runtime::Event::SetWarnings(warnings) => match &mut self.screen {
+                    Screen::LessonView(state) => state.warnings = warnings,
+                    Screen::Sandbox(state) => state.warnings = warnings,
+                    _ => unreachable!(),
+                },
+
                 // Add to the list of outputs in the terminal pane
                 runtime::Event::Output(output) => match &mut self.screen {
                     Screen::LessonView(state) => state.output.push(output),
@@ -309,24 +600,26 @@ impl Algor {
                     // If the user is in a lesson, automatically feed in the inputs from the lesson, otherwise send a message to the user asking for input in the terminal pane
                     match &mut self.screen {
                         Screen::LessonView(state) => {
-                            if let Ok(mut computer) = state.computer.lock() {
-                                computer.accumulator = *state
-                                    .lesson
-                                    .body
-                                    .slides
-                                    .get(state.slide)
-                                    .unwrap()
-                                    .inputs
-                                    .items
-                                    .get(state.input)
-                                    .unwrap_or(&0i16);
-
-                                state.input += 1;
-                            }
+                            let input = state
+                                .lesson
+                                .body
+                                .slides
+                                .get(state.slide)
+                                .and_then(|slide| slide.inputs.items.get(state.input))
+                                .copied()
+                                .unwrap_or_default();
+
+                            // Sent through the runtime like a typed input, so it's recorded in the trace
+                            runtime::send(&self.sender, Input::SetInput(input.to_string()));
+
+                            state.input += 1;
+                            self.computers.input_needed = false;
                         }
                         Screen::Sandbox(state) => {
                             self.computers.running = None;
-                            state.output.push("Waiting for input...".into())
+                            state
+                                .output
+                                .push(Output::Message(tr("runtime-waiting-for-input").into()))
                         }
                         _ => unreachable!(),
                     }
@@ -339,6 +632,22 @@ impl Algor {
                 runtime::Event::Continue => {}
             },
 
+            // Look the key up in the keymap and let the screen being shown decide what the shortcut does, handling it as if the user had clicked the button
+            Message::KeyPressed(key, modifiers) => {
+                if let Some(shortcut) = shortcuts::shortcut(&key, modifiers, &self.config.keymap)
+                    && let Some(message) = self.screen.shortcut(shortcut)
+                {
+                    return self.update(Message::Screen(message));
+                }
+            }
+
+            // Pass the modifier keys on to the screens with an editor pane
+            Message::ModifiersChanged(modifiers) => match &mut self.screen {
+                Screen::LessonView(state) => state.assist.modifiers = modifiers,
+                Screen::Sandbox(state) => state.assist.modifiers = modifiers,
+                _ => {}
+            },
+
             // Message received when the user clicks "Browse" to change the directory of where the program searches for lessons
             Message::LessonsDirectoryChanged(mut state, directory) => {
                 // Set the lessons directory to the new directory
@@ -348,53 +657,185 @@ impl Algor {
             }
 
             // Message recieved when the user clicks "Open" to browse a list of lessons
-            Message::SetContent(mut state, path) => {
-                // Ditto LessonsDirectoryChanged comment but with text editor content only unded the condition that the file could be read to disk
-                if let Some(path) = path
-                    && let Ok(text) = fs::read_to_string(path)
+            Message::SetContent(path) => {
+                // Open the file in a new tab of the sandbox screen only unded the condition that the file could be read to disk
+                if let Screen::Sandbox(state) = &mut self.screen
+                    && let Some(path) = path
                 {
-                    state.content = text_editor::Content::with_text(text.as_str());
-                    self.screen = Screen::Sandbox(state);
+                    match fs::read_to_string(&path) {
+                        Ok(text) => {
+                            self.session.add_recent(&path);
+                            state.open(path, &text);
+                            self.save_session();
+                        }
+                        Err(e) => {
+                            state.error =
+                                tr_with("file-read-failed", &[("path", &path), ("error", &e)])
+                        }
+                    }
                 }
             }
 
             // Message recieved when the user clicks "Save" to write to an LMC file
-            Message::SaveContent(state, path) => {
-                // Ditto SetContent comment but with a writing operation instead of a reading one
-                if let Some(path) = path
-                    && let Ok(mut file) = fs::File::create(path)
-                {
-                    if let Err(e) = file.write_all(state.content.text().as_bytes()) {
-                        panic!("Failed to write to file: {e}");
+            Message::SaveContent(id, text, path) => {
+                // Ditto SetContent comment but with a writing operation instead of a reading one, telling the sandbox whether the tab was saved
+                if let Screen::Sandbox(state) = &mut self.screen {
+                    let saved = path.filter(|path| {
+                        match fs::File::create(path)
+                            .and_then(|mut file| file.write_all(text.as_bytes()))
+                        {
+                            Ok(()) => true,
+                            Err(e) => {
+                                state.error =
+                                    tr_with("file-write-failed", &[("path", path), ("error", &e)]);
+                                false
+                            }
+                        }
+                    });
+                    if let Some(path) = &saved {
+                        self.session.add_recent(path);
                     }
+                    state.saved(id, saved);
+                    self.save_session();
                 }
             }
 
-            // No information needs to be relayed once the config is saved but iced requires me to handle it
-            Message::ConfigSaved => {}
+            // The runtime keeps the trace, so ask it to write the trace to the picked file
+            Message::TraceExportPicked(path) => {
+                if let Some(path) = path {
+                    runtime::send(&self.sender, Input::ExportTrace(path));
+                }
+            }
 
-            // Message recieved while the program is running
-            Message::Step(_) => {
-                // Only run when we have the ability for bidirectional communication (cannot be ensured at compile-tim)
-                if let Some(sender) = &mut self.sender
-                    && let Ok(mut sender) = sender.lock()
-                {
-                    // If we are in the Sandox or Lesson View screen send a message to advance the state of the virtual machine, otherwise the user has quit out of the screen so stop running the VM
-                    if matches!(self.screen, Screen::Sandbox(_) | Screen::LessonView(_)) {
-                        sender.try_send(Input::Step).unwrap();
-                    } else {
-                        self.computers.running = None;
-                    }
+            // No information needs to be relayed once the config is saved, but a config that couldn't be written is reported in the terminal
+            Message::ConfigSaved(result) => {
+                if let Err(e) = result {
+                    eprintln!("Failed to save config, {e}");
                 }
             }
+
+            Message::Autosave => self.autosave(),
+
+            // Save the session before exiting, there is only one window so exiting closes it
+            Message::CloseRequested => {
+                self.remember();
+                self.save_session();
+                // Unsaved changes are kept for next time rather than asking about every one of them
+                self.autosave();
+                return iced::exit();
+            }
+
+            // Message recieved while the program is running
+            Message::Step(_) => self.step(),
         }
 
         // Send an empty task if no cases match
         Task::none()
     }
 
+    // Advance the virtual machine once, while it is running or when the user steps through it
+    fn step(&mut self) {
+        // If we are in the Sandox or Lesson View screen send a message to advance the state of the virtual machine (by a whole instruction or one part of the fetch-decode-execute cycle), otherwise the user has quit out of the screen so stop running the VM
+        match &self.screen {
+            Screen::Sandbox(screen::sandbox::State { micro_steps, .. })
+            | Screen::LessonView(screen::lesson_view::State { micro_steps, .. }) => {
+                let input = if *micro_steps {
+                    Input::MicroStep
+                } else {
+                    Input::Step
+                };
+                runtime::send(&self.sender, input);
+            }
+            _ => self.computers.running = None,
+        }
+    }
+
+    // Save the config to the default path (dependant on the operating system)
+    fn save_config(&self) -> Task<Message> {
+        /* Use a task to run an asynchronous function that saves the config, ensuring the program doesn't freeze while the file is being serialised and written to disk
+        If the function wasn't asynchronous, the program could hang and cause a "... is not responding" popup on Windows and potentially macOS and potentially cause a "Hall of Mirrors" effect when other windows pass above this window on other unix-like operating systems due to the framebuffer not updating properly */
+        Task::perform(
+            self.config.clone().save(self.paths.config.clone()),
+            Message::ConfigSaved,
+        )
+    }
+
+    // A new sandbox screen, passing along a "clone" (incrementing atomic reference count) of the sender and computer, sending the editor font size too
+    fn sandbox(&self) -> sandbox::State {
+        sandbox::State::new(
+            self.computers.sandbox.clone(),
+            self.sender.clone(),
+            self.config.editor_font_size,
+            self.config.instruction_set,
+            self.config.format_aliases,
+        )
+    }
+
+    // Ditto sandbox comment but for every lesson in the lessons directory
+    fn lessons(&self) -> io::Result<Vec<lesson_view::State>> {
+        screen::lesson_select::State::get_lessons(
+            self.config.lessons_directory.clone(),
+            self.computers.lesson_viewer.clone(),
+            self.sender.clone(),
+            self.config.editor_font_size,
+            self.config.format_aliases,
+        )
+    }
+
+    // Copy the current screen into the session (the screen the settings screen was opened from if in settings), the menu is never remembered so leaving from it keeps the last session
+    fn remember(&mut self) {
+        match working_screen(&self.screen) {
+            Screen::Sandbox(state) => {
+                self.session.screen = LastScreen::Sandbox;
+                self.session.sandbox = Some(state.session());
+            }
+            Screen::LessonView(state) => {
+                self.session.screen = LastScreen::LessonView {
+                    title: state.lesson.head.title.clone(),
+                    slide: state.slide,
+                }
+            }
+            Screen::LessonSelect(_) => self.session.screen = LastScreen::LessonSelect,
+            Screen::Menu(_) | Screen::Settings(_) => {}
+        }
+    }
+
+    // Write the editor buffers with unsaved changes to the recovery directory, along with any that haven't been restored yet so ignoring the prompt doesn't lose them
+    fn autosave(&mut self) {
+        let mut buffers = match working_screen(&self.screen) {
+            Screen::Sandbox(state) => state.buffers(),
+            Screen::LessonView(state) => state.buffer().into_iter().collect(),
+            _ => Vec::new(),
+        };
+        buffers.extend(self.recovered.iter().cloned());
+
+        if buffers == self.autosaved {
+            return;
+        }
+
+        // Once every buffer has been saved, restored, or thrown away the recovery directory is removed rather than left empty
+        let result = if buffers.is_empty() {
+            recovery::clear(&self.paths.recovery())
+        } else {
+            recovery::save(&self.paths.recovery(), &buffers)
+        };
+
+        // Ditto save_session comment
+        match result {
+            Ok(()) => self.autosaved = buffers,
+            Err(e) => eprintln!("Failed to autosave, {e}"),
+        }
+    }
+
+    // Write the session to disk, failing quietly as losing the session only loses convenience
+    fn save_session(&self) {
+        if let Err(e) = self.session.save(&self.paths.session()) {
+            eprintln!("Failed to save session, {e}");
+        }
+    }
+
     // Set the theme to the theme in the user's config as an iced theme
     fn iced_theme(&self) -> iced::Theme {
-        self.config.theme.clone().into()
+        self.config.theme.to_iced(&self.themes)
     }
 }
@@ -0,0 +1,278 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::shared::locale::{tr, tr_with};
+
+// What an instruction does when the virtual machine runs it, every instruction set maps its mnemonics and machine code onto these
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Operation {
+    Halt,
+    Add,
+    Sub,
+    Store,
+    Load,
+    // Load the value at the address stored in the operand (e.g. LDI X where X DAT 20 loads the value at address 20)
+    LoadIndirect,
+    Branch,
+    BranchZero,
+    BranchPositive,
+    Input,
+    Output,
+    // Output the accumulator as an ASCII character instead of a number
+    OutputCharacter,
+}
+
+impl Operation {
+    // Represents a list of every operation, in the order they're listed in errors
+    pub const ALL: &'static [Operation] = &[
+        Operation::Halt,
+        Operation::Add,
+        Operation::Sub,
+        Operation::Store,
+        Operation::Load,
+        Operation::LoadIndirect,
+        Operation::Branch,
+        Operation::BranchZero,
+        Operation::BranchPositive,
+        Operation::Input,
+        Operation::Output,
+        Operation::OutputCharacter,
+    ];
+
+    // Whether or not the instruction is followed by an address (e.g. ADD X), as opposed to being by itself (e.g. HLT)
+    pub fn has_operand(&self) -> bool {
+        !matches!(
+            self,
+            Operation::Halt | Operation::Input | Operation::Output | Operation::OutputCharacter
+        )
+    }
+
+    // What the instruction does in plain words (in the language being shown), shown in the instruction reference pane and when hovering in an editor
+    pub fn description(&self) -> String {
+        tr(match self {
+            Operation::Halt => "description-halt",
+            Operation::Add => "description-add",
+            Operation::Sub => "description-sub",
+            Operation::Store => "description-store",
+            Operation::Load => "description-load",
+            Operation::LoadIndirect => "description-load-indirect",
+            Operation::Branch => "description-branch",
+            Operation::BranchZero => "description-branch-zero",
+            Operation::BranchPositive => "description-branch-positive",
+            Operation::Input => "description-input",
+            Operation::Output => "description-output",
+            Operation::OutputCharacter => "description-output-character",
+        })
+    }
+
+    // How the instruction changes the registers and memory, where X is the operand and [X] is the value at address X (every instruction also moves PC on by one before it runs)
+    pub fn effect(&self) -> String {
+        match self {
+            Operation::Halt => tr("effect-halt"),
+            Operation::Add => "ACC = ACC + [X]".into(),
+            Operation::Sub => "ACC = ACC - [X]".into(),
+            Operation::Store => "[X] = ACC".into(),
+            Operation::Load => "ACC = [X]".into(),
+            Operation::LoadIndirect => "ACC = [[X]]".into(),
+            Operation::Branch => "PC = X".into(),
+            Operation::BranchZero => tr("effect-branch-zero"),
+            Operation::BranchPositive => tr("effect-branch-positive"),
+            Operation::Input => tr("effect-input"),
+            Operation::Output => tr("effect-output"),
+            Operation::OutputCharacter => tr("effect-output-character"),
+        }
+    }
+
+    // A short program using the instruction
+    pub fn example(&self) -> &'static str {
+        match self {
+            Operation::Halt => "INP\nOUT\nHLT",
+            Operation::Add => "LDA X\nADD ONE\nSTA X",
+            Operation::Sub => "LDA X\nSUB ONE\nSTA X",
+            Operation::Store => "INP\nSTA X\n...\nX DAT",
+            Operation::Load => "LDA X\nOUT\n...\nX DAT 5",
+            Operation::LoadIndirect => "LDI P\nOUT\n...\nP DAT 10",
+            Operation::Branch => "loop INP\nOUT\nBRA loop",
+            Operation::BranchZero => "INP\nBRZ end\nOUT\nend HLT",
+            Operation::BranchPositive => "loop OUT\nSUB ONE\nBRP loop",
+            Operation::Input => "INP\nOUT",
+            Operation::Output => "INP\nOUT",
+            Operation::OutputCharacter => "LDA A\nOTC\n...\nA DAT 65",
+        }
+    }
+}
+
+// A single row of an instruction set table, e.g. INP is written as "INP", runs the Input operation, and is encoded as 901
+#[derive(Debug)]
+pub struct Mnemonic {
+    pub name: &'static str,
+    // Other names the same instruction can be written as (e.g. COB for HLT)
+    pub aliases: &'static [&'static str],
+    pub operation: Operation,
+    // The hundreds digit of the machine code
+    pub opcode: u8,
+    // The tens and ones digits for instructions that have no address (e.g. 01 for INP), None if the operand is an address or is ignored
+    pub operand: Option<u8>,
+}
+
+impl Mnemonic {
+    // How the instruction looks in memory, xx for instructions followed by an address (e.g. 5xx for LDA, 901 for INP)
+    pub fn machine_code(&self) -> String {
+        match self.operand {
+            Some(operand) => format!("{}{operand:02}", self.opcode),
+            None => format!("{}xx", self.opcode),
+        }
+    }
+}
+
+// Shorthand for building the instruction set tables below
+const fn mnemonic(
+    name: &'static str,
+    aliases: &'static [&'static str],
+    operation: Operation,
+    opcode: u8,
+    operand: Option<u8>,
+) -> Mnemonic {
+    Mnemonic {
+        name,
+        aliases,
+        operation,
+        opcode,
+        operand,
+    }
+}
+
+// The instruction set most LMC simulators use, note that opcode 4 is unused
+const CLASSIC: &[Mnemonic] = &[
+    mnemonic("HLT", &["COB"], Operation::Halt, 0, None),
+    mnemonic("ADD", &[], Operation::Add, 1, None),
+    mnemonic("SUB", &[], Operation::Sub, 2, None),
+    mnemonic("STA", &["STO"], Operation::Store, 3, None),
+    mnemonic("LDA", &[], Operation::Load, 5, None),
+    mnemonic("BRA", &[], Operation::Branch, 6, None),
+    mnemonic("BRZ", &[], Operation::BranchZero, 7, None),
+    mnemonic("BRP", &[], Operation::BranchPositive, 8, None),
+    mnemonic("INP", &[], Operation::Input, 9, Some(1)),
+    mnemonic("OUT", &[], Operation::Output, 9, Some(2)),
+];
+
+// The classic instruction set with indirect loads in the unused opcode 4 and character output
+const EXTENDED: &[Mnemonic] = &[
+    mnemonic("HLT", &["COB"], Operation::Halt, 0, None),
+    mnemonic("ADD", &[], Operation::Add, 1, None),
+    mnemonic("SUB", &[], Operation::Sub, 2, None),
+    mnemonic("STA", &["STO"], Operation::Store, 3, None),
+    mnemonic("LDI", &[], Operation::LoadIndirect, 4, None),
+    mnemonic("LDA", &[], Operation::Load, 5, None),
+    mnemonic("BRA", &[], Operation::Branch, 6, None),
+    mnemonic("BRZ", &[], Operation::BranchZero, 7, None),
+    mnemonic("BRP", &[], Operation::BranchPositive, 8, None),
+    mnemonic("INP", &[], Operation::Input, 9, Some(1)),
+    mnemonic("OUT", &[], Operation::Output, 9, Some(2)),
+    mnemonic("OTC", &[], Operation::OutputCharacter, 9, Some(22)),
+];
+
+// Only the mnemonics written in exam papers, without any aliases, so students practise with exactly what they will be tested on
+const EXAM_BOARD: &[Mnemonic] = &[
+    mnemonic("HLT", &[], Operation::Halt, 0, None),
+    mnemonic("ADD", &[], Operation::Add, 1, None),
+    mnemonic("SUB", &[], Operation::Sub, 2, None),
+    mnemonic("STA", &[], Operation::Store, 3, None),
+    mnemonic("LDA", &[], Operation::Load, 5, None),
+    mnemonic("BRA", &[], Operation::Branch, 6, None),
+    mnemonic("BRZ", &[], Operation::BranchZero, 7, None),
+    mnemonic("BRP", &[], Operation::BranchPositive, 8, None),
+    mnemonic("INP", &[], Operation::Input, 9, Some(1)),
+    mnemonic("OUT", &[], Operation::Output, 9, Some(2)),
+];
+
+/* The instruction sets a program can be assembled and run with, lessons pick one with <instruction-set>...</instruction-set>
+Implement Deserialize and Serialize for lessons and config files, deserialising from a string as the XML library expects enums to be elements (e.g. <extended />) otherwise */
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", try_from = "String")]
+pub enum InstructionSet {
+    #[default]
+    Classic,
+    Extended,
+    ExamBoard,
+}
+
+impl InstructionSet {
+    // Represents a list of instruction sets (used in drop-down menus)
+    pub const ALL: &'static [InstructionSet] = &[
+        InstructionSet::Classic,
+        InstructionSet::Extended,
+        InstructionSet::ExamBoard,
+    ];
+
+    // The table of every instruction in the instruction set
+    pub fn mnemonics(&self) -> &'static [Mnemonic] {
+        match self {
+            InstructionSet::Classic => CLASSIC,
+            InstructionSet::Extended => EXTENDED,
+            InstructionSet::ExamBoard => EXAM_BOARD,
+        }
+    }
+
+    // Find an instruction by its name or one of its aliases, ignoring case
+    pub fn mnemonic(&self, name: &str) -> Option<&'static Mnemonic> {
+        self.mnemonics().iter().find(|mnemonic| {
+            mnemonic.name.eq_ignore_ascii_case(name)
+                || mnemonic
+                    .aliases
+                    .iter()
+                    .any(|alias| alias.eq_ignore_ascii_case(name))
+        })
+    }
+
+    // Find how an operation is encoded, None if the instruction set doesn't support the operation
+    pub fn encoding(&self, operation: Operation) -> Option<&'static Mnemonic> {
+        self.mnemonics()
+            .iter()
+            .find(|mnemonic| mnemonic.operation == operation)
+    }
+
+    // Turn machine code back into an operation, None if the opcode (and operand, for instructions without an address) isn't part of the instruction set
+    pub fn decode(&self, opcode: u8, operand: u8) -> Option<Operation> {
+        self.mnemonics()
+            .iter()
+            .find(|mnemonic| {
+                mnemonic.opcode == opcode && mnemonic.operand.is_none_or(|fixed| fixed == operand)
+            })
+            .map(|mnemonic| mnemonic.operation)
+    }
+
+    // Ditto decode comment but giving the mnemonic the machine code would be written as (e.g. for showing memory as LDA 03)
+    pub fn disassemble(&self, opcode: u8, operand: u8) -> Option<&'static Mnemonic> {
+        self.decode(opcode, operand)
+            .and_then(|operation| self.encoding(operation))
+    }
+}
+
+// Turn the name of an instruction set into an instruction set (e.g. "exam-board" into InstructionSet::ExamBoard)
+impl TryFrom<String> for InstructionSet {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        match name.trim() {
+            "classic" => Ok(InstructionSet::Classic),
+            "extended" => Ok(InstructionSet::Extended),
+            "exam-board" => Ok(InstructionSet::ExamBoard),
+            name => Err(tr_with("instruction-set-unknown", &[("name", &name)])),
+        }
+    }
+}
+
+// Allow formatting and printing out of strings (for drop-down menus)
+impl Display for InstructionSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            InstructionSet::Classic => tr("instruction-set-classic"),
+            InstructionSet::Extended => tr("instruction-set-extended"),
+            InstructionSet::ExamBoard => tr("instruction-set-exam-board"),
+        };
+
+        write!(f, "{text}")
+    }
+}
@@ -0,0 +1,253 @@
+use serde::{Deserialize, Serialize};
+
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    sync::{
+        LazyLock,
+        atomic::{AtomicU8, Ordering},
+    },
+};
+
+// The languages the interface and diagnostics can be shown in
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub enum Language {
+    #[default]
+    English,
+    Welsh,
+    Spanish,
+}
+
+impl Language {
+    // Every language in the order they are listed in the settings screen (and their catalogues are kept in)
+    pub const ALL: [Language; 3] = [Language::English, Language::Welsh, Language::Spanish];
+
+    // The message catalogue for the language, see assets/locales
+    fn file(&self) -> &'static str {
+        match self {
+            Language::English => include_str!("../../assets/locales/en.ftl"),
+            Language::Welsh => include_str!("../../assets/locales/cy.ftl"),
+            Language::Spanish => include_str!("../../assets/locales/es.ftl"),
+        }
+    }
+
+    fn catalogue(&self) -> &'static Catalogue {
+        &CATALOGUES[*self as usize]
+    }
+}
+
+// Each language is written in itself, so it can be found by someone who can't read the current one
+impl Display for Language {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Language::English => write!(f, "English"),
+            Language::Welsh => write!(f, "Cymraeg"),
+            Language::Spanish => write!(f, "Español"),
+        }
+    }
+}
+
+// The line of a catalogue that isn't a message, a comment, or part of the message before it
+#[derive(Debug, PartialEq)]
+pub struct CatalogueError(pub usize);
+
+impl Display for CatalogueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Line {} isn't a message or a comment", self.0)
+    }
+}
+
+/* The messages of a language by their id, read from a file laid out like Fluent's, e.g.
+# A comment
+editor-run = Run
+runtime-invalid-instruction = Instruction `{ $code }` is not part of the instruction set
+runtime-error =
+    Encountered an error at runtime...
+    { $error }
+Indented lines carry on the message before them on a new line, and { $name } is replaced with the argument called name */
+#[derive(Debug, Default)]
+pub struct Catalogue(HashMap<String, String>);
+
+impl Catalogue {
+    pub fn parse(file: &str) -> Result<Self, CatalogueError> {
+        let mut messages = HashMap::new();
+        let mut last: Option<String> = None;
+
+        for (i, line) in file.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            // Carry on the last message
+            if line.starts_with(char::is_whitespace) {
+                let value: &mut String = last
+                    .as_ref()
+                    .and_then(|id| messages.get_mut(id))
+                    .ok_or(CatalogueError(i + 1))?;
+
+                if !value.is_empty() {
+                    value.push('\n');
+                }
+                value.push_str(line.trim());
+                continue;
+            }
+
+            let (id, value) = line.split_once('=').ok_or(CatalogueError(i + 1))?;
+            let id = id.trim();
+
+            if id.is_empty()
+                || !id
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+            {
+                return Err(CatalogueError(i + 1));
+            }
+
+            messages.insert(id.to_string(), value.trim().to_string());
+            last = Some(id.to_string());
+        }
+
+        Ok(Self(messages))
+    }
+
+    // The message with the arguments put in, None if the catalogue doesn't have it
+    pub fn format(&self, id: &str, args: &[(&str, &dyn Display)]) -> Option<String> {
+        let message = self.0.get(id)?;
+        let mut formatted = String::with_capacity(message.len());
+        let mut rest = message.as_str();
+
+        while let Some(start) = rest.find('{') {
+            formatted.push_str(&rest[..start]);
+
+            let Some(end) = rest[start..].find('}') else {
+                break;
+            };
+            let placeable = &rest[start + 1..start + end];
+
+            // Anything that isn't a known argument is left as it is
+            match placeable
+                .trim()
+                .strip_prefix('$')
+                .and_then(|name| args.iter().find(|(arg, _)| *arg == name))
+            {
+                Some((_, value)) => formatted.push_str(&value.to_string()),
+                None => formatted.push_str(&rest[start..=start + end]),
+            }
+
+            rest = &rest[start + end + 1..];
+        }
+
+        formatted.push_str(rest);
+        Some(formatted)
+    }
+
+    // The ids of every message, used to check every language has every message
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.0.keys().map(String::as_str)
+    }
+}
+
+// A catalogue with a mistake in it is left empty, so English is used instead of the application not opening
+static CATALOGUES: LazyLock<[Catalogue; 3]> = LazyLock::new(|| {
+    Language::ALL.map(|language| Catalogue::parse(language.file()).unwrap_or_default())
+});
+
+// The language being shown, shared by every thread so errors from the runtime are in the same language as the interface
+static LANGUAGE: AtomicU8 = AtomicU8::new(Language::English as u8);
+
+pub fn set_language(language: Language) {
+    LANGUAGE.store(language as u8, Ordering::Relaxed);
+}
+
+pub fn language() -> Language {
+    Language::ALL
+        .get(LANGUAGE.load(Ordering::Relaxed) as usize)
+        .copied()
+        .unwrap_or_default()
+}
+
+// The message with an id in the current language, in English if it hasn't been translated yet (or the id itself if there is no such message)
+pub fn tr(id: &str) -> String {
+    tr_with(id, &[])
+}
+
+// Ditto tr comment but putting arguments into the message (e.g. tr_with("runtime-invalid-instruction", &[("code", &"410")]))
+pub fn tr_with(id: &str, args: &[(&str, &dyn Display)]) -> String {
+    language()
+        .catalogue()
+        .format(id, args)
+        .or_else(|| Language::English.catalogue().format(id, args))
+        .unwrap_or_else(|| id.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shared::locale::{Catalogue, CatalogueError, Language};
+
+    // 12.1
+    #[test]
+    fn locale_catalogue() {
+        let catalogue = Catalogue::parse(
+            "# Comment\nsaved = Saved { $name }\nerror =\n    Encountered an error...\n    { $error }\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            catalogue.format("saved", &[("name", &"add.lmc")]),
+            Some("Saved add.lmc".into())
+        );
+        assert_eq!(
+            catalogue.format("error", &[("error", &410)]),
+            Some("Encountered an error...\n410".into())
+        );
+        // Arguments that aren't given are left in
+        assert_eq!(
+            catalogue.format("saved", &[]),
+            Some("Saved { $name }".into())
+        );
+        assert_eq!(catalogue.format("missing", &[]), None);
+
+        assert_eq!(
+            Catalogue::parse("    carried on").unwrap_err(),
+            CatalogueError(1)
+        );
+        assert_eq!(
+            Catalogue::parse("ok = Ok\nNot Ok = No").unwrap_err(),
+            CatalogueError(2)
+        );
+    }
+
+    // 12.2
+    #[test]
+    fn locale_languages() {
+        let english = Catalogue::parse(Language::English.file()).unwrap();
+
+        // Every language has every message, using the same arguments
+        for language in Language::ALL {
+            let catalogue = Catalogue::parse(language.file()).unwrap();
+
+            for id in english.ids() {
+                let message = catalogue
+                    .0
+                    .get(id)
+                    .unwrap_or_else(|| panic!("{language} is missing {id}"));
+                let arguments = |message: &str| {
+                    let mut arguments = message
+                        .split("{ $")
+                        .skip(1)
+                        .filter_map(|rest| rest.split_once(" }").map(|(name, _)| name.to_string()))
+                        .collect::<Vec<_>>();
+                    arguments.sort();
+                    arguments
+                };
+
+                assert_eq!(
+                    arguments(message),
+                    arguments(&english.0[id]),
+                    "{language} has different arguments in {id}"
+                );
+            }
+            assert_eq!(catalogue.ids().count(), english.ids().count());
+        }
+    }
+}
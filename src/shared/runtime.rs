@@ -5,49 +5,80 @@ use iced_futures::futures::StreamExt;
 
 use crate::backend::compiler::generator::Location;
 use crate::backend::compiler::{self};
-use crate::shared::vm::Computer;
+use crate::shared::isa::InstructionSet;
+use crate::shared::locale::tr_with;
+use crate::shared::trace::{Trace, TraceFormat};
+use crate::shared::vm::{Cell, Computer};
+use std::fs;
 use std::sync::{Arc, Mutex};
 
 // Events received during runtime execution
 #[derive(Debug)]
 pub enum Input {
-    AssembleClicked(String),
+    AssembleClicked(String, InstructionSet),
     SetInput(String),
     Step,
+    // Run one part of the fetch-decode-execute cycle rather than a whole instruction
+    MicroStep,
+    // Put a value into a register or memory location while the program is paused
+    Poke(Cell, i16),
+    // Write every instruction run since the program was assembled or reset to a CSV or JSON file (picked by the file extension)
+    ExportTrace(String),
     Reset,
 }
 
+// Something shown in the terminal pane, numbers are shown on their own line while characters are joined together into text
+#[derive(PartialEq, Clone, Debug)]
+pub enum Output {
+    // Printed by OUT
+    Number(i16),
+    // Printed by OTC, a newline character ends the line
+    Character(char),
+    // Printed by algor itself rather than the program (e.g. "Waiting for input...")
+    Message(Box<str>),
+}
+
 // Events sent back during runtime execution
 #[derive(Debug)]
 pub enum Event {
     Ready(mpsc::Sender<Input>),
     UpdateState(Arc<Mutex<Computer>>),
     SetError(String),
+    SetWarnings(Vec<String>),
     Continue,
     Halt,
-    Output(Box<str>),
+    Output(Output),
     Input,
 }
 
-// A macro that expands out to an if let block, crashing the thread at runtime if there is an error sending back an event
-macro_rules! send_or_panic {
+// A macro that expands out to an if let block, logging the error if an event can't be sent back (i.e. the interface has closed or isn't keeping up)
+macro_rules! send_or_log {
     ($a:expr,$b:expr) => {{
         if let Err(e) = $a.try_send($b) {
-            panic!(
-                "Encountered a fatal error whilst trying to send event to subscription handler: {e}"
-            );
+            eprintln!("Failed to send an event to the subscription handler, {e}");
         }
     }};
 }
 
+// Send an input to the runtime, logging the error rather than crashing if it can't be sent (i.e. the runtime isn't ready yet or is still busy)
+pub fn send(sender: &Mutex<mpsc::Sender<Input>>, input: Input) {
+    if let Ok(mut sender) = sender.lock()
+        && let Err(e) = sender.try_send(input)
+    {
+        eprintln!("Failed to send an input to the runtime, {e}");
+    }
+}
+
 pub fn run() -> impl Stream<Item = Event> {
     // Create a new channel and take ownership of all variables in the function into the closure (for future maintainability)
     stream::channel(100, |mut output: mpsc::Sender<Event>| async move {
         let (sender, mut receiver) = mpsc::channel(100);
-        send_or_panic!(output, Event::Ready(sender));
+        send_or_log!(output, Event::Ready(sender));
 
         // Create a new computer and wrap it in an atomically references counted mutex
         let computer = Arc::new(Mutex::new(Computer::default()));
+        // Trace table of the program being run
+        let mut trace = Trace::default();
 
         loop {
             let input = receiver.select_next_some().await;
@@ -58,40 +89,87 @@ pub fn run() -> impl Stream<Item = Event> {
             };
 
             match input {
-                Input::AssembleClicked(source) => match compiler::compile(&source) {
-                    // Case for no compiler errors
-                    Ok(code) => {
-                        // Reset the computer and assign machine code to memory
-                        inner_computer.reset();
-                        inner_computer.memory = code;
-                    }
-                    Err(e) => {
-                        // If there is a compiler error, send it back as a string to be displayed in the terminal widget
-                        send_or_panic!(output, Event::SetError(e.to_string()));
+                Input::AssembleClicked(source, instruction_set) => {
+                    match compiler::compile_with(&source, instruction_set) {
+                        // Case for no compiler errors
+                        Ok(code) => {
+                            // Reset the computer and assign machine code to memory, decoding it with the same instruction set it was assembled with
+                            inner_computer.reset();
+                            inner_computer.memory = code;
+                            inner_computer.instruction_set = instruction_set;
+                            trace.clear();
+                        }
+                        Err(e) => {
+                            // If there is a compiler error, send it back as a string to be displayed in the terminal widget
+                            send_or_log!(output, Event::SetError(e.to_string()));
+                        }
                     }
-                },
+
+                    // Send back warnings about likely mistakes, an empty list clears the warnings from the last time the program was assembled
+                    let warnings = compiler::warnings(&source, instruction_set)
+                        .map(|warnings| warnings.iter().map(ToString::to_string).collect())
+                        .unwrap_or_default();
+
+                    send_or_log!(output, Event::SetWarnings(warnings));
+                }
 
                 Input::SetInput(input) => {
                     // Parse and set input asynchronously
                     inner_computer.accumulator = input.parse().unwrap_or_default();
+                    trace.set_input(inner_computer.accumulator);
+                }
+
+                Input::Step | Input::MicroStep => {
+                    trace.before(&inner_computer);
+
+                    let result = if matches!(input, Input::Step) {
+                        inner_computer.step()
+                    } else {
+                        inner_computer.micro_step()
+                    };
+
+                    match result {
+                        // Record and send the event from the virtual machine
+                        Ok(event) => {
+                            trace.after(&inner_computer, &event);
+                            send_or_log!(output, event)
+                        }
+                        // Send the error message from the virtual machine
+                        Err(e) => {
+                            trace.cancel();
+                            send_or_log!(output, Event::SetError(e.to_string()))
+                        }
+                    }
                 }
 
-                Input::Step => match inner_computer.step() {
-                    // Send the event from the virtual machine
-                    Ok(event) => send_or_panic!(output, event),
-                    // Send the error message from the virtual machine
-                    Err(e) => send_or_panic!(output, Event::SetError(e.to_string())),
-                },
+                Input::Poke(cell, value) => {
+                    if let Err(e) = inner_computer.poke(cell, value) {
+                        send_or_log!(output, Event::SetError(e.to_string()));
+                    }
+                }
+
+                Input::ExportTrace(path) => {
+                    if let Err(e) = fs::write(&path, trace.export(TraceFormat::from_path(&path))) {
+                        send_or_log!(
+                            output,
+                            Event::SetError(tr_with(
+                                "runtime-export-failed",
+                                &[("path", &path), ("error", &e)]
+                            ))
+                        );
+                    }
+                }
 
                 Input::Reset => {
-                    // Reset registers and memory
+                    // Reset registers, memory, and the trace
                     inner_computer.reset();
+                    trace.clear();
                     inner_computer.memory = [Location::Data(0); 100];
                 }
             }
 
             // Send back a copy of the updated state of the computer after an input
-            send_or_panic!(output, Event::UpdateState(Arc::clone(&computer)))
+            send_or_log!(output, Event::UpdateState(Arc::clone(&computer)))
         }
     })
 }
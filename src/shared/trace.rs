@@ -0,0 +1,470 @@
+use serde::Serialize;
+
+use crate::backend::compiler::{self, generator::Location};
+use crate::shared::isa::InstructionSet;
+use crate::shared::locale::{tr, tr_with};
+use crate::shared::runtime::{Event, Output};
+use crate::shared::vm::{Computer, Stage};
+
+// A value stored in memory by an instruction (i.e. by STA)
+#[derive(PartialEq, Clone, Debug, Serialize)]
+pub struct Write {
+    pub address: u8,
+    pub value: i16,
+}
+
+// A single row of a trace table, one for each instruction run
+#[derive(PartialEq, Clone, Debug, Serialize)]
+pub struct Step {
+    // Counts up from 1
+    pub cycle: usize,
+    pub program_counter: u8,
+    // The instruction as it would be written (e.g. LDA 03), or the number in memory if it isn't an instruction
+    pub instruction: String,
+    pub accumulator_before: i16,
+    pub accumulator_after: i16,
+    pub memory_writes: Vec<Write>,
+    pub input: Option<i16>,
+    // Numbers are written as numbers and characters as themselves
+    pub output: Option<String>,
+}
+
+// The formats a trace can be exported as
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum TraceFormat {
+    Csv,
+    Json,
+}
+
+impl TraceFormat {
+    // Pick the format from the extension of a file, CSV unless the file ends in .json
+    pub fn from_path(path: &str) -> Self {
+        if path.to_ascii_lowercase().ends_with(".json") {
+            TraceFormat::Json
+        } else {
+            TraceFormat::Csv
+        }
+    }
+}
+
+impl TryFrom<String> for TraceFormat {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "csv" => Ok(TraceFormat::Csv),
+            "json" => Ok(TraceFormat::Json),
+            _ => Err(tr_with("trace-unknown-format", &[("format", &value)])),
+        }
+    }
+}
+
+// Records each instruction run by a computer, a step is only recorded once the whole fetch-decode-execute cycle has run so micro-steps are recorded as one instruction
+#[derive(Debug, Default)]
+pub struct Trace {
+    pub steps: Vec<Step>,
+    // The computer before the instruction being run started
+    start: Option<Computer>,
+    // The input given to the instruction being run, Some(0) until the user has typed it (None if it didn't ask for input)
+    input: Option<i16>,
+    // What the instruction being run printed
+    output: Option<Output>,
+}
+
+impl Trace {
+    // Call before stepping the computer
+    pub fn before(&mut self, computer: &Computer) {
+        if self.start.is_none() {
+            self.start = Some(computer.clone());
+        }
+    }
+
+    // Call after stepping the computer, recording the instruction if it has finished
+    pub fn after(&mut self, computer: &Computer, event: &Event) {
+        match event {
+            Event::Output(output) => self.output = Some(output.clone()),
+            Event::Input => self.input = Some(0),
+            _ => {}
+        }
+
+        // The cycle starts again once the instruction has finished (halting and errors also start it again)
+        if computer.stage != Stage::FetchAddress {
+            return;
+        }
+
+        let Some(start) = self.start.take() else {
+            return;
+        };
+
+        let memory_writes = (0..100)
+            .filter(|address| computer.memory[*address] != start.memory[*address])
+            .map(|address| Write {
+                address: address as u8,
+                value: match computer.memory[address] {
+                    Location::Instruction(instruction) => {
+                        instruction.opcode as i16 * 100 + <i16>::from(instruction.operand)
+                    }
+                    Location::Data(value) => value,
+                },
+            })
+            .collect();
+
+        self.steps.push(Step {
+            cycle: self.steps.len() + 1,
+            program_counter: start.program_counter,
+            instruction: instruction(&start),
+            accumulator_before: start.accumulator,
+            accumulator_after: computer.accumulator,
+            memory_writes,
+            // The value is filled in by set_input once the user has typed it, unless it was typed before the cycle finished (e.g. with micro-steps)
+            input: self.input.take(),
+            output: self.output.take().map(|output| match output {
+                Output::Number(number) => number.to_string(),
+                Output::Character(character) => character.to_string(),
+                Output::Message(message) => message.to_string(),
+            }),
+        });
+    }
+
+    // Forget the instruction being run (i.e. when it fails with an error)
+    pub fn cancel(&mut self) {
+        self.start = None;
+        self.input = None;
+        self.output = None;
+    }
+
+    // Inputs are given after INP has run, so fill in the value and the accumulator afterwards (or keep it until the step is recorded if the cycle hasn't finished yet)
+    pub fn set_input(&mut self, value: i16) {
+        if self.input.is_some() {
+            self.input = Some(value);
+        } else if let Some(step) = self.steps.last_mut()
+            && step.input.is_some()
+        {
+            step.input = Some(value);
+            step.accumulator_after = value;
+        }
+    }
+
+    // Forget every step (i.e. when the program is reset or assembled again)
+    pub fn clear(&mut self) {
+        self.cancel();
+        self.steps.clear();
+    }
+
+    // One row per step with a header row, memory writes are written as address=value separated by spaces
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "cycle,program_counter,instruction,accumulator_before,accumulator_after,memory_writes,input,output\n",
+        );
+
+        for step in &self.steps {
+            let memory_writes = step
+                .memory_writes
+                .iter()
+                .map(|write| format!("{}={}", write.address, write.value))
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            let fields = [
+                step.cycle.to_string(),
+                step.program_counter.to_string(),
+                step.instruction.clone(),
+                step.accumulator_before.to_string(),
+                step.accumulator_after.to_string(),
+                memory_writes,
+                step.input
+                    .map(|input| input.to_string())
+                    .unwrap_or_default(),
+                step.output.clone().unwrap_or_default(),
+            ];
+
+            csv.push_str(&fields.map(|field| csv_field(&field)).join(","));
+            csv.push('\n');
+        }
+
+        csv
+    }
+
+    // A list of steps
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.steps).unwrap_or_default()
+    }
+
+    pub fn export(&self, format: TraceFormat) -> String {
+        match format {
+            TraceFormat::Csv => self.to_csv(),
+            TraceFormat::Json => self.to_json(),
+        }
+    }
+}
+
+// The instruction at the program counter as it would be written
+fn instruction(computer: &Computer) -> String {
+    let location = computer.memory[computer.program_counter as usize];
+
+    let Location::Instruction(instruction) = location else {
+        return location.to_string();
+    };
+
+    match computer
+        .instruction_set
+        .disassemble(instruction.opcode, instruction.operand)
+    {
+        Some(mnemonic) if mnemonic.operation.has_operand() => {
+            format!("{} {:02}", mnemonic.name, instruction.operand)
+        }
+        Some(mnemonic) => mnemonic.name.to_string(),
+        None => location.to_string(),
+    }
+}
+
+// Quote fields with commas, quotes, or newlines in them (e.g. OTC printing a comma)
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+// Assemble and run a program without the application, feeding in inputs in order, and stop after a number of instructions in case the program never halts
+pub fn run(
+    source: &str,
+    instruction_set: InstructionSet,
+    inputs: &[i16],
+    max_steps: usize,
+) -> Result<Trace, String> {
+    run_with(source, instruction_set, inputs, max_steps, |_, _| {})
+}
+
+// Ditto run comment, calling a function with the computer and trace before the program starts and after each instruction (once any input has been given)
+fn run_with(
+    source: &str,
+    instruction_set: InstructionSet,
+    inputs: &[i16],
+    max_steps: usize,
+    mut each: impl FnMut(&Computer, &Trace),
+) -> Result<Trace, String> {
+    let mut computer = Computer::default();
+    computer.memory = compiler::compile_with(source, instruction_set).map_err(|e| e.to_string())?;
+    computer.instruction_set = instruction_set;
+
+    let mut trace = Trace::default();
+    let mut inputs = inputs.iter();
+
+    each(&computer, &trace);
+
+    while trace.steps.len() < max_steps {
+        trace.before(&computer);
+        let event = computer.step().map_err(|e| e.to_string())?;
+        trace.after(&computer, &event);
+
+        if let Event::Input = event {
+            let Some(input) = inputs.next() else {
+                return Err(tr("trace-missing-input"));
+            };
+
+            computer.accumulator = *input;
+            trace.set_input(*input);
+        }
+
+        each(&computer, &trace);
+
+        if let Event::Halt = event {
+            return Ok(trace);
+        }
+    }
+
+    Err(tr_with("trace-max-steps", &[("max_steps", &max_steps)]))
+}
+
+// The state of the computer after an instruction, as written in a trace table
+#[derive(PartialEq, Clone, Debug)]
+pub struct Row {
+    pub program_counter: u8,
+    pub accumulator: i16,
+    pub output: Option<String>,
+    // The values in the memory locations picked for the table, in the same order
+    pub memory: Vec<i16>,
+}
+
+impl Row {
+    fn new(computer: &Computer, output: Option<String>, addresses: &[u8]) -> Self {
+        Self {
+            program_counter: computer.program_counter,
+            accumulator: computer.accumulator,
+            output,
+            memory: addresses
+                .iter()
+                .map(|address| match computer.memory[*address as usize] {
+                    Location::Instruction(instruction) => {
+                        instruction.opcode as i16 * 100 + <i16>::from(instruction.operand)
+                    }
+                    Location::Data(value) => value,
+                })
+                .collect(),
+        }
+    }
+}
+
+// Run a program and build the trace table for it, the first row is the computer before the program starts
+pub fn table(
+    source: &str,
+    instruction_set: InstructionSet,
+    inputs: &[i16],
+    addresses: &[u8],
+    max_steps: usize,
+) -> Result<Vec<Row>, String> {
+    let mut rows = Vec::new();
+
+    run_with(
+        source,
+        instruction_set,
+        inputs,
+        max_steps,
+        |computer, trace| {
+            let output = trace.steps.last().and_then(|step| step.output.clone());
+            rows.push(Row::new(computer, output, addresses));
+        },
+    )?;
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backend::compiler;
+    use crate::shared::isa::InstructionSet;
+    use crate::shared::runtime::Event;
+    use crate::shared::trace::{self, Row, Step, Trace, Write};
+    use crate::shared::vm::Computer;
+
+    // 4.1
+    #[test]
+    fn trace_steps() {
+        let source = r#"INP
+        STA total
+        ADD total
+        OUT
+        HLT
+        total DAT"#;
+
+        let trace = trace::run(source, InstructionSet::Classic, &[4], 100).unwrap();
+
+        assert_eq!(trace.steps.len(), 5);
+        assert_eq!(
+            trace.steps[0],
+            Step {
+                cycle: 1,
+                program_counter: 0,
+                instruction: "INP".into(),
+                accumulator_before: 0,
+                accumulator_after: 4,
+                memory_writes: vec![],
+                input: Some(4),
+                output: None,
+            }
+        );
+        assert_eq!(
+            trace.steps[1].memory_writes,
+            vec![Write {
+                address: 5,
+                value: 4
+            }]
+        );
+        assert_eq!(trace.steps[3].output.as_deref(), Some("8"));
+        assert_eq!(trace.steps[4].instruction, "HLT");
+
+        assert_eq!(trace.to_csv().lines().nth(2), Some("2,1,STA 05,4,4,5=4,,"));
+    }
+
+    // 4.2
+    #[test]
+    fn trace_errors() {
+        assert!(trace::run("INP\nHLT", InstructionSet::Classic, &[], 100).is_err());
+        assert!(trace::run("loop BRA loop", InstructionSet::Classic, &[], 100).is_err());
+    }
+
+    // 4.3
+    #[test]
+    fn trace_table() {
+        let source = r#"INP
+        STA total
+        OUT
+        HLT
+        total DAT 1"#;
+
+        let rows = trace::table(source, InstructionSet::Classic, &[6], &[4], 100).unwrap();
+
+        assert_eq!(
+            rows,
+            vec![
+                Row {
+                    program_counter: 0,
+                    accumulator: 0,
+                    output: None,
+                    memory: vec![1]
+                },
+                Row {
+                    program_counter: 1,
+                    accumulator: 6,
+                    output: None,
+                    memory: vec![1]
+                },
+                Row {
+                    program_counter: 2,
+                    accumulator: 6,
+                    output: None,
+                    memory: vec![6]
+                },
+                Row {
+                    program_counter: 3,
+                    accumulator: 6,
+                    output: Some("6".into()),
+                    memory: vec![6]
+                },
+                // PC stays on the HLT
+                Row {
+                    program_counter: 3,
+                    accumulator: 6,
+                    output: None,
+                    memory: vec![6]
+                },
+            ]
+        );
+    }
+    // 4.4
+    #[test]
+    fn trace_micro_steps() {
+        let mut computer = Computer::default();
+        computer.memory = compiler::compile("INP\nINP\nHLT").unwrap();
+
+        let mut trace = Trace::default();
+        let mut inputs = [3, 7].into_iter();
+
+        // The input is given before the cycle of INP has finished, so it's kept until the step is recorded
+        loop {
+            trace.before(&computer);
+            let event = computer.micro_step().unwrap();
+            trace.after(&computer, &event);
+
+            match event {
+                Event::Input => {
+                    let input = inputs.next().unwrap();
+                    computer.accumulator = input;
+                    trace.set_input(input);
+                }
+                Event::Halt => break,
+                _ => {}
+            }
+        }
+
+        let inputs = trace
+            .steps
+            .iter()
+            .map(|step| (step.input, step.accumulator_after))
+            .collect::<Vec<_>>();
+
+        assert_eq!(inputs, vec![(Some(3), 3), (Some(7), 7), (None, 7)]);
+    }
+}
@@ -1,8 +1,110 @@
 use std::error::Error;
 use std::fmt::Display;
 
-use crate::backend::compiler::generator::Location;
-use crate::shared::runtime::Event;
+use crate::backend::compiler::generator::{InstructionLocation, Location};
+use crate::shared::isa::{InstructionSet, Operation};
+use crate::shared::locale::{tr, tr_with};
+use crate::shared::runtime::{Event, Output};
+
+// The parts of the fetch-decode-execute cycle, each is run by a single call to Computer::micro_step
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum Stage {
+    // PC is copied into MAR
+    #[default]
+    FetchAddress,
+    // The instruction at MAR is copied into MDR, the opcode goes into CIR and the operand into MAR
+    FetchInstruction,
+    // CIR is decoded into an operation
+    Decode,
+    // The operation is carried out
+    Execute,
+    // PC is incremented
+    Increment,
+}
+
+impl Stage {
+    // The part of the cycle after this one, after incrementing PC the cycle starts again
+    pub fn next(&self) -> Self {
+        match self {
+            Stage::FetchAddress => Stage::FetchInstruction,
+            Stage::FetchInstruction => Stage::Decode,
+            Stage::Decode => Stage::Execute,
+            Stage::Execute => Stage::Increment,
+            Stage::Increment => Stage::FetchAddress,
+        }
+    }
+}
+
+// Shown in the state viewer under the registers
+impl Display for Stage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            Stage::FetchAddress => tr("stage-fetch-address"),
+            Stage::FetchInstruction => tr("stage-fetch-instruction"),
+            Stage::Decode => tr("stage-decode"),
+            Stage::Execute => tr("stage-execute"),
+            Stage::Increment => tr("stage-increment"),
+        };
+
+        write!(f, "{text}")
+    }
+}
+
+// The registers of the computer, used for highlighting
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Register {
+    ProgramCounter,
+    Accumulator,
+    CurrentInstruction,
+    MemoryAddress,
+    MemoryData,
+}
+
+impl Register {
+    pub const ALL: [Register; 5] = [
+        Register::ProgramCounter,
+        Register::Accumulator,
+        Register::CurrentInstruction,
+        Register::MemoryAddress,
+        Register::MemoryData,
+    ];
+}
+
+// How many steps a change stays highlighted for, fading a little more on each step
+pub const CHANGE_STEPS: u8 = 4;
+
+// A register or memory location
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Cell {
+    Register(Register),
+    Memory(u8),
+}
+
+// A register or memory location that was changed by a recent step, used to show students what an instruction did
+#[derive(PartialEq, Clone, Debug)]
+pub struct Change {
+    pub cell: Cell,
+    // The value before the change, shown when hovering over the highlight
+    pub previous: String,
+    // How many steps ago the change was made, 0 for the last step
+    pub age: u8,
+}
+
+// The registers and memory location used by the last part of the fetch-decode-execute cycle
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct Highlight {
+    pub registers: Vec<Register>,
+    pub address: Option<u8>,
+}
+
+impl Highlight {
+    fn new(registers: &[Register], address: Option<u8>) -> Self {
+        Self {
+            registers: registers.to_vec(),
+            address,
+        }
+    }
+}
 
 // Represents a little man computer
 #[derive(PartialEq, Clone, Debug)]
@@ -14,6 +116,19 @@ pub struct Computer {
     pub memory_data_register: i16,
     // 100 instruction/data memory locations
     pub memory: [Location; 100],
+    // Decides how machine code is decoded (e.g. 922 is OTC in the extended instruction set but invalid in the classic one)
+    pub instruction_set: InstructionSet,
+    // The next part of the fetch-decode-execute cycle to run
+    pub stage: Stage,
+    // The part of the cycle that was run last and what it used, None when whole instructions are run at once
+    pub last_stage: Option<Stage>,
+    pub highlight: Highlight,
+    // The operation decoded from CIR, kept between the decode and execute parts of the cycle
+    decoded: Option<Operation>,
+    // Whether or not the instruction being run jumped, if it did PC isn't incremented
+    branched: bool,
+    // Registers and memory locations changed by the last few steps, newest last
+    pub changes: Vec<Change>,
 }
 
 // Create a default computer (all values set to zero)
@@ -26,6 +141,13 @@ impl Default for Computer {
             memory_address_register: 0,
             memory_data_register: 0,
             memory: [Location::Data(0); 100],
+            instruction_set: InstructionSet::default(),
+            stage: Stage::default(),
+            last_stage: None,
+            highlight: Highlight::default(),
+            decoded: None,
+            branched: false,
+            changes: Vec::new(),
         }
     }
 }
@@ -37,6 +159,12 @@ pub enum InvalidLocation {
     ExpectedInstruction,
     // Error when running into an operand that is of the Location::Data(T) type and not an instruction (i.e. ADD 20, where the memory address 20 is an instruction)
     ExpectedData,
+    // Error when running into machine code that isn't part of the instruction set (e.g. 400 in the classic instruction set)
+    InvalidInstruction(i16),
+    // Error when an indirect load points outside of memory (i.e. the data is not between 0 and 99)
+    AddressOutOfRange(i16),
+    // Error when a value put into a register or memory location by the user doesn't fit (e.g. 1000 in the accumulator)
+    NumberOutOfRange(i16),
 }
 
 impl Error for InvalidLocation {}
@@ -44,16 +172,21 @@ impl Error for InvalidLocation {}
 impl Display for InvalidLocation {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let text = match self {
-            InvalidLocation::ExpectedInstruction => {
-                "Ran into data memory whilst running code, did you forget to halt?"
+            InvalidLocation::ExpectedInstruction => tr("runtime-expected-instruction"),
+            InvalidLocation::ExpectedData => tr("runtime-expected-data"),
+            InvalidLocation::InvalidInstruction(code) => tr_with(
+                "runtime-invalid-instruction",
+                &[("code", &format!("{code:03}"))],
+            ),
+            InvalidLocation::AddressOutOfRange(address) => {
+                tr_with("address-out-of-range", &[("address", address)])
             }
-            InvalidLocation::ExpectedData => {
-                "Expected an operand pointing to a data location, got an instruction location.\
-                \nDid you use a number instead of an identifier or change the line count of your program?"
+            InvalidLocation::NumberOutOfRange(number) => {
+                tr_with("number-out-of-range", &[("number", number)])
             }
         };
 
-        write!(f, "Encountered an error at runtime...\n{text}")
+        write!(f, "{}", tr_with("runtime-error", &[("error", &text)]))
     }
 }
 
@@ -65,31 +198,238 @@ impl Computer {
         self.current_instruction_register = 0;
         self.memory_address_register = 0;
         self.memory_data_register = 0;
+        self.stage = Stage::default();
+        self.last_stage = None;
+        self.highlight = Highlight::default();
+        self.decoded = None;
+        self.branched = false;
+        self.changes.clear();
     }
 
-    // Compute one instruction and prepare the program counter for the nmext instruction
-    pub fn step(&mut self) -> Result<Event, InvalidLocation> {
-        let Location::Instruction(instruction) = self.memory[self.program_counter as usize] else {
-            if self.memory[self.program_counter as usize] == Location::Data(0) {
-                // Send halt event for empty data memory locations
-                return Ok(Event::Halt);
-            } else {
-                // Return an error if the memory location is data and isn't 0 (i.e. running into data memory)
-                return Err(InvalidLocation::ExpectedInstruction);
+    // The value in a register
+    pub fn register(&self, register: Register) -> i16 {
+        match register {
+            Register::ProgramCounter => self.program_counter as i16,
+            Register::Accumulator => self.accumulator,
+            Register::CurrentInstruction => self.current_instruction_register as i16,
+            Register::MemoryAddress => self.memory_address_register as i16,
+            Register::MemoryData => self.memory_data_register,
+        }
+    }
+
+    // The change to a register or memory location if it was made in the last few steps
+    pub fn change(&self, cell: Cell) -> Option<&Change> {
+        self.changes.iter().find(|change| change.cell == cell)
+    }
+
+    // Put a value into a register or memory location (e.g. a teacher showing what happens if the input was 0), memory locations holding instructions keep holding instructions so self-modifying code can be demonstrated
+    pub fn poke(&mut self, cell: Cell, value: i16) -> Result<(), InvalidLocation> {
+        let address = |value: i16| {
+            u8::try_from(value)
+                .ok()
+                .filter(|address| *address < 100)
+                .ok_or(InvalidLocation::AddressOutOfRange(value))
+        };
+
+        if !(-999..=999).contains(&value) {
+            return Err(InvalidLocation::NumberOutOfRange(value));
+        }
+
+        let previous = match cell {
+            Cell::Register(register) => self.register(register).to_string(),
+            Cell::Memory(address) => self.memory[address as usize].to_string(),
+        };
+
+        match cell {
+            Cell::Register(Register::ProgramCounter) => self.program_counter = address(value)?,
+            Cell::Register(Register::Accumulator) => self.accumulator = value,
+            Cell::Register(Register::CurrentInstruction) => {
+                self.current_instruction_register = u8::try_from(value)
+                    .ok()
+                    .filter(|opcode| *opcode < 10)
+                    .ok_or(InvalidLocation::NumberOutOfRange(value))?
+            }
+            Cell::Register(Register::MemoryAddress) => {
+                self.memory_address_register = address(value)?
+            }
+            Cell::Register(Register::MemoryData) => self.memory_data_register = value,
+            Cell::Memory(address) => {
+                let location = &mut self.memory[address as usize];
+
+                *location = match location {
+                    Location::Instruction(_) if value >= 0 => Location::Instruction(
+                        InstructionLocation::new((value / 100) as u8, (value % 100) as u8),
+                    ),
+                    _ => Location::Data(value),
+                };
+            }
+        }
+
+        // Show the new value as a change like any other
+        self.changes.retain(|change| change.cell != cell);
+        self.changes.push(Change {
+            cell,
+            previous,
+            age: 0,
+        });
+
+        Ok(())
+    }
+
+    // Compare against the registers and memory from before a step, aging older changes and adding new ones
+    fn record_changes(&mut self, registers: [i16; 5], memory: [Location; 100]) {
+        let mut changes = Vec::new();
+
+        for (register, previous) in Register::ALL.into_iter().zip(registers) {
+            if self.register(register) != previous {
+                changes.push(Change {
+                    cell: Cell::Register(register),
+                    previous: previous.to_string(),
+                    age: 0,
+                });
+            }
+        }
+
+        for (address, previous) in memory.iter().enumerate() {
+            if self.memory[address] != *previous {
+                changes.push(Change {
+                    cell: Cell::Memory(address as u8),
+                    previous: previous.to_string(),
+                    age: 0,
+                });
+            }
+        }
+
+        // Changes made again replace the older change, the rest fade until they are removed
+        self.changes.retain_mut(|change| {
+            change.age += 1;
+            change.age < CHANGE_STEPS && !changes.iter().any(|new| new.cell == change.cell)
+        });
+        self.changes.extend(changes);
+    }
+
+    // Run the next part of the fetch-decode-execute cycle
+    pub fn micro_step(&mut self) -> Result<Event, InvalidLocation> {
+        let registers = Register::ALL.map(|register| self.register(register));
+        let memory = self.memory;
+
+        let result = self.run_stage();
+        self.record_changes(registers, memory);
+
+        result
+    }
+
+    // Ditto micro_step comment but without recording changes, as a whole instruction counts as a single step
+    fn run_stage(&mut self) -> Result<Event, InvalidLocation> {
+        let stage = self.stage;
+        self.last_stage = Some(stage);
+
+        let event = match stage {
+            // Copy the address of the next instruction into MAR
+            Stage::FetchAddress => {
+                self.memory_address_register = self.program_counter;
+                self.highlight =
+                    Highlight::new(&[Register::ProgramCounter, Register::MemoryAddress], None);
+                Event::Continue
+            }
+
+            // Copy the instruction at the address in MAR into MDR, then split it into the opcode (CIR) and operand (MAR)
+            Stage::FetchInstruction => {
+                let address = self.memory_address_register;
+
+                let Location::Instruction(instruction) = self.memory[address as usize] else {
+                    self.stage = Stage::FetchAddress;
+
+                    if self.memory[address as usize] == Location::Data(0) {
+                        // Send halt event for empty data memory locations
+                        return Ok(Event::Halt);
+                    } else {
+                        // Return an error if the memory location is data and isn't 0 (i.e. running into data memory)
+                        return Err(InvalidLocation::ExpectedInstruction);
+                    }
+                };
+
+                self.memory_data_register =
+                    instruction.opcode as i16 * 100 + <i16>::from(instruction.operand);
+                self.current_instruction_register = instruction.opcode;
+                self.memory_address_register = instruction.operand;
+                self.highlight = Highlight::new(
+                    &[
+                        Register::MemoryAddress,
+                        Register::MemoryData,
+                        Register::CurrentInstruction,
+                    ],
+                    Some(address),
+                );
+                Event::Continue
+            }
+
+            // Use the instruction set to work out what the instruction does
+            Stage::Decode => {
+                let Some(operation) = self.instruction_set.decode(
+                    self.current_instruction_register,
+                    self.memory_address_register,
+                ) else {
+                    self.stage = Stage::FetchAddress;
+
+                    return Err(InvalidLocation::InvalidInstruction(
+                        self.current_instruction_register as i16 * 100
+                            + <i16>::from(self.memory_address_register),
+                    ));
+                };
+
+                self.decoded = Some(operation);
+                self.highlight = Highlight::new(&[Register::CurrentInstruction], None);
+                Event::Continue
+            }
+
+            Stage::Execute => self.execute()?,
+
+            // Move PC on to the next instruction, unless the instruction was a branch that jumped
+            Stage::Increment => {
+                if !self.branched {
+                    self.program_counter += 1;
+                }
+
+                self.branched = false;
+                self.decoded = None;
+                self.highlight = Highlight::new(&[Register::ProgramCounter], None);
+                Event::Continue
             }
         };
 
-        self.current_instruction_register = instruction.opcode;
-        self.memory_address_register = instruction.operand;
+        // Halting stops before PC is incremented so the program counter stays on the HLT
+        self.stage = match (&event, stage.next()) {
+            (Event::Halt, _) => Stage::FetchAddress,
+            (_, next) => next,
+        };
 
-        match self.current_instruction_register {
+        Ok(event)
+    }
+
+    // The execute part of the fetch-decode-execute cycle
+    fn execute(&mut self) -> Result<Event, InvalidLocation> {
+        let Some(operation) = self.decoded else {
+            return Ok(Event::Continue);
+        };
+
+        match operation {
             // HLT/COB
-            0 => return Ok(Event::Halt),
+            Operation::Halt => {
+                self.decoded = None;
+                self.highlight = Highlight::default();
+                return Ok(Event::Halt);
+            }
 
-            // ADD, SUB, STA/STO, and LDA
-            1..=3 | 5 => {
-                let Location::Data(number) = self.memory[self.memory_address_register as usize]
-                else {
+            // ADD, SUB, STA/STO, LDA, and LDI
+            Operation::Add
+            | Operation::Sub
+            | Operation::Store
+            | Operation::Load
+            | Operation::LoadIndirect => {
+                let address = self.memory_address_register;
+
+                let Location::Data(number) = self.memory[address as usize] else {
                     // Cannot load address that doesn't point to a data location
                     return Err(InvalidLocation::ExpectedData);
                 };
@@ -97,24 +437,63 @@ impl Computer {
                 // Set MDR to value at MAR
                 self.memory_data_register = number;
 
-                match self.current_instruction_register {
+                match operation {
                     // ADD or SUB, if ADD then add the number as normal, if SUB then add the negated number (equivalent to subtraction)
-                    1 | 2 => {
-                        self.accumulator += if self.current_instruction_register == 1 {
+                    Operation::Add | Operation::Sub => {
+                        self.accumulator += if operation == Operation::Add {
                             self.memory_data_register
                         } else {
                             -self.memory_data_register
                         };
+                        self.highlight = Highlight::new(
+                            &[Register::MemoryData, Register::Accumulator],
+                            Some(address),
+                        );
                     }
 
                     // STA, store current value of accumulator
-                    3 => {
-                        self.memory[self.memory_address_register as usize] =
-                            Location::Data(self.accumulator);
+                    Operation::Store => {
+                        self.memory[address as usize] = Location::Data(self.accumulator);
+                        self.highlight = Highlight::new(&[Register::Accumulator], Some(address));
                     }
 
                     // LDA, load accumulator with value in data location
-                    5 => self.accumulator = self.memory_data_register,
+                    Operation::Load => {
+                        self.accumulator = self.memory_data_register;
+                        self.highlight = Highlight::new(
+                            &[Register::MemoryData, Register::Accumulator],
+                            Some(address),
+                        );
+                    }
+
+                    // LDI, the value in the data location is the address of the value to load
+                    Operation::LoadIndirect => {
+                        if !(0..100).contains(&self.memory_data_register) {
+                            return Err(InvalidLocation::AddressOutOfRange(
+                                self.memory_data_register,
+                            ));
+                        }
+
+                        self.memory_address_register = self.memory_data_register as u8;
+
+                        let Location::Data(number) =
+                            self.memory[self.memory_address_register as usize]
+                        else {
+                            // Ditto cannot load address comment
+                            return Err(InvalidLocation::ExpectedData);
+                        };
+
+                        self.memory_data_register = number;
+                        self.accumulator = self.memory_data_register;
+                        self.highlight = Highlight::new(
+                            &[
+                                Register::MemoryAddress,
+                                Register::MemoryData,
+                                Register::Accumulator,
+                            ],
+                            Some(self.memory_address_register),
+                        );
+                    }
 
                     // Unreachable due to outer match statement
                     _ => unreachable!(),
@@ -122,49 +501,105 @@ impl Computer {
             }
 
             // BRA, BRZ, and BRP
-            6..=8 => {
-                let condition = match self.current_instruction_register {
+            Operation::Branch | Operation::BranchZero | Operation::BranchPositive => {
+                let condition = match operation {
                     // BRA, will always succeed
-                    6 => true,
+                    Operation::Branch => true,
                     // BRZ, will only succeed if the accumulator is 0
-                    7 => self.accumulator == 0,
+                    Operation::BranchZero => self.accumulator == 0,
                     // BRP, will only succeed if the accumulator is 0 or greater
-                    8 => self.accumulator >= 0,
+                    Operation::BranchPositive => self.accumulator >= 0,
                     // Unreachable due to outer match statement
                     _ => unreachable!(),
                 };
+
                 if condition {
                     self.program_counter = self.memory_address_register;
-                    return Ok(Event::Continue);
+                    self.branched = true;
+                    self.highlight =
+                        Highlight::new(&[Register::Accumulator, Register::ProgramCounter], None);
+                } else {
+                    self.highlight = Highlight::new(&[Register::Accumulator], None);
                 }
             }
 
-            // INP or OUT
-            9 => {
-                self.program_counter += 1;
+            // INP, send input event
+            Operation::Input => {
+                self.highlight = Highlight::new(&[Register::Accumulator], None);
+                return Ok(Event::Input);
+            }
 
-                if self.memory_address_register == 1 {
-                    // Send input event
-                    return Ok(Event::Input);
-                } else {
-                    // Send output event with accumulator as a string
-                    return Ok(Event::Output(self.accumulator.to_string().into()));
-                }
+            // OUT, send output event with accumulator as a number
+            Operation::Output => {
+                self.highlight = Highlight::new(&[Register::Accumulator], None);
+                return Ok(Event::Output(Output::Number(self.accumulator)));
             }
 
-            _ => unreachable!(),
+            // OTC
+            Operation::OutputCharacter => {
+                self.highlight = Highlight::new(&[Register::Accumulator], None);
+
+                // Send output event with the accumulator as an ASCII character, showing a replacement character for anything outside of the ASCII range
+                let character = u8::try_from(self.accumulator)
+                    .ok()
+                    .filter(u8::is_ascii)
+                    .map_or('�', char::from);
+
+                return Ok(Event::Output(Output::Character(character)));
+            }
         }
 
-        // Bump PC for next step call
-        self.program_counter += 1;
         Ok(Event::Continue)
     }
+
+    // Compute one instruction and prepare the program counter for the next instruction, running every part of the fetch-decode-execute cycle that is left
+    pub fn step(&mut self) -> Result<Event, InvalidLocation> {
+        let registers = Register::ALL.map(|register| self.register(register));
+        let memory = self.memory;
+
+        let result = self.run_stages();
+        self.record_changes(registers, memory);
+
+        // The highlights only make sense when the cycle is shown one part at a time
+        self.last_stage = None;
+        self.highlight = Highlight::default();
+
+        result
+    }
+
+    // Run the parts of the fetch-decode-execute cycle left in the current instruction
+    fn run_stages(&mut self) -> Result<Event, InvalidLocation> {
+        let mut event = Event::Continue;
+
+        loop {
+            match self.run_stage()? {
+                Event::Halt => {
+                    event = Event::Halt;
+                    break;
+                }
+                Event::Continue => {}
+                // Outputs and inputs are sent once PC has been incremented
+                other => event = other,
+            }
+
+            if self.stage == Stage::FetchAddress {
+                break;
+            }
+        }
+
+        Ok(event)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::backend::compiler;
-    use crate::shared::vm::Computer;
+    use crate::backend::compiler::generator::{InstructionLocation, Location};
+    use crate::shared::isa::InstructionSet;
+    use crate::shared::runtime::{Event, Output};
+    use crate::shared::vm::{
+        CHANGE_STEPS, Cell, Change, Computer, InvalidLocation, Register, Stage,
+    };
 
     // 2.1
     #[test]
@@ -185,8 +620,194 @@ mod tests {
             expected.memory = computer.memory.clone();
 
             expected.current_instruction_register = 6;
+            // MDR holds the last instruction fetched (BRA test)
+            expected.memory_data_register = 600;
+
+            // Every register but ACC was changed by the last BRA, replacing the changes made by OUT
+            expected.changes = vec![
+                Change {
+                    cell: Cell::Register(Register::ProgramCounter),
+                    previous: "1".into(),
+                    age: 0,
+                },
+                Change {
+                    cell: Cell::Register(Register::CurrentInstruction),
+                    previous: "9".into(),
+                    age: 0,
+                },
+                Change {
+                    cell: Cell::Register(Register::MemoryAddress),
+                    previous: "2".into(),
+                    age: 0,
+                },
+                Change {
+                    cell: Cell::Register(Register::MemoryData),
+                    previous: "902".into(),
+                    age: 0,
+                },
+            ];
 
             expected
         });
     }
+
+    // 2.2
+    #[test]
+    fn virtual_machine_extended() {
+        let source = r#"LDI pointer
+        OTC
+        HLT
+        pointer DAT 4
+        letter DAT 72"#;
+
+        let mut computer = Computer::default();
+        computer.memory = compiler::compile_with(source, InstructionSet::Extended).unwrap();
+        computer.instruction_set = InstructionSet::Extended;
+
+        computer.step().unwrap();
+        assert_eq!(computer.accumulator, 72);
+
+        assert!(matches!(
+            computer.step(),
+            Ok(Event::Output(Output::Character('H')))
+        ));
+    }
+
+    // 2.3
+    #[test]
+    fn virtual_machine_invalid_instruction() {
+        let mut computer = Computer::default();
+        computer.memory[0] = Location::Instruction(InstructionLocation::new(4, 10));
+
+        assert!(matches!(
+            computer.step(),
+            Err(InvalidLocation::InvalidInstruction(410))
+        ));
+    }
+
+    // 2.4
+    #[test]
+    fn virtual_machine_micro_steps() {
+        let source = r#"LDA number
+        HLT
+        number DAT 7"#;
+
+        let mut computer = Computer::default();
+        computer.memory = compiler::compile(source).unwrap();
+
+        // PC -> MAR
+        computer.micro_step().unwrap();
+        assert_eq!(computer.memory_address_register, 0);
+        assert_eq!(
+            computer.highlight.registers,
+            vec![Register::ProgramCounter, Register::MemoryAddress]
+        );
+
+        // RAM[MAR] -> MDR -> CIR, MAR
+        computer.micro_step().unwrap();
+        assert_eq!(computer.memory_data_register, 502);
+        assert_eq!(computer.current_instruction_register, 5);
+        assert_eq!(computer.memory_address_register, 2);
+        assert_eq!(computer.highlight.address, Some(0));
+
+        // Decode, then execute
+        computer.micro_step().unwrap();
+        computer.micro_step().unwrap();
+        assert_eq!(computer.accumulator, 7);
+        assert_eq!(computer.program_counter, 0);
+        assert_eq!(computer.highlight.address, Some(2));
+
+        // Increment PC, starting the cycle again
+        computer.micro_step().unwrap();
+        assert_eq!(computer.program_counter, 1);
+        assert_eq!(computer.stage, Stage::FetchAddress);
+
+        // Halting leaves PC on the HLT
+        assert!(matches!(computer.step(), Ok(Event::Halt)));
+        assert_eq!(computer.program_counter, 1);
+        assert_eq!(computer.stage, Stage::FetchAddress);
+    }
+
+    // 2.5
+    #[test]
+    fn virtual_machine_changes() {
+        let source = r#"LDA number
+        STA result
+        HLT
+        number DAT 7
+        result DAT"#;
+
+        let mut computer = Computer::default();
+        computer.memory = compiler::compile(source).unwrap();
+
+        computer.step().unwrap();
+        computer.step().unwrap();
+
+        // ACC changed by LDA and the STA target
+        assert_eq!(
+            computer
+                .change(Cell::Register(Register::Accumulator))
+                .map(|change| (change.previous.as_str(), change.age)),
+            Some(("0", 1))
+        );
+        assert_eq!(
+            computer
+                .change(Cell::Memory(4))
+                .map(|change| (change.previous.as_str(), change.age)),
+            Some(("0000", 0))
+        );
+
+        // Changes fade out after a few steps (HLT doesn't change anything)
+        for _ in 0..CHANGE_STEPS {
+            computer.step().unwrap();
+        }
+
+        assert_eq!(computer.change(Cell::Memory(4)), None);
+    }
+
+    // 2.6
+    #[test]
+    fn virtual_machine_poke() {
+        let source = r#"LDA number
+        OUT
+        HLT
+        number DAT 7"#;
+
+        let mut computer = Computer::default();
+        computer.memory = compiler::compile(source).unwrap();
+
+        // Data stays data and instructions stay instructions (OUT -> HLT)
+        computer.poke(Cell::Memory(3), 0).unwrap();
+        computer.poke(Cell::Memory(1), 0).unwrap();
+        assert_eq!(computer.memory[3], Location::Data(0));
+        assert_eq!(
+            computer.memory[1],
+            Location::Instruction(InstructionLocation::new(0, 0))
+        );
+
+        computer.step().unwrap();
+        assert_eq!(computer.accumulator, 0);
+        assert!(matches!(computer.step(), Ok(Event::Halt)));
+
+        computer
+            .poke(Cell::Register(Register::Accumulator), -5)
+            .unwrap();
+        assert_eq!(
+            computer.change(Cell::Register(Register::Accumulator)),
+            Some(&Change {
+                cell: Cell::Register(Register::Accumulator),
+                previous: "0".into(),
+                age: 0
+            })
+        );
+
+        assert!(matches!(
+            computer.poke(Cell::Register(Register::ProgramCounter), 100),
+            Err(InvalidLocation::AddressOutOfRange(100))
+        ));
+        assert!(matches!(
+            computer.poke(Cell::Memory(0), 1000),
+            Err(InvalidLocation::NumberOutOfRange(1000))
+        ));
+    }
 }